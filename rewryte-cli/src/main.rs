@@ -1,5 +1,6 @@
 use {
     codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
         files::SimpleFiles,
         term::{
             self,
@@ -7,12 +8,18 @@ use {
             Config,
         },
     },
-    rewryte_generator::{Format, FormatType},
-    rewryte_parser::{parse, Context},
+    pest::error::InputLocation,
+    rewryte_generator::{
+        check_format, explain, mysql::EnumStorage, write_combined, write_sql, Format, FormatType,
+        GeneratorOptions,
+    },
+    rewryte_parser::{models::Item, parse, Context, Error, Limits, ParseOptions},
     std::{
+        convert::TryFrom,
         fs::{self, File},
-        io::BufWriter,
-        path::PathBuf,
+        io::{self, BufWriter},
+        path::{Path, PathBuf},
+        process,
     },
 };
 
@@ -26,7 +33,7 @@ fn main() -> anyhow::Result<()> {
                 .long("input")
                 .short("i")
                 .value_name("FILE")
-                .help("The required DAL schema file"),
+                .help("The required DAL schema file, or a directory of `.dal` schema files"),
         )
         .arg(
             clap::Arg::with_name("output")
@@ -36,26 +43,333 @@ fn main() -> anyhow::Result<()> {
                 .help("The file to write the transformed schema to")
                 .conflicts_with("check"),
         )
+        .arg(
+            clap::Arg::with_name("output-dir")
+                .long("output-dir")
+                .short("d")
+                .value_name("DIR")
+                .help("The directory to write the transformed schemas to, when the input is a directory")
+                .conflicts_with("check"),
+        )
         .arg(
             clap::Arg::with_name("format")
                 .long("format")
                 .short("f")
                 .value_name("FORMAT")
                 .takes_value(true)
-                .possible_values(&["mysql", "postgres", "sqlite", "rust"])
-                .help("What formats to export to")
+                .multiple(true)
+                .use_delimiter(true)
+                .possible_values(&["mysql", "postgres", "sqlite", "rust", "sea-orm"])
+                .help("What formats to export to, inferred from the output file's extension or the schema's `@database` if not given, more than one needs `--combined`")
                 .conflicts_with("check"),
         )
+        .arg(
+            clap::Arg::with_name("combined")
+                .long("combined")
+                .help("Writes every format into the one output file, each below a `-- === sqlite ===` header")
+                .requires("format")
+                .conflicts_with_all(&["check", "explain"]),
+        )
+        .arg(
+            clap::Arg::with_name("indent")
+                .long("indent")
+                .value_name("WIDTH")
+                .takes_value(true)
+                .default_value("2")
+                .validator(|width| match width.parse::<usize>() {
+                    Ok(1..=8) => Ok(()),
+                    _ => Err(String::from("the indent must be a number from 1 to 8")),
+                })
+                .help("How many spaces to indent columns by, only used by the SQL formats"),
+        )
+        .arg(
+            clap::Arg::with_name("uppercase-keywords")
+                .long("uppercase-keywords")
+                .value_name("BOOL")
+                .takes_value(true)
+                .possible_values(&["true", "false"])
+                .default_value("true")
+                .help("Writes SQL keywords and types in uppercase, only used by the SQL formats"),
+        )
+        .arg(
+            clap::Arg::with_name("enforce-varchar-length")
+                .long("enforce-varchar-length")
+                .help("Adds a `CHECK` on the length of `varchar(n)` columns, only used by SQLite, which ignores the length"),
+        )
+        .arg(
+            clap::Arg::with_name("sort-tables")
+                .long("sort-tables")
+                .help("Creates every table after the tables it references, only used by the SQL formats"),
+        )
+        .arg(
+            clap::Arg::with_name("wrap-in-transaction")
+                .long("wrap-in-transaction")
+                .help("Wraps the schema in a transaction, so a failing statement does not leave it half created, only used by the SQL formats"),
+        )
+        .arg(
+            clap::Arg::with_name("mysql-enums")
+                .long("mysql-enums")
+                .value_name("STORAGE")
+                .takes_value(true)
+                .possible_values(&["native", "check", "integer"])
+                .default_value("native")
+                .help("How MySQL stores enum columns, `check` makes them a `VARCHAR` limited to the variants, which can be altered more easily than an `ENUM`, `integer` stores the variant's index"),
+        )
+        .arg(
+            clap::Arg::with_name("diagnostics-format")
+                .long("diagnostics-format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&["human", "json"])
+                .default_value("human")
+                .help("How to print errors, `json` writes one object per line for editors and other tools"),
+        )
+        .arg(
+            clap::Arg::with_name("color")
+                .long("color")
+                .value_name("WHEN")
+                .takes_value(true)
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+                .help("When to color errors, `auto` only colors them when stderr is a terminal"),
+        )
+        .arg(
+            clap::Arg::with_name("no-color")
+                .long("no-color")
+                .help("Never color errors, the same as `--color never`")
+                .overrides_with("color"),
+        )
         .arg(
             clap::Arg::with_name("check")
                 .long("check")
                 .short("c")
                 .help("Checks the DAL schema file for syntax errors"),
         )
+        .arg(
+            clap::Arg::with_name("quiet")
+                .long("quiet")
+                .short("q")
+                .help("Does not print a summary of the schema when a check succeeds"),
+        )
+        .arg(
+            clap::Arg::with_name("explain")
+                .long("explain")
+                .short("e")
+                .help("Prints the DAL, SQL, and Rust type of every column instead of writing the schema, needs a SQL format")
+                .conflicts_with_all(&["check", "output", "output-dir"]),
+        )
+        .arg(
+            clap::Arg::with_name("strict-types")
+                .long("strict-types")
+                .help("Rejects column types that are neither built-in nor a declared enum, instead of passing them to the database as written"),
+        )
+        .arg(
+            clap::Arg::with_name("deny-warnings")
+                .long("deny-warnings")
+                .short("W")
+                .help("Fails a schema that has any warnings, without writing it"),
+        )
         .get_matches();
 
-    let file = matches.value_of("input").unwrap();
-    let path = PathBuf::from(file);
+    let input = PathBuf::from(matches.value_of("input").unwrap());
+
+    let color = match matches.value_of("color") {
+        _ if matches.is_present("no-color") => ColorChoice::Never,
+        Some("always") => ColorChoice::Always,
+        Some("never") => ColorChoice::Never,
+        // `ColorChoice::Auto` only looks at the environment, not at where the output goes
+        _ if atty::is(atty::Stream::Stderr) => ColorChoice::Auto,
+        _ => ColorChoice::Never,
+    };
+
+    let diagnostics = match matches.value_of("diagnostics-format") {
+        Some("json") => DiagnosticsFormat::Json,
+        _ => DiagnosticsFormat::Human(color),
+    };
+
+    let output = matches.value_of("output").map(PathBuf::from);
+
+    let formats = matches
+        .values_of("format")
+        .map(|formats| {
+            formats
+                .map(FormatType::try_from)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let combined = matches.is_present("combined");
+
+    if formats.len() > 1 && !combined {
+        anyhow::bail!("Writing more than one format needs `--combined`");
+    }
+
+    let options = GeneratorOptions {
+        // Checked by the validator
+        indent: matches.value_of("indent").unwrap().parse()?,
+        uppercase_keywords: matches.value_of("uppercase-keywords") == Some("true"),
+        enforce_varchar_length: matches.is_present("enforce-varchar-length"),
+        wrap_in_transaction: matches.is_present("wrap-in-transaction"),
+        sort_tables: matches.is_present("sort-tables"),
+        enum_storage: match matches.value_of("mysql-enums") {
+            Some("check") => EnumStorage::Check,
+            Some("integer") => EnumStorage::Integer,
+            _ => EnumStorage::Native,
+        },
+    };
+
+    let settings = Settings {
+        format: formats.first().copied(),
+        combined: if combined { &formats } else { &[] },
+        check: matches.is_present("check"),
+        options,
+        explain: matches.is_present("explain"),
+        quiet: matches.is_present("quiet"),
+        deny_warnings: matches.is_present("deny-warnings"),
+        parse_options: ParseOptions {
+            strict_types: matches.is_present("strict-types"),
+        },
+        diagnostics,
+    };
+
+    if input.is_dir() {
+        let output_dir = match matches.value_of("output-dir") {
+            Some(dir) if !settings.check => Some(PathBuf::from(dir)),
+            None if !settings.check && !settings.explain => {
+                anyhow::bail!("You must specify an output directory for the schemas")
+            }
+            _ => None,
+        };
+
+        let mut failed = 0;
+
+        for path in schema_files(&input)? {
+            let output = match &output_dir {
+                Some(dir) => Output::Dir(dir.join(path.strip_prefix(&input)?)),
+                None => Output::None,
+            };
+
+            match run(&path, output, settings) {
+                Ok(true) => {}
+                Ok(false) => failed += 1,
+                Err(err) => {
+                    eprintln!("{}: {:?}", path.display(), err);
+
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed != 0 {
+            anyhow::bail!("{} schema file(s) failed", failed);
+        }
+    } else {
+        let output = match output.as_deref() {
+            Some(output) => Output::File(output),
+            None => Output::None,
+        };
+
+        // The diagnostics have already been printed, so only the exit code is left to report
+        if !run(&input, output, settings)? {
+            process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds every `.dal` file in a directory and its sub directories, sorted so output is stable.
+fn schema_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            files.extend(schema_files(&path)?);
+        } else if path.extension().map(|ext| ext == "dal").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Guesses the format from an output file's extension.
+fn format_from_extension(path: &Path) -> Option<FormatType> {
+    FormatType::from_extension(path.extension()?.to_str()?)
+}
+
+/// Picks the format a schema is written as.
+///
+/// `--format` always wins, then the output file's extension. `.sql` does not say which database it is for, so the
+/// schema's `@database` is used over it.
+fn pick_format(
+    format: Option<FormatType>,
+    output: Option<&Path>,
+    database: Option<FormatType>,
+) -> Option<FormatType> {
+    let extension = output.and_then(format_from_extension);
+
+    format
+        .or_else(|| extension.filter(|typ| !typ.is_sql()))
+        .or(database)
+        .or(extension)
+}
+
+#[derive(Clone, Copy)]
+enum DiagnosticsFormat {
+    Human(ColorChoice),
+    Json,
+}
+
+/// What is done with every schema file, the SQL formats are laid out with `options`.
+#[derive(Clone, Copy)]
+struct Settings<'f> {
+    /// The first format from `--format`, without it one is picked for each schema.
+    format: Option<FormatType>,
+    /// Every format from `--format` with `--combined`, written one after another into the same output.
+    combined: &'f [FormatType],
+    check: bool,
+    options: GeneratorOptions,
+    explain: bool,
+    quiet: bool,
+    deny_warnings: bool,
+    parse_options: ParseOptions,
+    diagnostics: DiagnosticsFormat,
+}
+
+/// Where a schema is written to.
+enum Output<'a> {
+    None,
+    File(&'a Path),
+    /// The schema's path in the output directory, its extension is set once the format is known.
+    Dir(PathBuf),
+}
+
+/// Parses a single schema file, writing it to `output` in the picked format.
+///
+/// With `explain` the column type mappings for the format are printed instead. With `check` the schema is only
+/// checked, and a summary is printed unless `quiet` is set.
+///
+/// Returns `false` if the schema had errors, or warnings with `deny_warnings`, after they have been printed.
+fn run(path: &Path, output: Output<'_>, settings: Settings<'_>) -> anyhow::Result<bool> {
+    let Settings {
+        format,
+        combined,
+        check,
+        options,
+        explain,
+        quiet,
+        deny_warnings,
+        parse_options,
+        diagnostics,
+    } = settings;
+
+    let file = path.display().to_string();
     let file_contents = fs::read_to_string(path)?;
     let contents_str = file_contents.as_str();
 
@@ -63,39 +377,195 @@ fn main() -> anyhow::Result<()> {
 
     let file_id = files.add(file, contents_str);
 
-    let mut ctx = Context::new(file_id);
+    let mut ctx = Context::with_options(file_id, Limits::default(), parse_options);
 
     match parse(&mut ctx, contents_str) {
         Ok(schema) => {
-            if !matches.is_present("check") {
-                let typ = match matches.value_of("format") {
-                    Some("mysql") => FormatType::MySQL,
-                    Some("postgres") => FormatType::PostgreSQL,
-                    Some("rust") => FormatType::Rust,
-                    Some("sqlite") => FormatType::SQLite,
-                    _ => unreachable!(),
+            let typ = if check {
+                None
+            } else {
+                let database = schema
+                    .database
+                    .and_then(|name| FormatType::try_from(name).ok());
+
+                let file = match &output {
+                    Output::File(output) => Some(*output),
+                    Output::None | Output::Dir(_) => None,
                 };
 
-                let output = matches
-                    .value_of("output")
-                    .ok_or_else(|| anyhow::anyhow!("You must specify an output for the schema"))?;
+                match pick_format(format, file, database) {
+                    Some(typ) => Some(typ),
+                    None => anyhow::bail!(
+                        "You must specify a format for the schema, an output file with a known extension, or a `@database` in the schema"
+                    ),
+                }
+            };
+
+            let output = match (typ, output) {
+                (Some(typ), Output::Dir(output)) => Some(output.with_extension(typ.extension())),
+                (_, Output::File(output)) => Some(output.to_path_buf()),
+                (Some(_), Output::None) if !explain => {
+                    anyhow::bail!("You must specify an output for the schema")
+                }
+                _ => None,
+            };
+
+            if let (Some(typ), Some(_)) = (typ, &output) {
+                let written = if combined.is_empty() {
+                    std::slice::from_ref(&typ)
+                } else {
+                    combined
+                };
+
+                for typ in written {
+                    check_format(&mut ctx, contents_str, &schema, *typ);
+                }
+            }
+
+            let warnings = ctx.warnings();
+
+            for diag in warnings {
+                emit_diagnostic(diagnostics, &files, &path.display().to_string(), diag)?;
+            }
+
+            if deny_warnings && !warnings.is_empty() {
+                if let DiagnosticsFormat::Human(_) = diagnostics {
+                    eprintln!(
+                        "error: {} warning(s) in {} and `--deny-warnings` is set",
+                        warnings.len(),
+                        path.display()
+                    );
+                }
+
+                return Ok(false);
+            }
+
+            if let (true, Some(typ)) = (explain, typ) {
+                let stdout = io::stdout();
+
+                explain::write_schema(&schema, typ, &mut stdout.lock())?;
+            } else if let (Some(typ), Some(output)) = (typ, output.as_deref()) {
+                if let Some(parent) = output.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
                 let file = File::create(output)?;
                 let mut writer = BufWriter::new(file);
 
-                schema.fmt(&mut writer, typ)?;
+                if !combined.is_empty() {
+                    write_combined(&schema, &mut writer, combined, options)?;
+                } else if typ.is_sql() {
+                    write_sql(&schema, &mut writer, typ, options)?;
+                } else {
+                    schema.fmt(&mut writer, typ)?;
+                }
+            } else if typ.is_none() && !quiet {
+                let tables = schema
+                    .items
+                    .iter()
+                    .filter(|item| matches!(item, Item::Table(_)))
+                    .count();
+                let enums = schema
+                    .items
+                    .iter()
+                    .filter(|item| matches!(item, Item::Enum(_)))
+                    .count();
+
+                eprintln!(
+                    "ok: parsed {} tables, {} enums in {}",
+                    tables,
+                    enums,
+                    path.display()
+                );
             }
+
+            Ok(true)
         }
         Err(err) => {
-            eprintln!("{:?}", err);
+            match diagnostics {
+                DiagnosticsFormat::Human(_) => {
+                    eprintln!("{:?}", err);
 
-            let writer = StandardStream::stderr(ColorChoice::Always);
-            let config = Config::default();
+                    for diag in ctx.diagnostics() {
+                        emit_diagnostic(diagnostics, &files, &path.display().to_string(), diag)?;
+                    }
+                }
+                DiagnosticsFormat::Json => {
+                    let file = path.display().to_string();
 
-            for diag in ctx.diagnostics() {
-                term::emit(&mut writer.lock(), &config, &files, diag)?;
+                    if ctx.diagnostics().is_empty() {
+                        let diag = match &err {
+                            Error::Parse(err) => {
+                                let range = match err.location {
+                                    InputLocation::Pos(pos) => pos..pos,
+                                    InputLocation::Span((start, end)) => start..end,
+                                };
+
+                                Diagnostic::error()
+                                    .with_message("Syntax error")
+                                    .with_labels(vec![Label::primary(file_id, range)
+                                        .with_message(err.variant.message())])
+                            }
+                            err => Diagnostic::error().with_message(err.to_string()),
+                        };
+
+                        eprintln!("{}", diagnostic_json(&file, &diag));
+                    }
+
+                    for diag in ctx.diagnostics() {
+                        eprintln!("{}", diagnostic_json(&file, diag));
+                    }
+                }
             }
+
+            Ok(false)
+        }
+    }
+}
+
+/// Prints a single diagnostic to stderr in the chosen format.
+fn emit_diagnostic(
+    diagnostics: DiagnosticsFormat,
+    files: &SimpleFiles<String, &str>,
+    file: &str,
+    diag: &Diagnostic<usize>,
+) -> anyhow::Result<()> {
+    match diagnostics {
+        DiagnosticsFormat::Human(color) => {
+            let writer = StandardStream::stderr(color);
+            let config = Config::default();
+
+            term::emit(&mut writer.lock(), &config, files, diag)?;
+        }
+        DiagnosticsFormat::Json => {
+            eprintln!("{}", diagnostic_json(file, diag));
         }
     }
 
     Ok(())
 }
+
+/// Converts a diagnostic into a single JSON object, label ranges are byte offsets into the file.
+fn diagnostic_json(file: &str, diag: &Diagnostic<usize>) -> serde_json::Value {
+    let labels = diag
+        .labels
+        .iter()
+        .map(|label| {
+            serde_json::json!({
+                "style": format!("{:?}", label.style).to_lowercase(),
+                "start": label.range.start,
+                "end": label.range.end,
+                "message": label.message,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "file": file,
+        "severity": format!("{:?}", diag.severity).to_lowercase(),
+        "code": diag.code,
+        "message": diag.message,
+        "labels": labels,
+        "notes": diag.notes,
+    })
+}
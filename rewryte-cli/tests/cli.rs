@@ -0,0 +1,418 @@
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+/// A file in the temporary directory for a test to write to, removed when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> Self {
+        TempFile(std::env::temp_dir().join(format!("rewryte-cli-{}-{}", std::process::id(), name)))
+    }
+
+    fn read(&self) -> String {
+        fs::read_to_string(&self.0).expect("Unable to read output")
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// A directory in the temporary directory for a test to write to, removed along with its contents when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = TempDir(std::env::temp_dir().join(format!("rewryte-cli-{}-{}", std::process::id(), name)));
+
+        fs::create_dir_all(&dir.0).expect("Unable to create directory");
+
+        dir
+    }
+
+    /// Copies a schema from `tests/schemas` into the directory.
+    fn add(&self, name: &str, to: &str) {
+        let to = self.0.join(to);
+
+        fs::create_dir_all(to.parent().unwrap()).expect("Unable to create directory");
+        fs::copy(schema(name), to).expect("Unable to copy schema");
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn schema(name: &str) -> String {
+    format!("{}/tests/schemas/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn rewryte(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rewryte-cli"))
+        .args(args)
+        .output()
+        .expect("Unable to run rewryte")
+}
+
+#[test]
+fn indent_and_uppercase_keywords() {
+    let output = TempFile::new("indent.sql");
+
+    let run = rewryte(&[
+        "--input",
+        &schema("story.dal"),
+        "--output",
+        output.0.to_str().unwrap(),
+        "--format",
+        "postgres",
+        "--indent",
+        "4",
+        "--uppercase-keywords",
+        "true",
+    ]);
+
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+    assert_eq!(
+        "CREATE TABLE Story (
+    Id TEXT NOT NULL,
+    Name TEXT NOT NULL,
+    Parent TEXT,
+    PRIMARY KEY (Id),
+    CONSTRAINT Story_Parent_fkey FOREIGN KEY (Parent) REFERENCES Story(Id) ON UPDATE NO ACTION ON DELETE SET NULL
+);
+",
+        output.read()
+    );
+}
+
+#[test]
+fn lowercase_keywords() {
+    let output = TempFile::new("lowercase.sql");
+
+    let run = rewryte(&[
+        "--input",
+        &schema("story.dal"),
+        "--output",
+        output.0.to_str().unwrap(),
+        "--format",
+        "sqlite",
+        "--uppercase-keywords",
+        "false",
+    ]);
+
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+    assert_eq!(
+        "create table Story (
+  Id text not null,
+  Name text not null,
+  Parent text,
+  primary key (Id),
+  constraint Story_Parent_fkey foreign key (Parent) references Story(Id) on update no action on delete set null
+);
+",
+        output.read()
+    );
+}
+
+#[test]
+fn mysql_enums() {
+    for (storage, column) in [
+        ("native", "State ENUM('working', 'on-hold') NOT NULL"),
+        (
+            "check",
+            "State VARCHAR(7) NOT NULL CHECK (State IN ('working', 'on-hold'))",
+        ),
+        ("integer", "State TINYINT NOT NULL"),
+    ]
+    .iter()
+    {
+        let output = TempFile::new(&format!("mysql-{}.sql", storage));
+
+        let run = rewryte(&[
+            "--input",
+            &schema("task.dal"),
+            "--output",
+            output.0.to_str().unwrap(),
+            "--format",
+            "mysql",
+            "--mysql-enums",
+            storage,
+        ]);
+
+        assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+        assert_eq!(
+            format!(
+                "CREATE TABLE Task (
+  Id INT NOT NULL,
+  {},
+  PRIMARY KEY (Id)
+);
+",
+                column
+            ),
+            output.read()
+        );
+    }
+}
+
+#[test]
+fn indent_out_of_range() {
+    let run = rewryte(&["--input", &schema("story.dal"), "--indent", "0"]);
+
+    assert!(!run.status.success());
+    assert!(
+        String::from_utf8_lossy(&run.stderr).contains("the indent must be a number from 1 to 8"),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn database_annotation_is_default_format() {
+    let output = TempFile::new("counter.sql");
+
+    // `.sql` would be SQLite, the schema's `@database postgres` is picked over it
+    let run = rewryte(&[
+        "--input",
+        &schema("counter.dal"),
+        "--output",
+        output.0.to_str().unwrap(),
+    ]);
+
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+    assert_eq!(
+        "CREATE TABLE Counter (
+  Id SERIAL NOT NULL,
+  PRIMARY KEY (Id)
+);
+",
+        output.read()
+    );
+}
+
+#[test]
+fn color_never() {
+    let run = rewryte(&["--input", &schema("broken.dal"), "--color", "never"]);
+    let stderr = String::from_utf8_lossy(&run.stderr);
+
+    assert!(!run.status.success());
+    assert!(stderr.contains("Invalid column default"), "{}", stderr);
+    assert!(!stderr.contains('\u{1b}'), "{}", stderr);
+}
+
+#[test]
+fn color_always() {
+    let run = rewryte(&["--input", &schema("broken.dal"), "--color", "always"]);
+    let stderr = String::from_utf8_lossy(&run.stderr);
+
+    assert!(!run.status.success());
+    assert!(stderr.contains('\u{1b}'), "{}", stderr);
+}
+
+#[test]
+fn color_auto_without_terminal() {
+    // The output is captured, so it is not a terminal
+    let run = rewryte(&["--input", &schema("broken.dal")]);
+    let stderr = String::from_utf8_lossy(&run.stderr);
+
+    assert!(!run.status.success());
+    assert!(!stderr.contains('\u{1b}'), "{}", stderr);
+}
+
+#[test]
+fn check_summary() {
+    let path = schema("summary.dal");
+
+    let run = rewryte(&["--input", &path, "--check"]);
+
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+    assert_eq!(
+        format!("ok: parsed 1 tables, 1 enums in {}\n", path),
+        String::from_utf8_lossy(&run.stderr)
+    );
+
+    let run = rewryte(&["--input", &path, "--check", "--quiet"]);
+
+    assert!(run.status.success());
+    assert!(run.stderr.is_empty(), "{}", String::from_utf8_lossy(&run.stderr));
+}
+
+#[test]
+fn input_directory() {
+    let input = TempDir::new("dir-input");
+    let output = TempDir::new("dir-output");
+
+    input.add("story.dal", "story.dal");
+    input.add("counter.dal", "nested/counter.dal");
+
+    let run = rewryte(&[
+        "--input",
+        input.0.to_str().unwrap(),
+        "--output-dir",
+        output.0.to_str().unwrap(),
+        "--format",
+        "sqlite",
+    ]);
+
+    assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+    assert!(fs::read_to_string(output.0.join("story.sql"))
+        .expect("Unable to read output")
+        .contains("CREATE TABLE Story"));
+    assert!(fs::read_to_string(output.0.join("nested/counter.sql"))
+        .expect("Unable to read output")
+        .contains("CREATE TABLE Counter"));
+}
+
+#[test]
+fn input_directory_continues_on_error() {
+    let input = TempDir::new("dir-error-input");
+    let output = TempDir::new("dir-error-output");
+
+    // Sorted before the valid schema, so it has to keep going after the error
+    input.add("broken.dal", "a.dal");
+    input.add("story.dal", "b.dal");
+
+    let run = rewryte(&[
+        "--input",
+        input.0.to_str().unwrap(),
+        "--output-dir",
+        output.0.to_str().unwrap(),
+        "--format",
+        "sqlite",
+        "--color",
+        "never",
+    ]);
+    let stderr = String::from_utf8_lossy(&run.stderr);
+
+    assert_eq!(Some(1), run.status.code());
+    assert!(stderr.contains("Invalid column default"), "{}", stderr);
+    assert!(stderr.contains("1 schema file(s) failed"), "{}", stderr);
+    assert!(!output.0.join("a.sql").exists());
+    assert!(output.0.join("b.sql").exists());
+}
+
+#[test]
+fn json_diagnostics() {
+    let path = schema("broken.dal");
+
+    let run = rewryte(&["--input", &path, "--diagnostics-format", "json"]);
+    let stderr = String::from_utf8_lossy(&run.stderr);
+
+    assert!(!run.status.success());
+
+    let lines = stderr.lines().collect::<Vec<_>>();
+
+    assert_eq!(1, lines.len(), "{}", stderr);
+
+    let diag: serde_json::Value = serde_json::from_str(lines[0]).expect("Unable to parse the diagnostic");
+
+    assert_eq!(path, diag["file"]);
+    assert_eq!("error", diag["severity"]);
+    assert_eq!("Invalid column default", diag["message"]);
+
+    let labels = diag["labels"].as_array().expect("The labels are not an array");
+
+    assert_eq!(1, labels.len());
+    assert_eq!("primary", labels[0]["style"]);
+
+    let start = labels[0]["start"].as_u64().unwrap() as usize;
+    let end = labels[0]["end"].as_u64().unwrap() as usize;
+
+    // The range is a byte range of the column's name
+    assert_eq!("Name", &fs::read_to_string(&path).unwrap()[start..end]);
+}
+
+#[test]
+fn malformed_schema_exit_code() {
+    let output = TempFile::new("malformed.sql");
+
+    let check = rewryte(&["--input", &schema("broken.dal"), "--check"]);
+    let generate = rewryte(&[
+        "--input",
+        &schema("broken.dal"),
+        "--output",
+        output.0.to_str().unwrap(),
+    ]);
+
+    assert_eq!(Some(1), check.status.code());
+    assert_eq!(Some(1), generate.status.code());
+    assert!(!output.0.exists());
+
+    let check = rewryte(&["--input", &schema("story.dal"), "--check"]);
+
+    assert_eq!(Some(0), check.status.code());
+}
+
+#[test]
+fn format_from_extension() {
+    let generate = |name: &str, format: Option<&str>| {
+        let input = schema("story.dal");
+        let output = TempFile::new(name);
+
+        let mut args = vec!["--input", &input, "--output", output.0.to_str().unwrap()];
+
+        if let Some(format) = format {
+            args.extend(&["--format", format]);
+        }
+
+        let run = rewryte(&args);
+
+        assert!(run.status.success(), "{}", String::from_utf8_lossy(&run.stderr));
+
+        output.read()
+    };
+
+    assert_eq!(generate("explicit.sql", Some("sqlite")), generate("inferred.sql", None));
+    assert_eq!(generate("explicit.rs", Some("rust")), generate("inferred.rs", None));
+    assert!(generate("inferred.rs", None).contains("pub struct Story"));
+
+    // `--format` wins over the extension
+    assert_eq!(generate("explicit.sql", Some("postgres")), generate("overridden.rs", Some("postgres")));
+}
+
+#[test]
+fn deny_warnings() {
+    let output = TempFile::new("warnings.sql");
+
+    let run = rewryte(&[
+        "--input",
+        &schema("unused.dal"),
+        "--output",
+        output.0.to_str().unwrap(),
+        "--format",
+        "sqlite",
+        "--color",
+        "never",
+    ]);
+    let stderr = String::from_utf8_lossy(&run.stderr);
+
+    assert_eq!(Some(0), run.status.code(), "{}", stderr);
+    assert!(stderr.contains("Unused enum"), "{}", stderr);
+    assert!(output.0.exists());
+
+    fs::remove_file(&output.0).expect("Unable to remove output");
+
+    let run = rewryte(&[
+        "--input",
+        &schema("unused.dal"),
+        "--output",
+        output.0.to_str().unwrap(),
+        "--format",
+        "sqlite",
+        "--color",
+        "never",
+        "--deny-warnings",
+    ]);
+    let stderr = String::from_utf8_lossy(&run.stderr);
+
+    assert_eq!(Some(1), run.status.code(), "{}", stderr);
+    assert!(stderr.contains("`--deny-warnings` is set"), "{}", stderr);
+    assert!(!output.0.exists());
+}
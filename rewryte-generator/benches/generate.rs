@@ -0,0 +1,118 @@
+//! Compares writing a large schema through a byte buffer with writing it straight into a `String`.
+//!
+//! Run with `cargo bench -p rewryte-generator`, add `--features postgres,sqlite` to include the row conversions in
+//! the generated models.
+
+use {
+    rewryte_generator::{rust, Format, FormatType, StringWriter},
+    rewryte_parser::{models::Schema, parse, Context},
+    std::{
+        fmt::Write as _,
+        time::{Duration, Instant},
+    },
+};
+
+const TABLES: usize = 500;
+const ITERATIONS: u32 = 20;
+
+fn schema_source() -> String {
+    let mut source = String::from("enum State { Working Finished }\n\n");
+
+    for i in 0..TABLES {
+        writeln!(
+            source,
+            "table Table{i} {{
+    Id text [primary key]
+    Name text [default: 'Unnamed', unique]
+    Count int!
+    State State
+    Parent text! [ref: Table{i}.Id (delete: cascade)]
+    Created dateTime [default: now()]
+    Updated dateTime [default: now()]
+
+    index Table{i}ByName on (Name)
+}}
+",
+            i = i
+        )
+        .unwrap();
+    }
+
+    source
+}
+
+fn time(mut f: impl FnMut() -> usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let mut len = 0;
+
+    for _ in 0..ITERATIONS {
+        len = f();
+    }
+
+    (start.elapsed() / ITERATIONS, len)
+}
+
+fn report(name: &str, bytes: (Duration, usize), string: (Duration, usize)) {
+    assert_eq!(bytes.1, string.1, "{} wrote different output", name);
+
+    println!(
+        "{:<10} {:>8} bytes  byte buffer {:>10.3?}  string {:>10.3?}",
+        name, string.1, bytes.0, string.0
+    );
+}
+
+fn bench_format(schema: &Schema, typ: FormatType) {
+    let bytes = time(|| {
+        let mut buff = Vec::new();
+
+        schema.fmt(&mut buff, typ).unwrap();
+
+        String::from_utf8(buff).unwrap().len()
+    });
+
+    let string = time(|| {
+        let mut string = String::new();
+
+        schema.fmt(&mut StringWriter(&mut string), typ).unwrap();
+
+        string.len()
+    });
+
+    report(typ.as_str(), bytes, string);
+}
+
+fn main() {
+    let source = schema_source();
+
+    let mut ctx = Context::new(0);
+
+    let schema = parse(&mut ctx, &source).expect("Unable to parse schema");
+
+    for typ in [
+        FormatType::PostgreSQL,
+        FormatType::SQLite,
+        FormatType::SeaOrm,
+    ]
+    .iter()
+    {
+        bench_format(&schema, *typ);
+    }
+
+    let bytes = time(|| {
+        let mut buff = Vec::new();
+
+        rust::write_schema(&schema, &mut buff, Default::default()).unwrap();
+
+        String::from_utf8(buff).unwrap().len()
+    });
+
+    let string = time(|| {
+        let mut string = String::new();
+
+        rust::write_schema(&schema, &mut StringWriter(&mut string), Default::default()).unwrap();
+
+        string.len()
+    });
+
+    report("rust", bytes, string);
+}
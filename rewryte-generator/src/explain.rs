@@ -0,0 +1,193 @@
+use {
+    crate::{mysql, postgresql, rust, sqlite, Error, FormatType},
+    rewryte_parser::models::{Item, Schema, Types},
+    std::{borrow::Cow, io},
+};
+
+/// Writes a table showing how every column's type is mapped, its DAL type, the SQL type for
+/// the chosen format, and the Rust type the models are generated with.
+///
+/// Nullable columns are marked with `!` like in the schema, and columns skipped by the Rust
+/// generator have a `-` as their Rust type.
+pub fn write_schema(
+    schema: &Schema,
+    typ: FormatType,
+    writer: &mut impl io::Write,
+) -> Result<(), Error> {
+    let mut rows = vec![[
+        "column".to_string(),
+        "dal".to_string(),
+        typ.to_string(),
+        "rust".to_string(),
+    ]];
+
+    for item in &schema.items {
+        let decl = match item {
+            Item::Table(decl) => decl,
+            _ => continue,
+        };
+
+        for column in &decl.columns {
+            let rust = if column.skips("rust") {
+                "-".to_string()
+            } else if column.null {
+                format!("Option<{}>", rust::type_name(&column.typ))
+            } else {
+                rust::type_name(&column.typ)
+            };
+
+            rows.push([
+                format!("{}.{}", decl.name, column.name),
+                format!("{}{}", column.typ, if column.null { "!" } else { "" }),
+                sql_type(&column.typ, typ)?.into_owned(),
+                rust,
+            ]);
+        }
+    }
+
+    let mut widths = [0; 4];
+
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    for row in &rows {
+        writeln!(
+            writer,
+            "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn sql_type<'t>(types: &'t Types, typ: FormatType) -> Result<Cow<'t, str>, Error> {
+    match typ {
+        FormatType::MySQL => mysql::type_name(types),
+        FormatType::PostgreSQL => Ok(postgresql::type_name(types)),
+        FormatType::SQLite => sqlite::type_name(types),
+        FormatType::Rust | FormatType::SeaOrm => Err(Error::NotSql(typ)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{explain::write_schema, Error, FormatType},
+        rewryte_parser::models::*,
+    };
+
+    fn column<'a>(name: &'a str, typ: Types<'a>, null: bool) -> Column<'a> {
+        Column {
+            name,
+            doc: None,
+            typ,
+            null,
+            default: ColumnDefault::None,
+            skip: vec![],
+            rust_name: None,
+            collate: None,
+            searchable: false,
+            version: false,
+        }
+    }
+
+    fn schema() -> Schema<'static> {
+        let mut secret = column("Secret", Types::Text, false);
+
+        secret.skip = vec!["rust"];
+
+        Schema {
+            database: None,
+            if_not_exists: false,
+            items: vec![Item::Table(Table {
+                name: "Example",
+                doc: None,
+                not_exists: false,
+                without_rowid: false,
+                columns: vec![
+                    column("Id", Types::Serial, false),
+                    column("Rating", Types::Float, true),
+                    column("Score", Types::Double, false),
+                    column("Rank", Types::SmallInt, false),
+                    secret,
+                ],
+                primary_keys: vec!["Id"],
+                foreign_keys: vec![],
+                unique_keys: vec![],
+                unique_name: None,
+                unique_nulls_not_distinct: false,
+                indexes: vec![],
+            })],
+        }
+    }
+
+    fn explain(typ: FormatType) -> String {
+        let mut buff = Vec::new();
+
+        write_schema(&schema(), typ, &mut buff).expect("Unable to write explanation to buffer");
+
+        String::from_utf8(buff).expect("Unable to convert buff into string")
+    }
+
+    #[test]
+    fn postgresql() {
+        assert_eq!(
+            "column          dal       postgresql        rust
+Example.Id      serial    SERIAL            i32
+Example.Rating  float!    REAL              Option<f32>
+Example.Score   double    DOUBLE PRECISION  f64
+Example.Rank    smallInt  SMALLINT          i16
+Example.Secret  text      TEXT              -
+",
+            explain(FormatType::PostgreSQL)
+        );
+    }
+
+    #[test]
+    fn sqlite() {
+        assert_eq!(
+            "column          dal       sqlite   rust
+Example.Id      serial    INTEGER  i32
+Example.Rating  float!    REAL     Option<f32>
+Example.Score   double    REAL     f64
+Example.Rank    smallInt  INTEGER  i16
+Example.Secret  text      TEXT     -
+",
+            explain(FormatType::SQLite)
+        );
+    }
+
+    #[test]
+    fn rust_is_not_sql() {
+        let mut buff = Vec::new();
+
+        match write_schema(&schema(), FormatType::Rust, &mut buff) {
+            Err(Error::NotSql(FormatType::Rust)) => {}
+            res => panic!("expected a format error, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn mysql() {
+        assert_eq!(
+            "column          dal       mysql     rust
+Example.Id      serial    INT       i32
+Example.Rating  float!    FLOAT     Option<f32>
+Example.Score   double    DOUBLE    f64
+Example.Rank    smallInt  SMALLINT  i16
+Example.Secret  text      TEXT      -
+",
+            explain(FormatType::MySQL)
+        );
+    }
+}
@@ -1,17 +1,33 @@
+pub mod explain;
 pub mod mysql;
 pub mod postgresql;
 pub mod rust;
+pub mod sea_orm;
 pub mod sqlite;
 
 use {
-    rewryte_parser::models::Schema,
-    std::{convert::TryFrom, fmt, io},
+    rewryte_parser::{
+        models::{Item, Schema},
+        str_range, Context,
+    },
+    std::{borrow::Cow, convert::TryFrom, fmt, io},
 };
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("`{0}` is not a valid format type")]
     InvalidFormat(String),
+    #[error("{0} does not support `{1}` columns")]
+    UnsupportedType(&'static str, String),
+    #[error("`{0}` is not a SQL format")]
+    NotSql(FormatType),
+    #[error("The `{0}` and `{1}` options can not be used together")]
+    ConflictingOptions(&'static str, &'static str),
+    #[error("{0}")]
+    Schema(#[from] rewryte_parser::Error),
+    #[cfg(feature = "pretty")]
+    #[error("Unable to parse the generated code for formatting")]
+    Syntax(#[from] syn::Error),
 
     #[error("Format error")]
     Format(#[from] fmt::Error),
@@ -19,11 +35,12 @@ pub enum Error {
     Io(#[from] io::Error),
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FormatType {
     MySQL,
     PostgreSQL,
     Rust,
+    SeaOrm,
     SQLite,
 }
 
@@ -33,14 +50,327 @@ impl<'s> TryFrom<&'s str> for FormatType {
     fn try_from(s: &'s str) -> Result<Self, Self::Error> {
         match s {
             "mysql" => Ok(FormatType::MySQL),
-            "postgresql" => Ok(FormatType::PostgreSQL),
+            "postgres" | "postgresql" => Ok(FormatType::PostgreSQL),
             "rust" => Ok(FormatType::Rust),
+            "sea-orm" | "sea_orm" => Ok(FormatType::SeaOrm),
             "sqlite" => Ok(FormatType::SQLite),
             t => Err(Error::InvalidFormat(t.to_string())),
         }
     }
 }
 
+impl FormatType {
+    /// Guesses the format from a file extension, `sql` is taken to be SQLite.
+    pub fn from_extension(extension: &str) -> Option<FormatType> {
+        match extension {
+            "rs" => Some(FormatType::Rust),
+            "sql" => Some(FormatType::SQLite),
+            _ => None,
+        }
+    }
+
+    /// The canonical name of the format, as accepted by `TryFrom<&str>`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FormatType::MySQL => "mysql",
+            FormatType::PostgreSQL => "postgresql",
+            FormatType::Rust => "rust",
+            FormatType::SeaOrm => "sea-orm",
+            FormatType::SQLite => "sqlite",
+        }
+    }
+
+    /// Checks if the format is a SQL dialect, rather than generated Rust code.
+    pub fn is_sql(self) -> bool {
+        match self {
+            FormatType::MySQL | FormatType::PostgreSQL | FormatType::SQLite => true,
+            FormatType::Rust | FormatType::SeaOrm => false,
+        }
+    }
+
+    /// The file extension for files of this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            FormatType::Rust | FormatType::SeaOrm => "rs",
+            FormatType::MySQL | FormatType::PostgreSQL | FormatType::SQLite => "sql",
+        }
+    }
+}
+
+impl fmt::Display for FormatType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Splits a documentation comment into its lines, with the source indentation removed.
+pub(crate) fn doc_lines(doc: &str) -> impl Iterator<Item = &str> {
+    doc.lines().map(str::trim)
+}
+
+/// How the SQL formats lay out their output, only the SQL formats use it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GeneratorOptions {
+    /// The number of spaces a column or constraint is indented by.
+    pub indent: usize,
+    /// Writes keywords and types in uppercase, `create table` instead of `CREATE TABLE` if not set.
+    pub uppercase_keywords: bool,
+    /// Adds a `CHECK` on the length of SQLite `varchar(n)` columns, SQLite does not enforce the length itself.
+    pub enforce_varchar_length: bool,
+    /// Wraps the schema in a transaction, so a statement failing does not leave it half created.
+    ///
+    /// MySQL commits every `CREATE` on its own, so its schema only gets a comment saying so.
+    pub wrap_in_transaction: bool,
+    /// Creates every table after the tables it references, see [`Schema::topo_sort`].
+    ///
+    /// PostgreSQL and MySQL need a referenced table to exist when the foreign key is created.
+    pub sort_tables: bool,
+    /// How MySQL stores the columns of an enum's type, a native `ENUM` unless set.
+    pub enum_storage: mysql::EnumStorage,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            indent: 2,
+            uppercase_keywords: true,
+            enforce_varchar_length: false,
+            wrap_in_transaction: false,
+            sort_tables: false,
+            enum_storage: mysql::EnumStorage::Native,
+        }
+    }
+}
+
+impl GeneratorOptions {
+    /// A keyword, or several separated by spaces, in the configured case.
+    pub(crate) fn keyword<'k>(&self, keyword: &'k str) -> Cow<'k, str> {
+        if self.uppercase_keywords {
+            Cow::Borrowed(keyword)
+        } else {
+            Cow::Owned(keyword.to_ascii_lowercase())
+        }
+    }
+
+    /// The indentation of a column or constraint.
+    pub(crate) fn indentation(&self) -> String {
+        " ".repeat(self.indent)
+    }
+}
+
+/// Warns about the parts of a schema that do not fit a SQL format, unsupported options are left out of the output.
+///
+/// The warnings are pushed onto the context the schema was parsed with, `input` being the parsed source.
+pub fn check_format(ctx: &mut Context, input: &str, schema: &Schema, typ: FormatType) {
+    if !typ.is_sql() {
+        return;
+    }
+
+    if let Some(database) = schema.database {
+        if FormatType::try_from(database).ok() != Some(typ) {
+            ctx.warning(
+                "Mismatched database",
+                str_range(input, database),
+                format!(
+                    "the schema is written for `{}`, but is being written as `{}`",
+                    database,
+                    typ.as_str()
+                ),
+            );
+        }
+    }
+
+    if typ == FormatType::PostgreSQL {
+        return;
+    }
+
+    for item in &schema.items {
+        let decl = match item {
+            Item::Table(decl) => decl,
+            _ => continue,
+        };
+
+        if decl.unique_nulls_not_distinct {
+            ctx.warning(
+                "Unsupported option",
+                str_range(input, decl.name),
+                format!(
+                    "`nulls not distinct` is only supported by PostgreSQL, table `{}` gets a plain `UNIQUE`",
+                    decl.name
+                ),
+            );
+        }
+
+        if typ == FormatType::MySQL {
+            for foreign_key in decl.foreign_keys.iter().filter(|key| key.deferrable) {
+                ctx.warning(
+                    "Unsupported option",
+                    str_range(input, foreign_key.local[0]),
+                    format!(
+                        "MySQL checks foreign keys immediately, the reference to `{}` is not deferred",
+                        foreign_key.table
+                    ),
+                );
+            }
+
+            for index in decl
+                .indexes
+                .iter()
+                .filter(|index| index.predicate.is_some())
+            {
+                ctx.warning(
+                    "Unsupported option",
+                    str_range(input, index.name),
+                    format!(
+                        "MySQL has no partial indexes, index `{}` covers every row",
+                        index.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Writes a schema in a SQL format, laid out with the given options.
+pub fn write_sql(
+    schema: &Schema,
+    writer: &mut impl io::Write,
+    typ: FormatType,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    if !typ.is_sql() {
+        return Err(Error::NotSql(typ));
+    }
+
+    let sorted;
+
+    let schema = if options.sort_tables {
+        let mut schema = schema.clone();
+
+        schema.topo_sort()?;
+
+        sorted = schema;
+
+        &sorted
+    } else {
+        schema
+    };
+
+    // Everything is written straight to the writer, the transaction around the schema as well
+    if options.wrap_in_transaction {
+        match typ {
+            FormatType::PostgreSQL => write!(writer, "{};\n\n", options.keyword("BEGIN"))?,
+            FormatType::SQLite => write!(writer, "{};\n\n", options.keyword("BEGIN TRANSACTION"))?,
+            FormatType::MySQL => write!(
+                writer,
+                "-- MySQL commits every statement that changes the schema, so it can not be wrapped in a transaction\n\n"
+            )?,
+            FormatType::Rust | FormatType::SeaOrm => unreachable!("only SQL formats get here"),
+        }
+    }
+
+    match typ {
+        FormatType::PostgreSQL => postgresql::write_schema(schema, writer, options)?,
+        FormatType::SQLite => sqlite::write_schema(schema, writer, options)?,
+        FormatType::MySQL => mysql::write_schema(schema, writer, options)?,
+        FormatType::Rust | FormatType::SeaOrm => unreachable!("only SQL formats get here"),
+    }
+
+    if options.wrap_in_transaction && typ != FormatType::MySQL {
+        write!(writer, "\n{};\n", options.keyword("COMMIT"))?;
+    }
+
+    Ok(())
+}
+
+/// Writes a schema in several formats one after another, each below a `-- === sqlite ===` header.
+///
+/// The SQL formats are laid out with `options`, Rust headers are `//` comments.
+pub fn write_combined(
+    schema: &Schema,
+    writer: &mut impl io::Write,
+    formats: &[FormatType],
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    for (i, typ) in formats.iter().enumerate() {
+        if i != 0 {
+            writeln!(writer)?;
+        }
+
+        let comment = if typ.is_sql() { "--" } else { "//" };
+
+        writeln!(writer, "{} === {} ===", comment, typ)?;
+        writeln!(writer)?;
+
+        match typ {
+            FormatType::Rust => rust::write_schema(schema, writer, rust::Options::default())?,
+            typ if typ.is_sql() => write_sql(schema, writer, *typ, options)?,
+            typ => schema.fmt(writer, *typ)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Lets the generators write straight into a `String`.
+///
+/// The generators format everything they write, which `write_fmt` passes on to the string as it is, so the output
+/// is never buffered as bytes that have to be checked for UTF-8 again. Bytes written with `write` are still checked.
+pub struct StringWriter<'s>(pub &'s mut String);
+
+impl io::Write for StringWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let s = std::str::from_utf8(buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        self.0.push_str(s);
+
+        Ok(buf.len())
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
+        // Writing to a string only fails if a `Display` impl does
+        fmt::Write::write_fmt(self.0, args)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes a schema into a new `String`, for callers that do not have a writer of their own.
+pub trait ToSchemaString {
+    /// Writes the schema in a SQL format, laid out with the default options.
+    fn to_sql_string(&self, typ: FormatType) -> Result<String, Error>;
+
+    /// Writes the schema's Rust models.
+    fn to_rust_string(&self, options: rust::Options) -> Result<String, Error>;
+}
+
+impl<'i> ToSchemaString for Schema<'i> {
+    fn to_sql_string(&self, typ: FormatType) -> Result<String, Error> {
+        let mut sql = String::new();
+
+        write_sql(
+            self,
+            &mut StringWriter(&mut sql),
+            typ,
+            GeneratorOptions::default(),
+        )?;
+
+        Ok(sql)
+    }
+
+    fn to_rust_string(&self, options: rust::Options) -> Result<String, Error> {
+        let mut models = String::new();
+
+        rust::write_schema(self, &mut StringWriter(&mut models), options)?;
+
+        Ok(models)
+    }
+}
+
 pub trait Format<W: io::Write> {
     fn fmt(&self, writer: &mut W, typ: FormatType) -> Result<(), Error>;
 }
@@ -48,12 +378,540 @@ pub trait Format<W: io::Write> {
 impl<'i, W: io::Write> Format<W> for Schema<'i> {
     fn fmt(&self, writer: &mut W, typ: FormatType) -> Result<(), Error> {
         match typ {
-            FormatType::MySQL => mysql::write_schema(self, writer)?,
-            FormatType::PostgreSQL => postgresql::write_schema(self, writer)?,
-            FormatType::SQLite => sqlite::write_schema(self, writer)?,
-            FormatType::Rust => todo!(),
+            FormatType::MySQL => mysql::write_schema(self, writer, Default::default())?,
+            FormatType::PostgreSQL => postgresql::write_schema(self, writer, Default::default())?,
+            FormatType::SQLite => sqlite::write_schema(self, writer, Default::default())?,
+            FormatType::SeaOrm => sea_orm::write_schema(self, writer, Default::default())?,
+            FormatType::Rust => rust::write_schema(self, writer, Default::default())?,
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            check_format, rust, write_combined, write_sql, Error, Format, FormatType,
+            GeneratorOptions, StringWriter, ToSchemaString,
+        },
+        rewryte_parser::{parse, Context},
+        std::{convert::TryFrom, io::Write as _},
+    };
+
+    const SCHEMA: &str = "table Story {
+        Id text [primary key]
+        Name text [default: 'NOT NULL']
+        Parent text! [ref: Story.Id (delete: set null)]
+    }";
+
+    fn write(options: GeneratorOptions) -> String {
+        write_schema(SCHEMA, options)
+    }
+
+    fn write_schema(schema: &str, options: GeneratorOptions) -> String {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(&mut ctx, schema).expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_sql(&schema, &mut buff, FormatType::PostgreSQL, options)
+            .expect("Unable to write schema to buffer");
+
+        String::from_utf8(buff).expect("Unable to convert buff into string")
+    }
+
+    fn format_warnings(schema: &str, typ: FormatType) -> Vec<String> {
+        let mut ctx = Context::new(0);
+
+        let parsed = parse(&mut ctx, schema).expect("Unable to parse schema");
+
+        check_format(&mut ctx, schema, &parsed, typ);
+
+        ctx.warnings()
+            .iter()
+            .flat_map(|diag| diag.labels.iter().map(|label| label.message.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn nulls_not_distinct_warning() {
+        const UNIQUE: &str = "table Node {
+            Id text [primary key]
+            Email text! [unique nulls not distinct]
+        }";
+
+        assert_eq!(
+            vec!["`nulls not distinct` is only supported by PostgreSQL, table `Node` gets a plain `UNIQUE`"],
+            format_warnings(UNIQUE, FormatType::SQLite)
+        );
+        assert!(format_warnings(UNIQUE, FormatType::PostgreSQL).is_empty());
+        assert!(format_warnings(UNIQUE, FormatType::Rust).is_empty());
+    }
+
+    #[test]
+    fn mysql_deferrable_warning() {
+        const DEFERRABLE: &str = "table Node {
+            Id text [primary key]
+            ParentId text! [ref: Node.Id (delete: cascade, deferrable)]
+        }";
+
+        assert_eq!(
+            vec!["MySQL checks foreign keys immediately, the reference to `Node` is not deferred"],
+            format_warnings(DEFERRABLE, FormatType::MySQL)
+        );
+        assert!(format_warnings(DEFERRABLE, FormatType::SQLite).is_empty());
+    }
+
+    #[test]
+    fn mysql_partial_index_warning() {
+        const INDEX: &str = "table Story {
+            Id text [primary key]
+            Published dateTime!
+
+            index StoryPublished on (Published) where Published is not null
+        }";
+
+        assert_eq!(
+            vec!["MySQL has no partial indexes, index `StoryPublished` covers every row"],
+            format_warnings(INDEX, FormatType::MySQL)
+        );
+        assert!(format_warnings(INDEX, FormatType::SQLite).is_empty());
+    }
+
+    #[test]
+    fn mismatched_database_warning() {
+        const DATABASE: &str = "@database sqlite
+
+        table Node {
+            Id text [primary key]
+        }";
+
+        assert_eq!(
+            vec!["the schema is written for `sqlite`, but is being written as `postgresql`"],
+            format_warnings(DATABASE, FormatType::PostgreSQL)
+        );
+        assert!(format_warnings(DATABASE, FormatType::SQLite).is_empty());
+    }
+
+    #[test]
+    fn indent() {
+        assert_eq!(
+            "CREATE TABLE Story (
+    Id TEXT NOT NULL,
+    Name TEXT NOT NULL DEFAULT 'NOT NULL',
+    Parent TEXT,
+    PRIMARY KEY (Id),
+    CONSTRAINT Story_Parent_fkey FOREIGN KEY (Parent) REFERENCES Story(Id) ON UPDATE NO ACTION ON DELETE SET NULL
+);
+",
+            write(GeneratorOptions {
+                indent: 4,
+                ..GeneratorOptions::default()
+            })
+        );
+    }
+
+    #[test]
+    fn lowercase_keywords() {
+        assert_eq!(
+            "create table Story (
+  Id text not null,
+  Name text not null default 'NOT NULL',
+  Parent text,
+  primary key (Id),
+  constraint Story_Parent_fkey foreign key (Parent) references Story(Id) on update no action on delete set null
+);
+",
+            write(GeneratorOptions {
+                uppercase_keywords: false,
+                ..GeneratorOptions::default()
+            })
+        );
+    }
+
+    #[test]
+    fn lowercase_keywords_keep_names() {
+        // Only the keywords the generator writes are lowercased, never a name or raw type spelled like one
+        assert_eq!(
+            "create table Event (
+  UUID uuid not null,
+  Search TSVECTOR[] not null,
+  primary key (UUID)
+);
+",
+            write_schema(
+                "table Event { UUID uuid [primary key] Search TSVECTOR[] }",
+                GeneratorOptions {
+                    uppercase_keywords: false,
+                    ..GeneratorOptions::default()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn wrap_in_transaction() {
+        let options = GeneratorOptions {
+            wrap_in_transaction: true,
+            ..GeneratorOptions::default()
+        };
+
+        let postgres = write(options);
+
+        assert!(
+            postgres.starts_with("BEGIN;\n\nCREATE TABLE Story ("),
+            "{}",
+            postgres
+        );
+        assert!(postgres.ends_with(");\n\nCOMMIT;\n"), "{}", postgres);
+
+        let mut ctx = Context::new(0);
+
+        let schema = parse(&mut ctx, SCHEMA).expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_sql(
+            &schema,
+            &mut buff,
+            FormatType::SQLite,
+            GeneratorOptions {
+                uppercase_keywords: false,
+                ..options
+            },
+        )
+        .expect("Unable to write schema to buffer");
+
+        let sqlite = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert!(
+            sqlite.starts_with("begin transaction;\n\ncreate table Story ("),
+            "{}",
+            sqlite
+        );
+        assert!(sqlite.ends_with(");\n\ncommit;\n"), "{}", sqlite);
+
+        let mut buff = Vec::new();
+
+        write_sql(&schema, &mut buff, FormatType::MySQL, options)
+            .expect("Unable to write schema to buffer");
+
+        let mysql = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert!(
+            mysql.starts_with("-- MySQL commits every statement that changes the schema, so it can not be wrapped in a transaction\n\nCREATE TABLE Story ("),
+            "{}",
+            mysql
+        );
+        assert!(mysql.ends_with(");\n"), "{}", mysql);
+        assert!(!mysql.contains("COMMIT"), "{}", mysql);
+    }
+
+    #[test]
+    fn write_sql_to_string() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(&mut ctx, SCHEMA).expect("Unable to parse schema");
+
+        let options = GeneratorOptions {
+            wrap_in_transaction: true,
+            ..GeneratorOptions::default()
+        };
+
+        for typ in [FormatType::PostgreSQL, FormatType::SQLite].iter() {
+            let mut string = String::new();
+
+            write_sql(&schema, &mut StringWriter(&mut string), *typ, options)
+                .expect("Unable to write schema to string");
+
+            let mut buff = Vec::new();
+
+            write_sql(&schema, &mut buff, *typ, options).expect("Unable to write schema to buffer");
+
+            assert_eq!(String::from_utf8(buff).unwrap(), string);
+        }
+    }
+
+    #[test]
+    fn sort_tables() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "table Chapter {
+                Id text [primary key]
+                Story text [ref: Story.Id]
+            }
+
+            table Story { Id text [primary key] }",
+        )
+        .expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_sql(
+            &schema,
+            &mut buff,
+            FormatType::PostgreSQL,
+            GeneratorOptions {
+                sort_tables: true,
+                ..GeneratorOptions::default()
+            },
+        )
+        .expect("Unable to write schema to buffer");
+
+        let sql = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert!(sql.starts_with("CREATE TABLE Story ("), "{}", sql);
+        assert!(sql.contains("CREATE TABLE Chapter ("), "{}", sql);
+
+        let schema = parse(
+            &mut ctx,
+            "table Chapter {
+                Id text [primary key]
+                Next text [ref: Story.Id]
+            }
+
+            table Story {
+                Id text [primary key]
+                First text [ref: Chapter.Id]
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        let err = write_sql(
+            &schema,
+            &mut Vec::new(),
+            FormatType::PostgreSQL,
+            GeneratorOptions {
+                sort_tables: true,
+                ..GeneratorOptions::default()
+            },
+        )
+        .expect_err("A cycle was written");
+
+        assert!(matches!(
+            err,
+            Error::Schema(rewryte_parser::Error::Cycle(_))
+        ));
+    }
+
+    #[test]
+    fn combined() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(&mut ctx, SCHEMA).expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_combined(
+            &schema,
+            &mut buff,
+            &[FormatType::SQLite, FormatType::Rust],
+            GeneratorOptions::default(),
+        )
+        .expect("Unable to write schema to buffer");
+
+        let combined = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let sqlite = combined
+            .find("-- === sqlite ===\n\nCREATE TABLE Story (")
+            .expect("The SQLite section is missing");
+        let rust = combined
+            .find("\n\n// === rust ===\n\n")
+            .expect("The Rust section is missing");
+
+        assert_eq!(0, sqlite);
+        assert!(
+            combined[rust..].contains("pub struct Story"),
+            "{}",
+            combined
+        );
+    }
+
+    #[test]
+    fn string_writer() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "/* Every état of a story */
+            enum State { Working Finished }
+
+            table Story {
+                Id text [primary key]
+                Name text [default: 'Ünnamed']
+                State State
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        for typ in [
+            FormatType::PostgreSQL,
+            FormatType::SQLite,
+            FormatType::SeaOrm,
+        ]
+        .iter()
+        {
+            let mut buff = Vec::new();
+
+            schema
+                .fmt(&mut buff, *typ)
+                .expect("Unable to write schema to buffer");
+
+            let mut string = String::new();
+
+            schema
+                .fmt(&mut StringWriter(&mut string), *typ)
+                .expect("Unable to write schema to string");
+
+            assert_eq!(String::from_utf8(buff).unwrap(), string);
+        }
+
+        let mut buff = Vec::new();
+
+        rust::write_schema(&schema, &mut buff, Default::default())
+            .expect("Unable to write models to buffer");
+
+        let mut string = String::new();
+
+        rust::write_schema(&schema, &mut StringWriter(&mut string), Default::default())
+            .expect("Unable to write models to string");
+
+        assert_eq!(String::from_utf8(buff).unwrap(), string);
+    }
+
+    #[test]
+    fn string_writer_invalid_utf8() {
+        let mut string = String::new();
+
+        assert!(StringWriter(&mut string).write_all(&[0xff, 0xfe]).is_err());
+        assert!(string.is_empty());
+    }
+
+    #[test]
+    fn not_sql() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(&mut ctx, SCHEMA).expect("Unable to parse schema");
+
+        assert!(write_sql(
+            &schema,
+            &mut Vec::new(),
+            FormatType::Rust,
+            Default::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn from_extension() {
+        assert_eq!(Some(FormatType::Rust), FormatType::from_extension("rs"));
+        assert_eq!(Some(FormatType::SQLite), FormatType::from_extension("sql"));
+        assert_eq!(None, FormatType::from_extension("dal"));
+    }
+
+    #[test]
+    fn names() {
+        let types = [
+            FormatType::MySQL,
+            FormatType::PostgreSQL,
+            FormatType::Rust,
+            FormatType::SeaOrm,
+            FormatType::SQLite,
+        ];
+
+        for typ in types.iter() {
+            assert_eq!(typ.as_str(), typ.to_string());
+            assert_eq!(
+                *typ,
+                FormatType::try_from(typ.as_str()).expect("Unable to parse format name")
+            );
+        }
+
+        assert_eq!(
+            FormatType::PostgreSQL,
+            FormatType::try_from("postgres").expect("Unable to parse format name")
+        );
+    }
+
+    #[test]
+    fn to_string() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "enum State { Working Finished }
+
+            table Story {
+                Id text [primary key]
+                State State
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        for typ in [FormatType::PostgreSQL, FormatType::SQLite].iter() {
+            let mut buff = Vec::new();
+
+            write_sql(&schema, &mut buff, *typ, GeneratorOptions::default())
+                .expect("Unable to write schema to buffer");
+
+            assert_eq!(
+                String::from_utf8(buff).unwrap(),
+                schema.to_sql_string(*typ).expect("Unable to write schema")
+            );
+        }
+
+        match schema.to_sql_string(FormatType::SeaOrm) {
+            Err(Error::NotSql(FormatType::SeaOrm)) => {}
+            res => panic!("expected a not SQL error, got {:?}", res),
+        }
+
+        let options = rust::Options {
+            serde: true,
+            ..rust::Options::default()
+        };
+
+        let mut buff = Vec::new();
+
+        rust::write_schema(&schema, &mut buff, options).expect("Unable to write models to buffer");
+
+        assert_eq!(
+            String::from_utf8(buff).unwrap(),
+            schema
+                .to_rust_string(options)
+                .expect("Unable to write models")
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "@timestamps(created = \"created_at\", updated = \"updated_at\")
+
+            table Story {
+                Id text [primary key]
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        assert_eq!(
+            "CREATE TABLE Story (
+  Id TEXT NOT NULL,
+  created_at DATETIME NOT NULL DEFAULT (DATETIME('now', 'utc')),
+  updated_at DATETIME NOT NULL DEFAULT (DATETIME('now', 'utc')),
+  PRIMARY KEY (Id)
+);
+",
+            schema
+                .to_sql_string(FormatType::SQLite)
+                .expect("Unable to write schema")
+        );
+    }
+}
@@ -1,54 +1,757 @@
 use {
-    crate::Error,
-    rewryte_parser::models::{Column, ColumnDefault, Enum, ForeignKey, Item, Schema, Table, Types},
-    std::io,
+    crate::{Error, GeneratorOptions},
+    heck::KebabCase,
+    rewryte_parser::models::{
+        Column, ColumnDefault, Enum, ForeignKey, Index, Item, Schema, Table, Types,
+    },
+    std::{borrow::Cow, io},
 };
 
-pub fn write_schema(schema: &Schema, writer: &mut impl io::Write) -> Result<(), Error> {
+pub fn write_schema(
+    schema: &Schema,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    let enums = schema
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Enum(decl) => Some(decl),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
     for item in &schema.items {
-        write_item(item, writer)?;
+        // Enums are written into the columns using them, so they have nothing of their own
+        if let Item::Table(_) = item {
+            write_item(item, &enums, writer, options)?;
 
-        writeln!(writer)?;
+            writeln!(writer)?;
+        }
     }
 
     Ok(())
 }
 
-pub fn write_item(item: &Item, writer: &mut impl io::Write) -> Result<(), Error> {
+pub fn write_item(
+    item: &Item,
+    enums: &[&Enum],
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
     match &item {
+        Item::Comment(_) => {}
         Item::Enum(decl) => write_enum(decl, writer)?,
-        Item::Table(decl) => write_table(decl, writer)?,
+        Item::Table(decl) => write_table(decl, enums, writer, options)?,
     }
 
     Ok(())
 }
 
+/// MySQL has no enum types, the columns of an enum's type are created as an `ENUM` of its variants instead.
 pub fn write_enum(_decl: &Enum, _writer: &mut impl io::Write) -> Result<(), Error> {
-    todo!()
+    Ok(())
+}
+
+/// How a column of an enum's type is stored.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum EnumStorage {
+    /// A native `ENUM('a', 'b')` column, adding or removing a variant means redefining the column.
+    #[default]
+    Native,
+    /// A `VARCHAR` column limited to the variants with a `CHECK`, which MySQL only enforces from 8.0.16.
+    Check,
+    /// The variant's index in declaration order, in the smallest integer type that fits, see [`enum_int_type`].
+    Integer,
+}
+
+/// The smallest integer type that can store every variant of an enum by its index.
+pub fn enum_int_type(decl: &Enum) -> &'static str {
+    let max = decl.variants.len().saturating_sub(1);
+
+    if max <= i8::MAX as usize {
+        "TINYINT"
+    } else if max <= i16::MAX as usize {
+        "SMALLINT"
+    } else {
+        "INT"
+    }
+}
+
+/// The strings the variants are stored as, the same kebab-case strings the generated models read and write.
+fn variant_values(decl: &Enum) -> Vec<String> {
+    decl.variants
+        .iter()
+        .map(|variant| format!("'{}'", variant.name.to_kebab_case()))
+        .collect()
+}
+
+/// Writes the type of a column storing an enum, `ENUM(...)` or a `VARCHAR` wide enough for the longest variant.
+pub fn write_enum_type(
+    decl: &Enum,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    match options.enum_storage {
+        EnumStorage::Native => write!(
+            writer,
+            "{}({})",
+            options.keyword("ENUM"),
+            variant_values(decl).join(", ")
+        )?,
+        EnumStorage::Check => {
+            let width = decl
+                .variants
+                .iter()
+                .map(|variant| variant.name.to_kebab_case().len())
+                .max()
+                .unwrap_or(1);
+
+            write!(writer, "{}({})", options.keyword("VARCHAR"), width)?;
+        }
+        EnumStorage::Integer => write!(writer, "{}", options.keyword(enum_int_type(decl)))?,
+    }
+
+    Ok(())
+}
+
+/// Writes the `CHECK` limiting a column to the enum's variants, native and integer enums get none.
+pub fn write_enum_check(
+    column: &str,
+    decl: &Enum,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    if options.enum_storage == EnumStorage::Check {
+        write!(
+            writer,
+            " {} ({} {} ({}))",
+            options.keyword("CHECK"),
+            column,
+            options.keyword("IN"),
+            variant_values(decl).join(", ")
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The enum a column's type names, if it is one of `enums`.
+fn find_enum<'e, 'a>(types: &Types, enums: &[&'e Enum<'a>]) -> Option<&'e Enum<'a>> {
+    match types {
+        Types::Raw(raw) => enums.iter().copied().find(|decl| decl.name == *raw),
+        _ => None,
+    }
+}
+
+/// Writes a `CREATE TABLE`, the table's enums are looked up in `enums` and its indexes are declared inside of it.
+pub fn write_table(
+    decl: &Table,
+    enums: &[&Enum],
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(writer, "{}", options.keyword("CREATE TABLE"))?;
+
+    if decl.not_exists {
+        write!(writer, " {}", options.keyword("IF NOT EXISTS"))?;
+    }
+
+    write!(writer, " {} (", decl.name)?;
+
+    writeln!(writer)?;
+
+    for (i, column) in decl.columns.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ",")?;
+
+            writeln!(writer)?;
+        }
+
+        if column.typ == Types::Interval {
+            writeln!(
+                writer,
+                "{}-- Stored as a number of nanoseconds.",
+                options.indentation()
+            )?;
+        }
+
+        write_column(column, enums, writer, options)?;
+    }
+
+    if !decl.primary_keys.is_empty() {
+        write!(writer, ",")?;
+        writeln!(writer)?;
+
+        write!(
+            writer,
+            "{}{} ({})",
+            options.indentation(),
+            options.keyword("PRIMARY KEY"),
+            decl.primary_keys.join(", ")
+        )?;
+    }
+
+    for foreign_key in &decl.foreign_keys {
+        write!(writer, ",")?;
+        writeln!(writer)?;
+
+        write_foreign_key(decl.name, foreign_key, writer, options)?;
+    }
+
+    if !decl.unique_keys.is_empty() {
+        write!(writer, ",")?;
+        writeln!(writer)?;
+
+        write!(
+            writer,
+            "{}{} {} {} ({})",
+            options.indentation(),
+            options.keyword("CONSTRAINT"),
+            decl.unique_constraint_name(),
+            options.keyword("UNIQUE"),
+            decl.unique_keys.join(", ")
+        )?;
+    }
+
+    // Declared in the table, as MySQL has no `CREATE INDEX IF NOT EXISTS` but the table can be guarded
+    for index in &decl.indexes {
+        write!(writer, ",")?;
+        writeln!(writer)?;
+
+        write_index(index, writer, options)?;
+    }
+
+    writeln!(writer)?;
+
+    write!(writer, ")")?;
+
+    if let Some(doc) = decl.doc {
+        write!(writer, " {} = ", options.keyword("COMMENT"))?;
+
+        write_comment(doc, writer)?;
+    }
+
+    write!(writer, ";")?;
+
+    Ok(())
+}
+
+/// Writes an index declared inside of its table, MySQL has no partial indexes so the predicate is left out.
+pub fn write_index(
+    index: &Index,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(
+        writer,
+        "{}{} {} ({})",
+        options.indentation(),
+        options.keyword("INDEX"),
+        index.name,
+        index.columns.join(", ")
+    )?;
+
+    Ok(())
+}
+
+/// Writes a documentation comment as a string, backslashes are escapes in MySQL strings so they are doubled.
+pub fn write_comment(doc: &str, writer: &mut impl io::Write) -> Result<(), Error> {
+    write!(writer, "'")?;
+
+    for (i, line) in crate::doc_lines(doc).enumerate() {
+        if i != 0 {
+            writeln!(writer)?;
+        }
+
+        write!(writer, "{}", line.replace('\\', "\\\\").replace('\'', "''"))?;
+    }
+
+    write!(writer, "'")?;
+
+    Ok(())
 }
 
-pub fn write_table(_decl: &Table, _writer: &mut impl io::Write) -> Result<(), Error> {
-    todo!()
+pub fn write_column(
+    column: &Column,
+    enums: &[&Enum],
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(writer, "{}{} ", options.indentation(), column.name)?;
+
+    write_types(&column.typ, enums, writer, options)?;
+
+    if let Some(collate) = column.collate {
+        write!(writer, " {} {}", options.keyword("COLLATE"), collate)?;
+    }
+
+    if !column.null {
+        write!(writer, " {}", options.keyword("NOT NULL"))?;
+    }
+
+    if column.typ.is_serial() {
+        write!(writer, " {}", options.keyword("AUTO_INCREMENT"))?;
+    }
+
+    write_column_default(&column.default, writer, options)?;
+
+    if let Some(doc) = column.doc {
+        write!(writer, " {} ", options.keyword("COMMENT"))?;
+
+        write_comment(doc, writer)?;
+    }
+
+    if let Some(decl) = find_enum(&column.typ, enums) {
+        write_enum_check(column.name, decl, writer, options)?;
+    }
+
+    Ok(())
 }
 
-pub fn write_column(_column: &Column, _writer: &mut impl io::Write) -> Result<(), Error> {
-    todo!()
+/// Writes a column's type, raw types naming one of `enums` are written as an `ENUM` of its variants.
+pub fn write_types(
+    types: &Types,
+    enums: &[&Enum],
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    match types {
+        Types::Raw(raw) => match find_enum(types, enums) {
+            Some(decl) => write_enum_type(decl, writer, options)?,
+            None => write!(writer, "{}", raw)?,
+        },
+        types => write!(writer, "{}", options.keyword(&type_name(types)?))?,
+    }
+
+    Ok(())
 }
 
-pub fn write_types(_types: &Types, _writer: &mut impl io::Write) -> Result<(), Error> {
-    todo!()
+/// The MySQL type a column type is created as, MySQL has no arrays.
+pub fn type_name<'t>(types: &'t Types) -> Result<Cow<'t, str>, Error> {
+    Ok(Cow::Borrowed(match types {
+        Types::Char => "CHAR(1)",
+        // MySQL needs a length for `VARCHAR`, so one without is as unbounded as `text`
+        Types::Text | Types::Varchar(None) => "TEXT",
+        Types::Varchar(Some(length)) => return Ok(Cow::Owned(format!("VARCHAR({})", length))),
+        Types::SmallInt | Types::SmallSerial => "SMALLINT",
+        Types::MediumInt => "MEDIUMINT",
+        Types::Number | Types::Int | Types::Serial => "INT",
+        // A number of nanoseconds, like SQLite stores it
+        Types::BigInt | Types::BigSerial | Types::Interval => "BIGINT",
+        Types::Float | Types::Real => "FLOAT",
+        Types::Double => "DOUBLE",
+        Types::Numeric => "NUMERIC",
+        Types::Decimal => "DECIMAL",
+        Types::Money => "DECIMAL(19, 4)",
+        Types::DateTime => "DATETIME",
+        Types::Date => "DATE",
+        Types::Time => "TIME",
+        Types::Boolean => "BOOL",
+        // Text like every other database reads and writes it, instead of the more compact `BINARY(16)`
+        Types::Uuid => "CHAR(36)",
+        Types::Raw(raw) => raw,
+        Types::Array(_) => return Err(Error::UnsupportedType("MySQL", types.to_string())),
+    }))
 }
 
+/// Writes a column's default, the functions are wrapped in parentheses as MySQL only takes expressions that way.
 pub fn write_column_default(
-    _column_default: &ColumnDefault,
-    _writer: &mut impl io::Write,
+    column_default: &ColumnDefault,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
 ) -> Result<(), Error> {
-    todo!()
+    if column_default != &ColumnDefault::None {
+        write!(writer, " {}", options.keyword("DEFAULT"))?;
+
+        match column_default {
+            ColumnDefault::Now => {
+                write!(writer, " ({})", options.keyword("UTC_TIMESTAMP()"))?;
+            }
+            ColumnDefault::CurrentDate => {
+                write!(writer, " ({})", options.keyword("CURRENT_DATE"))?;
+            }
+            ColumnDefault::CurrentTime => {
+                write!(writer, " ({})", options.keyword("CURRENT_TIME"))?;
+            }
+            ColumnDefault::Null => {
+                write!(writer, " {}", options.keyword("NULL"))?;
+            }
+            ColumnDefault::Uuid => {
+                write!(writer, " ({})", options.keyword("UUID()"))?;
+            }
+            ColumnDefault::Bool(value) => {
+                write!(
+                    writer,
+                    " {}",
+                    options.keyword(if *value { "TRUE" } else { "FALSE" })
+                )?;
+            }
+            ColumnDefault::Raw(raw) => {
+                write!(writer, " {}", raw)?;
+            }
+            ColumnDefault::None => unreachable!(),
+        }
+    }
+
+    Ok(())
 }
 
+/// Writes a foreign key, MySQL checks them immediately so `deferrable` is left out.
 pub fn write_foreign_key(
-    _foreign_key: &ForeignKey,
-    _writer: &mut impl io::Write,
+    table: &str,
+    foreign_key: &ForeignKey,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
 ) -> Result<(), Error> {
-    todo!()
+    write!(
+        writer,
+        "{}{} {} {} ({}) {} {}({}) {} {} {} {}",
+        options.indentation(),
+        options.keyword("CONSTRAINT"),
+        foreign_key.constraint_name(table),
+        options.keyword("FOREIGN KEY"),
+        foreign_key.local.join(", "),
+        options.keyword("REFERENCES"),
+        foreign_key.table,
+        foreign_key.foreign.join(", "),
+        options.keyword("ON UPDATE"),
+        options.keyword(&foreign_key.update.to_string()),
+        options.keyword("ON DELETE"),
+        options.keyword(&foreign_key.delete.to_string()),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{
+            mysql::{enum_int_type, write_schema, EnumStorage},
+            GeneratorOptions,
+        },
+        rewryte_parser::{models::*, parse, Context},
+    };
+
+    fn write(schema: &str, options: GeneratorOptions) -> String {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(&mut ctx, schema).expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_schema(&schema, &mut buff, options).expect("Unable to write schema to buffer");
+
+        String::from_utf8(buff).expect("Unable to convert buff into string")
+    }
+
+    #[test]
+    fn simple() {
+        assert_eq!(
+            "CREATE TABLE IF NOT EXISTS Story (
+  Id INT NOT NULL AUTO_INCREMENT,
+  Name VARCHAR(64) COLLATE utf8mb4_bin NOT NULL COMMENT 'The story''s title',
+  Summary TEXT,
+  Rating FLOAT NOT NULL DEFAULT 0,
+  Updated DATETIME NOT NULL DEFAULT (UTC_TIMESTAMP()),
+  PRIMARY KEY (Id)
+) COMMENT = 'Stories, with a \\\\ in the comment';
+",
+            write(
+                "/* Stories, with a \\ in the comment */
+                table Story? {
+                    Id serial [primary key]
+                    /* The story's title */
+                    Name varchar(64) [collate: utf8mb4_bin]
+                    Summary text!
+                    Rating float [default: 0]
+                    Updated dateTime [default: now()]
+                }",
+                GeneratorOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn constraints() {
+        assert_eq!(
+            "CREATE TABLE Story (
+  Id TEXT NOT NULL,
+  PRIMARY KEY (Id)
+);
+CREATE TABLE Chapter (
+  Id TEXT NOT NULL,
+  StoryId TEXT NOT NULL,
+  Place INT NOT NULL,
+  State ENUM('working', 'on-hold') NOT NULL,
+  PRIMARY KEY (Id),
+  CONSTRAINT Chapter_StoryId_fkey FOREIGN KEY (StoryId) REFERENCES Story(Id) ON UPDATE CASCADE ON DELETE CASCADE,
+  CONSTRAINT Chapter_StoryId_Place_key UNIQUE (StoryId, Place),
+  INDEX ChapterState (State)
+);
+",
+            write(
+                "enum State {
+                    Working
+                    OnHold
+                }
+
+                table Story {
+                    Id text [primary key]
+                }
+
+                table Chapter {
+                    Id text [primary key]
+                    StoryId text [ref: Story.Id (update: cascade, delete: cascade), unique]
+                    Place int [unique]
+                    State State
+
+                    index ChapterState on (State) where State != 'working'
+                }",
+                GeneratorOptions::default()
+            )
+        );
+    }
+
+    const ENUM: &str = "enum State {
+        Working
+        OnHold
+    }
+
+    table Task {
+        Id int [primary key]
+        State State [default: 'working']
+    }";
+
+    #[test]
+    fn enum_native() {
+        assert_eq!(
+            "CREATE TABLE Task (
+  Id INT NOT NULL,
+  State ENUM('working', 'on-hold') NOT NULL DEFAULT 'working',
+  PRIMARY KEY (Id)
+);
+",
+            write(ENUM, GeneratorOptions::default())
+        );
+    }
+
+    #[test]
+    fn enum_check() {
+        assert_eq!(
+            "CREATE TABLE Task (
+  Id INT NOT NULL,
+  State VARCHAR(7) NOT NULL DEFAULT 'working' CHECK (State IN ('working', 'on-hold')),
+  PRIMARY KEY (Id)
+);
+",
+            write(
+                ENUM,
+                GeneratorOptions {
+                    enum_storage: EnumStorage::Check,
+                    ..GeneratorOptions::default()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn enum_integer() {
+        assert_eq!(
+            "CREATE TABLE Task (
+  Id INT NOT NULL,
+  State TINYINT NOT NULL,
+  PRIMARY KEY (Id)
+);
+",
+            write(
+                "enum State {
+                    Working
+                    OnHold
+                }
+
+                table Task {
+                    Id int [primary key]
+                    State State
+                }",
+                GeneratorOptions {
+                    enum_storage: EnumStorage::Integer,
+                    ..GeneratorOptions::default()
+                }
+            )
+        );
+    }
+
+    fn decl(variants: usize) -> Enum<'static> {
+        Enum {
+            name: "Example",
+            not_exists: false,
+            variants: (0..variants)
+                .map(|_| Variant {
+                    name: "Variant",
+                    doc: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn enum_int_width() {
+        assert_eq!("TINYINT", enum_int_type(&decl(2)));
+        assert_eq!("TINYINT", enum_int_type(&decl(128)));
+        assert_eq!("SMALLINT", enum_int_type(&decl(129)));
+        assert_eq!("SMALLINT", enum_int_type(&decl(32768)));
+        assert_eq!("INT", enum_int_type(&decl(32769)));
+    }
+
+    #[test]
+    fn bool_default() {
+        const SCHEMA: &str = "table Setting {
+            Key text [primary key]
+            Enabled boolean [default: true]
+            Hidden boolean [default: false]
+        }";
+
+        assert_eq!(
+            "CREATE TABLE Setting (
+  Key TEXT NOT NULL,
+  Enabled BOOL NOT NULL DEFAULT TRUE,
+  Hidden BOOL NOT NULL DEFAULT FALSE,
+  PRIMARY KEY (Key)
+);
+",
+            write(SCHEMA, GeneratorOptions::default())
+        );
+        assert!(write(
+            SCHEMA,
+            GeneratorOptions {
+                uppercase_keywords: false,
+                ..GeneratorOptions::default()
+            }
+        )
+        .contains("Enabled bool not null default true,"));
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(
+            "CREATE TABLE Reading (
+  Celsius FLOAT NOT NULL,
+  Humidity DOUBLE,
+  PRIMARY KEY (Celsius)
+);
+",
+            write(
+                "table Reading {
+                    Celsius real [primary key]
+                    Humidity double!
+                }",
+                GeneratorOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn interval() {
+        assert_eq!(
+            "CREATE TABLE Task (
+  Id INT NOT NULL,
+  -- Stored as a number of nanoseconds.
+  Timeout BIGINT NOT NULL,
+  PRIMARY KEY (Id)
+);
+",
+            write(
+                "table Task {
+                    Id int [primary key]
+                    Timeout duration
+                }",
+                GeneratorOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn uuid() {
+        assert_eq!(
+            "CREATE TABLE Session (
+  Id CHAR(36) NOT NULL DEFAULT (UUID()),
+  PRIMARY KEY (Id)
+);
+",
+            write(
+                "table Session {
+                    Id uuid [primary key, default: uuid()]
+                }",
+                GeneratorOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn constraint_names() {
+        assert_eq!(
+            "CREATE TABLE Member (
+  Id TEXT NOT NULL,
+  GroupId TEXT NOT NULL,
+  Email TEXT NOT NULL,
+  PRIMARY KEY (Id),
+  CONSTRAINT member_group FOREIGN KEY (GroupId) REFERENCES Member(Id) ON UPDATE NO ACTION ON DELETE NO ACTION,
+  CONSTRAINT member_email UNIQUE (Email)
+);
+",
+            write(
+                "table Member {
+                    Id text [primary key]
+                    GroupId text [ref: Member.Id as member_group]
+                    Email text [unique as member_email]
+                }",
+                GeneratorOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn deferrable() {
+        assert_eq!(
+            "CREATE TABLE Node (
+  Id TEXT NOT NULL,
+  ParentId TEXT,
+  PRIMARY KEY (Id),
+  CONSTRAINT Node_ParentId_fkey FOREIGN KEY (ParentId) REFERENCES Node(Id) ON UPDATE NO ACTION ON DELETE CASCADE
+);
+",
+            write(
+                "table Node {
+                    Id text [primary key]
+                    ParentId text! [ref: Node.Id (delete: cascade, deferrable)]
+                }",
+                GeneratorOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn lowercase() {
+        assert_eq!(
+            "create table Setting (
+    Key text not null,
+    Created date not null default (current_date),
+    primary key (Key)
+);
+",
+            write(
+                "table Setting {
+                    Key text [primary key]
+                    Created date [default: today()]
+                }",
+                GeneratorOptions {
+                    indent: 4,
+                    uppercase_keywords: false,
+                    ..GeneratorOptions::default()
+                }
+            )
+        );
+    }
 }
@@ -1,12 +1,22 @@
 use {
-    crate::Error,
-    rewryte_parser::models::{Column, ColumnDefault, Enum, ForeignKey, Item, Schema, Table, Types},
-    std::io,
+    crate::{Error, GeneratorOptions},
+    rewryte_parser::models::{
+        Column, ColumnDefault, Enum, ForeignKey, Index, Item, Schema, Table, Types,
+    },
+    std::{borrow::Cow, io},
 };
 
-pub fn write_schema(schema: &Schema, writer: &mut impl io::Write) -> Result<(), Error> {
+pub fn write_schema(
+    schema: &Schema,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
     for item in &schema.items {
-        write_item(item, writer)?;
+        if let Item::Comment(_) = item {
+            continue;
+        }
+
+        write_item(item, writer, options)?;
 
         writeln!(writer)?;
     }
@@ -14,23 +24,50 @@ pub fn write_schema(schema: &Schema, writer: &mut impl io::Write) -> Result<(),
     Ok(())
 }
 
-pub fn write_item(item: &Item, writer: &mut impl io::Write) -> Result<(), Error> {
+pub fn write_item(
+    item: &Item,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
     match &item {
-        Item::Enum(decl) => write_enum(decl, writer)?,
-        Item::Table(decl) => write_table(decl, writer)?,
+        Item::Comment(_) => {}
+        Item::Enum(decl) => write_enum(decl, writer, options)?,
+        Item::Table(decl) => write_table(decl, writer, options)?,
     }
 
     Ok(())
 }
 
-// TODO: figure out how to handle `IF NOT EXISTS`
-pub fn write_enum(decl: &Enum, writer: &mut impl io::Write) -> Result<(), Error> {
-    write!(writer, "CREATE TYPE {} AS ENUM (", decl.name)?;
+/// Writes a `CREATE TYPE` for the enum.
+///
+/// PostgreSQL has no `CREATE TYPE IF NOT EXISTS`, so a guarded enum is created in a block that ignores the error
+/// raised when the type already exists.
+pub fn write_enum(
+    decl: &Enum,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    if decl.not_exists {
+        writeln!(
+            writer,
+            "{} $$ {}",
+            options.keyword("DO"),
+            options.keyword("BEGIN")
+        )?;
+    }
+
+    write!(
+        writer,
+        "{} {} {} (",
+        options.keyword("CREATE TYPE"),
+        decl.name,
+        options.keyword("AS ENUM")
+    )?;
 
     writeln!(writer)?;
 
     for (i, variant) in decl.variants.iter().enumerate() {
-        write!(writer, "  '{}'", variant)?;
+        write!(writer, "{}'{}'", options.indentation(), variant.name)?;
 
         if i != decl.variants.len() - 1 {
             write!(writer, ",")?;
@@ -41,14 +78,29 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write) -> Result<(), Error>
 
     write!(writer, ");")?;
 
+    if decl.not_exists {
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "{} duplicate_object {};",
+            options.keyword("EXCEPTION WHEN"),
+            options.keyword("THEN NULL")
+        )?;
+        write!(writer, "{} $$;", options.keyword("END"))?;
+    }
+
     Ok(())
 }
 
-pub fn write_table(decl: &Table, writer: &mut impl io::Write) -> Result<(), Error> {
-    write!(writer, "CREATE TABLE")?;
+pub fn write_table(
+    decl: &Table,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(writer, "{}", options.keyword("CREATE TABLE"))?;
 
     if decl.not_exists {
-        write!(writer, " IF NOT EXISTS")?;
+        write!(writer, " {}", options.keyword("IF NOT EXISTS"))?;
     }
 
     write!(writer, " {} (", decl.name)?;
@@ -56,14 +108,19 @@ pub fn write_table(decl: &Table, writer: &mut impl io::Write) -> Result<(), Erro
     writeln!(writer)?;
 
     for column in &decl.columns {
-        write_column(column, writer)?;
+        write_column(column, writer, options)?;
 
         write!(writer, ",")?;
 
         writeln!(writer)?;
     }
 
-    write!(writer, "  PRIMARY KEY (")?;
+    write!(
+        writer,
+        "{}{} (",
+        options.indentation(),
+        options.keyword("PRIMARY KEY")
+    )?;
 
     for (i, primary) in decl.primary_keys.iter().enumerate() {
         write!(writer, "{}", primary)?;
@@ -80,7 +137,7 @@ pub fn write_table(decl: &Table, writer: &mut impl io::Write) -> Result<(), Erro
         writeln!(writer)?;
 
         for (i, foreign_key) in decl.foreign_keys.iter().enumerate() {
-            write_foreign_key(foreign_key, writer)?;
+            write_foreign_key(decl.name, foreign_key, writer, options)?;
 
             if i != decl.foreign_keys.len() - 1 {
                 write!(writer, ",")?;
@@ -100,7 +157,20 @@ pub fn write_table(decl: &Table, writer: &mut impl io::Write) -> Result<(), Erro
         write!(writer, ",")?;
         writeln!(writer)?;
 
-        write!(writer, "  UNIQUE (")?;
+        write!(
+            writer,
+            "{}{} {} {}",
+            options.indentation(),
+            options.keyword("CONSTRAINT"),
+            decl.unique_constraint_name(),
+            options.keyword("UNIQUE")
+        )?;
+
+        if decl.unique_nulls_not_distinct {
+            write!(writer, " {}", options.keyword("NULLS NOT DISTINCT"))?;
+        }
+
+        write!(writer, " (")?;
 
         for (i, unique) in decl.unique_keys.iter().enumerate() {
             write!(writer, "{}", unique)?;
@@ -117,59 +187,216 @@ pub fn write_table(decl: &Table, writer: &mut impl io::Write) -> Result<(), Erro
 
     write!(writer, ");")?;
 
+    write_comments(decl, writer, options)?;
+
+    for index in &decl.indexes {
+        writeln!(writer)?;
+
+        write_index(decl, index, writer, options)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_index(
+    decl: &Table,
+    index: &Index,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(writer, "{}", options.keyword("CREATE INDEX"))?;
+
+    if index.not_exists || decl.not_exists {
+        write!(writer, " {}", options.keyword("IF NOT EXISTS"))?;
+    }
+
+    write!(
+        writer,
+        " {} {} {} (",
+        index.name,
+        options.keyword("ON"),
+        decl.name
+    )?;
+
+    for (i, column) in index.columns.iter().enumerate() {
+        write!(writer, "{}", column)?;
+
+        if i != index.columns.len() - 1 {
+            write!(writer, ", ")?;
+        }
+    }
+
+    write!(writer, ")")?;
+
+    if let Some(predicate) = index.predicate {
+        write!(writer, " {} {}", options.keyword("WHERE"), predicate)?;
+    }
+
+    write!(writer, ";")?;
+
     Ok(())
 }
 
-pub fn write_column(column: &Column, writer: &mut impl io::Write) -> Result<(), Error> {
-    write!(writer, "  {} ", column.name,)?;
+pub fn write_comments(
+    decl: &Table,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    if let Some(doc) = decl.doc {
+        writeln!(writer)?;
 
-    write_types(&column.typ, writer)?;
+        write!(
+            writer,
+            "{} {} {} ",
+            options.keyword("COMMENT ON TABLE"),
+            decl.name,
+            options.keyword("IS")
+        )?;
+
+        write_comment(doc, writer)?;
+
+        write!(writer, ";")?;
+    }
+
+    for column in &decl.columns {
+        if let Some(doc) = column.doc {
+            writeln!(writer)?;
+
+            write!(
+                writer,
+                "{} {}.{} {} ",
+                options.keyword("COMMENT ON COLUMN"),
+                decl.name,
+                column.name,
+                options.keyword("IS")
+            )?;
+
+            write_comment(doc, writer)?;
+
+            write!(writer, ";")?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_comment(doc: &str, writer: &mut impl io::Write) -> Result<(), Error> {
+    write!(writer, "'")?;
+
+    for (i, line) in crate::doc_lines(doc).enumerate() {
+        if i != 0 {
+            writeln!(writer)?;
+        }
+
+        write!(writer, "{}", line.replace('\'', "''"))?;
+    }
+
+    write!(writer, "'")?;
+
+    Ok(())
+}
+
+pub fn write_column(
+    column: &Column,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(writer, "{}{} ", options.indentation(), column.name)?;
+
+    write_types(&column.typ, writer, options)?;
+
+    if let Some(collate) = column.collate {
+        write!(writer, " {} {}", options.keyword("COLLATE"), collate)?;
+    }
 
     if !column.null {
-        write!(writer, " NOT NULL")?;
+        write!(writer, " {}", options.keyword("NOT NULL"))?;
     }
 
-    write_column_default(&column.default, writer)?;
+    write_column_default(&column.default, writer, options)?;
 
     Ok(())
 }
 
-pub fn write_types(types: &Types, writer: &mut impl io::Write) -> Result<(), Error> {
-    write!(
-        writer,
-        "{}",
-        match types {
-            Types::Char => r#""char""#,
-            Types::Text => "TEXT",
-            Types::Varchar => "VARCHAR",
-            Types::SmallInt => "SMALLINT",
-            Types::Number | Types::Int | Types::MediumInt | Types::Serial => "INT",
-            Types::BigInt => "BIGINT",
-            Types::Float | Types::Real => "REAL",
-            Types::Numeric => "NUMERIC",
-            Types::Decimal => "DECIMAL",
-            Types::DateTime => "TIMESTAMP WITH TIME ZONE",
-            Types::Boolean => "BOOL",
-            Types::Raw(raw) => raw,
+/// Writes a column's type, raw types are written as they are in the schema whatever the keyword case.
+pub fn write_types(
+    types: &Types,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    match types {
+        Types::Array(inner) => {
+            write_types(inner, writer, options)?;
+
+            write!(writer, "[]")?;
         }
-    )?;
+        Types::Raw(raw) => write!(writer, "{}", raw)?,
+        types => write!(writer, "{}", options.keyword(&type_name(types)))?,
+    }
 
     Ok(())
 }
 
+/// The PostgreSQL type a column type is created as, arrays get a `[]` suffix per level.
+pub fn type_name<'t>(types: &'t Types) -> Cow<'t, str> {
+    Cow::Borrowed(match types {
+        Types::Array(inner) => return Cow::Owned(format!("{}[]", type_name(inner))),
+        Types::Char => r#""char""#,
+        Types::Text => "TEXT",
+        Types::Varchar(None) => "VARCHAR",
+        Types::Varchar(Some(length)) => return Cow::Owned(format!("VARCHAR({})", length)),
+        Types::SmallInt => "SMALLINT",
+        Types::Number | Types::Int | Types::MediumInt => "INT",
+        Types::Serial => "SERIAL",
+        Types::SmallSerial => "SMALLSERIAL",
+        Types::BigSerial => "BIGSERIAL",
+        Types::BigInt => "BIGINT",
+        Types::Float | Types::Real => "REAL",
+        Types::Double => "DOUBLE PRECISION",
+        Types::Numeric => "NUMERIC",
+        Types::Decimal => "DECIMAL",
+        // `MONEY` depends on the server's locale, and most drivers can not read it
+        Types::Money => "NUMERIC(19, 4)",
+        Types::DateTime => "TIMESTAMP WITH TIME ZONE",
+        Types::Date => "DATE",
+        Types::Time => "TIME",
+        Types::Interval => "INTERVAL",
+        Types::Boolean => "BOOL",
+        Types::Uuid => "UUID",
+        Types::Raw(raw) => raw,
+    })
+}
+
 pub fn write_column_default(
     column_default: &ColumnDefault,
     writer: &mut impl io::Write,
+    options: GeneratorOptions,
 ) -> Result<(), Error> {
     if column_default != &ColumnDefault::None {
-        write!(writer, " DEFAULT")?;
+        write!(writer, " {}", options.keyword("DEFAULT"))?;
 
         match column_default {
             ColumnDefault::Now => {
                 write!(writer, " (timezone('utc', now()))")?;
             }
+            ColumnDefault::CurrentDate => {
+                write!(writer, " {}", options.keyword("CURRENT_DATE"))?;
+            }
+            ColumnDefault::CurrentTime => {
+                write!(writer, " {}", options.keyword("CURRENT_TIME"))?;
+            }
             ColumnDefault::Null => {
-                write!(writer, " NULL")?;
+                write!(writer, " {}", options.keyword("NULL"))?;
+            }
+            ColumnDefault::Uuid => {
+                write!(writer, " gen_random_uuid()")?;
+            }
+            ColumnDefault::Bool(value) => {
+                write!(
+                    writer,
+                    " {}",
+                    options.keyword(if *value { "TRUE" } else { "FALSE" })
+                )?;
             }
             ColumnDefault::Raw(raw) => {
                 write!(writer, " {}", raw)?;
@@ -182,38 +409,107 @@ pub fn write_column_default(
 }
 
 pub fn write_foreign_key(
+    table: &str,
     foreign_key: &ForeignKey,
     writer: &mut impl io::Write,
+    options: GeneratorOptions,
 ) -> Result<(), Error> {
     write!(
         writer,
-        "  FOREIGN KEY ({}) REFERENCES {}({}) ON UPDATE {} ON DELETE {}",
-        foreign_key.local,
+        "{}{} {} {} ({}) {} {}({}) {} {} {} {}",
+        options.indentation(),
+        options.keyword("CONSTRAINT"),
+        foreign_key.constraint_name(table),
+        options.keyword("FOREIGN KEY"),
+        foreign_key.local.join(", "),
+        options.keyword("REFERENCES"),
         foreign_key.table,
-        foreign_key.foreign,
-        foreign_key.update,
-        foreign_key.delete,
+        foreign_key.foreign.join(", "),
+        options.keyword("ON UPDATE"),
+        options.keyword(&foreign_key.update.to_string()),
+        options.keyword("ON DELETE"),
+        options.keyword(&foreign_key.delete.to_string()),
     )?;
 
+    if foreign_key.deferrable {
+        write!(
+            writer,
+            " {}",
+            options.keyword("DEFERRABLE INITIALLY DEFERRED")
+        )?;
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     mod enums {
-        use {crate::postgresql::write_enum, rewryte_parser::models::*};
+        use {
+            crate::{
+                postgresql::{write_enum, write_schema},
+                GeneratorOptions,
+            },
+            rewryte_parser::models::*,
+        };
+
+        #[test]
+        fn comments_ignored() {
+            let schema = Schema {
+                database: None,
+                if_not_exists: false,
+                items: vec![
+                    Item::Comment("Written by hand"),
+                    Item::Enum(Enum {
+                        name: "Test",
+                        not_exists: false,
+                        variants: vec![Variant {
+                            name: "Variant1",
+                            doc: None,
+                        }],
+                    }),
+                    Item::Comment("The end"),
+                ],
+            };
+
+            let mut writer = Vec::new();
+
+            write_schema(&schema, &mut writer, GeneratorOptions::default())
+                .expect("Unable to write schema to buffer");
+
+            let utf8_writer =
+                String::from_utf8(writer).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "CREATE TYPE Test AS ENUM (
+  'Variant1'
+);
+",
+                utf8_writer.as_str(),
+            );
+        }
 
         #[test]
         fn simple() {
             let decl = Enum {
                 name: "Test",
                 not_exists: false,
-                variants: vec!["Variant1", "Variant2"],
+                variants: vec![
+                    Variant {
+                        name: "Variant1",
+                        doc: None,
+                    },
+                    Variant {
+                        name: "Variant2",
+                        doc: None,
+                    },
+                ],
             };
 
             let mut writer = Vec::new();
 
-            write_enum(&decl, &mut writer).expect("Unable to write enum to buffer");
+            write_enum(&decl, &mut writer, GeneratorOptions::default())
+                .expect("Unable to write enum to buffer");
 
             let utf8_writer =
                 String::from_utf8(writer).expect("Unable to convert buff into string");
@@ -226,38 +522,339 @@ mod tests {
                 utf8_writer.as_str(),
             );
         }
+
+        #[test]
+        fn not_exists() {
+            let decl = Enum {
+                name: "Test",
+                not_exists: true,
+                variants: vec![Variant {
+                    name: "Variant1",
+                    doc: None,
+                }],
+            };
+
+            let mut writer = Vec::new();
+
+            write_enum(&decl, &mut writer, GeneratorOptions::default())
+                .expect("Unable to write enum to buffer");
+
+            let utf8_writer =
+                String::from_utf8(writer).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "DO $$ BEGIN
+CREATE TYPE Test AS ENUM (
+  'Variant1'
+);
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;",
+                utf8_writer.as_str(),
+            );
+        }
+    }
+
+    mod types {
+        use {
+            crate::{postgresql::write_types, GeneratorOptions},
+            rewryte_parser::models::*,
+        };
+
+        fn assert_type(expected: &str, types: Types) {
+            let mut buff = Vec::new();
+
+            write_types(&types, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write type to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(expected, utf8_buff.as_str());
+        }
+
+        #[test]
+        fn uuid() {
+            assert_type("UUID", Types::Uuid);
+        }
+
+        #[test]
+        fn floats() {
+            assert_type("REAL", Types::Float);
+            assert_type("REAL", Types::Real);
+            assert_type("DOUBLE PRECISION", Types::Double);
+        }
+
+        #[test]
+        fn array() {
+            assert_type("TEXT[]", Types::Array(Box::new(Types::Text)));
+            assert_type(
+                "INT[][]",
+                Types::Array(Box::new(Types::Array(Box::new(Types::Int)))),
+            );
+        }
+
+        #[test]
+        fn money() {
+            assert_type("NUMERIC(19, 4)", Types::Money);
+        }
+
+        #[test]
+        fn varchar() {
+            assert_type("VARCHAR", Types::Varchar(None));
+            assert_type("VARCHAR(10)", Types::Varchar(Some(10)));
+        }
+
+        #[test]
+        fn date_time() {
+            assert_type("TIMESTAMP WITH TIME ZONE", Types::DateTime);
+            assert_type("DATE", Types::Date);
+            assert_type("TIME", Types::Time);
+            assert_type("INTERVAL", Types::Interval);
+        }
+
+        #[test]
+        fn serial() {
+            assert_type("SERIAL", Types::Serial);
+            assert_type("SMALLSERIAL", Types::SmallSerial);
+            assert_type("BIGSERIAL", Types::BigSerial);
+        }
+    }
+
+    mod defaults {
+        use {
+            crate::{
+                postgresql::{write_column, write_column_default},
+                GeneratorOptions,
+            },
+            rewryte_parser::models::*,
+        };
+
+        #[test]
+        fn collate() {
+            let column = Column {
+                name: "Name",
+                doc: None,
+                typ: Types::Text,
+                null: true,
+                default: ColumnDefault::None,
+                skip: vec![],
+                rust_name: None,
+                collate: Some("\"en_US\""),
+                searchable: false,
+                version: false,
+            };
+
+            let mut buff = Vec::new();
+
+            write_column(&column, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write column to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!("  Name TEXT COLLATE \"en_US\"", utf8_buff.as_str());
+        }
+
+        #[test]
+        fn null() {
+            let cases = [
+                (false, ColumnDefault::None, "  Value TEXT NOT NULL"),
+                (true, ColumnDefault::None, "  Value TEXT"),
+                (true, ColumnDefault::Null, "  Value TEXT DEFAULT NULL"),
+            ];
+
+            for (null, default, expected) in cases.iter() {
+                let column = Column {
+                    name: "Value",
+                    doc: None,
+                    typ: Types::Text,
+                    null: *null,
+                    default: default.clone(),
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                };
+
+                let mut buff = Vec::new();
+
+                write_column(&column, &mut buff, GeneratorOptions::default())
+                    .expect("Unable to write column to buffer");
+
+                let utf8_buff =
+                    String::from_utf8(buff).expect("Unable to convert buff into string");
+
+                assert_eq!(*expected, utf8_buff.as_str());
+            }
+        }
+
+        #[test]
+        fn uuid() {
+            let mut buff = Vec::new();
+
+            write_column_default(&ColumnDefault::Uuid, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write default to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(" DEFAULT gen_random_uuid()", utf8_buff.as_str());
+        }
+
+        #[test]
+        fn current_date_time() {
+            let cases = [
+                (ColumnDefault::CurrentDate, " DEFAULT CURRENT_DATE"),
+                (ColumnDefault::CurrentTime, " DEFAULT CURRENT_TIME"),
+            ];
+
+            for (default, expected) in cases.iter() {
+                let mut buff = Vec::new();
+
+                write_column_default(default, &mut buff, GeneratorOptions::default())
+                    .expect("Unable to write default to buffer");
+
+                let utf8_buff =
+                    String::from_utf8(buff).expect("Unable to convert buff into string");
+
+                assert_eq!(*expected, utf8_buff.as_str());
+            }
+        }
+
+        #[test]
+        fn bool() {
+            for (value, expected) in [(true, " DEFAULT TRUE"), (false, " DEFAULT FALSE")].iter() {
+                let mut buff = Vec::new();
+
+                write_column_default(
+                    &ColumnDefault::Bool(*value),
+                    &mut buff,
+                    GeneratorOptions::default(),
+                )
+                .expect("Unable to write default to buffer");
+
+                let utf8_buff =
+                    String::from_utf8(buff).expect("Unable to convert buff into string");
+
+                assert_eq!(*expected, utf8_buff.as_str());
+            }
+        }
+    }
+
+    mod foreign_keys {
+        use {
+            crate::{postgresql::write_foreign_key, GeneratorOptions},
+            rewryte_parser::models::*,
+        };
+
+        #[test]
+        fn deferrable() {
+            let foreign_key = ForeignKey {
+                local: vec!["ParentId"],
+                table: "Node",
+                foreign: vec!["Id"],
+                delete: Action::Cascade,
+                update: Action::default(),
+                deferrable: true,
+                name: None,
+            };
+
+            let mut buff = Vec::new();
+
+            write_foreign_key("Node", &foreign_key, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write foreign key to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "  CONSTRAINT Node_ParentId_fkey FOREIGN KEY (ParentId) REFERENCES Node(Id) ON UPDATE NO ACTION ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED",
+                utf8_buff.as_str()
+            );
+        }
+
+        #[test]
+        fn multi_column() {
+            let foreign_key = ForeignKey {
+                local: vec!["StoryId", "ChapterId"],
+                table: "StoryChapter",
+                foreign: vec!["StoryId", "ChapterId"],
+                delete: Action::Cascade,
+                update: Action::default(),
+                deferrable: false,
+                name: None,
+            };
+
+            let mut buff = Vec::new();
+
+            write_foreign_key(
+                "Comment",
+                &foreign_key,
+                &mut buff,
+                GeneratorOptions::default(),
+            )
+            .expect("Unable to write foreign key to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "  CONSTRAINT Comment_StoryId_ChapterId_fkey FOREIGN KEY (StoryId, ChapterId) REFERENCES StoryChapter(StoryId, ChapterId) ON UPDATE NO ACTION ON DELETE CASCADE",
+                utf8_buff.as_str()
+            );
+        }
     }
 
     mod tables {
-        use {crate::postgresql::write_table, rewryte_parser::models::*};
+        use {
+            crate::{
+                postgresql::{write_schema, write_table},
+                GeneratorOptions,
+            },
+            rewryte_parser::{models::*, parse, Context},
+        };
 
         #[test]
         fn simple() {
             let table = Table {
                 name: "Example",
+                doc: None,
                 not_exists: true,
                 columns: vec![
                     Column {
                         name: "Id",
+                        doc: None,
                         typ: Types::Text,
                         null: false,
                         default: ColumnDefault::None,
+                        skip: vec![],
+                        rust_name: None,
+                        collate: None,
+                        searchable: false,
+                        version: false,
                     },
                     Column {
                         name: "Name",
+                        doc: None,
                         typ: Types::Text,
                         null: false,
                         default: ColumnDefault::None,
+                        skip: vec![],
+                        rust_name: None,
+                        collate: None,
+                        searchable: false,
+                        version: false,
                     },
                 ],
                 primary_keys: vec!["Id"],
                 foreign_keys: vec![],
                 unique_keys: vec![],
+                unique_name: None,
+                unique_nulls_not_distinct: false,
+                without_rowid: false,
+                indexes: vec![],
             };
 
             let mut buff = Vec::new();
 
-            write_table(&table, &mut buff).expect("Unable to write table to buffer");
+            write_table(&table, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write table to buffer");
 
             let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
 
@@ -270,5 +867,338 @@ mod tests {
                 utf8_buff.as_str()
             );
         }
+
+        #[test]
+        fn partial_index() {
+            let table = Table {
+                name: "Example",
+                doc: None,
+                not_exists: true,
+                columns: vec![Column {
+                    name: "Value",
+                    doc: None,
+                    typ: Types::Text,
+                    null: true,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                }],
+                primary_keys: vec!["Value"],
+                foreign_keys: vec![],
+                unique_keys: vec![],
+                unique_name: None,
+                unique_nulls_not_distinct: false,
+                without_rowid: false,
+                indexes: vec![Index {
+                    name: "ValueIndex",
+                    not_exists: true,
+                    columns: vec!["Value"],
+                    predicate: Some("Value IS NOT NULL"),
+                }],
+            };
+
+            let mut buff = Vec::new();
+
+            write_table(&table, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write table to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "CREATE TABLE IF NOT EXISTS Example (
+  Value TEXT,
+  PRIMARY KEY (Value)
+);
+CREATE INDEX IF NOT EXISTS ValueIndex ON Example (Value) WHERE Value IS NOT NULL;",
+                utf8_buff.as_str()
+            );
+        }
+
+        #[test]
+        fn index_of_guarded_table() {
+            // Built without the parser, which would have set the index's own flag
+            let table = Table {
+                name: "Example",
+                doc: None,
+                not_exists: true,
+                columns: vec![Column {
+                    name: "Value",
+                    doc: None,
+                    typ: Types::Text,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                }],
+                primary_keys: vec!["Value"],
+                foreign_keys: vec![],
+                unique_keys: vec![],
+                unique_name: None,
+                unique_nulls_not_distinct: false,
+                without_rowid: false,
+                indexes: vec![Index {
+                    name: "ValueIndex",
+                    not_exists: false,
+                    columns: vec!["Value"],
+                    predicate: None,
+                }],
+            };
+
+            let mut buff = Vec::new();
+
+            write_table(&table, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write table to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "CREATE TABLE IF NOT EXISTS Example (
+  Value TEXT NOT NULL,
+  PRIMARY KEY (Value)
+);
+CREATE INDEX IF NOT EXISTS ValueIndex ON Example (Value);",
+                utf8_buff.as_str()
+            );
+        }
+
+        #[test]
+        fn comments() {
+            let table = Table {
+                name: "Example",
+                doc: Some("An example's table"),
+                not_exists: false,
+                columns: vec![
+                    Column {
+                        name: "Id",
+                        doc: Some("The first line\n    the second line"),
+                        typ: Types::Text,
+                        null: false,
+                        default: ColumnDefault::None,
+                        skip: vec![],
+                        rust_name: None,
+                        collate: None,
+                        searchable: false,
+                        version: false,
+                    },
+                    Column {
+                        name: "Name",
+                        doc: None,
+                        typ: Types::Text,
+                        null: false,
+                        default: ColumnDefault::None,
+                        skip: vec![],
+                        rust_name: None,
+                        collate: None,
+                        searchable: false,
+                        version: false,
+                    },
+                    Column {
+                        name: "Value",
+                        doc: Some("The value"),
+                        typ: Types::Text,
+                        null: false,
+                        default: ColumnDefault::None,
+                        skip: vec![],
+                        rust_name: None,
+                        collate: None,
+                        searchable: false,
+                        version: false,
+                    },
+                ],
+                primary_keys: vec!["Id"],
+                foreign_keys: vec![],
+                unique_keys: vec![],
+                unique_name: None,
+                unique_nulls_not_distinct: false,
+                without_rowid: false,
+                indexes: vec![],
+            };
+
+            let mut buff = Vec::new();
+
+            write_table(&table, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write table to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "CREATE TABLE Example (
+  Id TEXT NOT NULL,
+  Name TEXT NOT NULL,
+  Value TEXT NOT NULL,
+  PRIMARY KEY (Id)
+);
+COMMENT ON TABLE Example IS 'An example''s table';
+COMMENT ON COLUMN Example.Id IS 'The first line
+the second line';
+COMMENT ON COLUMN Example.Value IS 'The value';",
+                utf8_buff.as_str()
+            );
+        }
+
+        #[test]
+        fn mixin() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "mixin Timestamps {
+                    Created dateTime [default: now()]
+                }
+
+                table Example includes Timestamps {
+                    Id text [primary key]
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            let mut buff = Vec::new();
+
+            write_schema(&schema, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write schema to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "CREATE TABLE Example (
+  Id TEXT NOT NULL,
+  Created TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (timezone('utc', now())),
+  PRIMARY KEY (Id)
+);
+",
+                utf8_buff.as_str()
+            );
+        }
+
+        fn write_unique(modifier: &str) -> String {
+            let mut ctx = Context::new(0);
+
+            let input = format!(
+                "table Example {{
+                    Id text [primary key]
+                    Email text! [{}]
+                }}",
+                modifier
+            );
+
+            let schema = parse(&mut ctx, &input).expect("Unable to parse schema");
+
+            let mut buff = Vec::new();
+
+            write_schema(&schema, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write schema to buffer");
+
+            String::from_utf8(buff).expect("Unable to convert buff into string")
+        }
+
+        #[test]
+        fn unique_nulls_distinct() {
+            assert_eq!(
+                "CREATE TABLE Example (
+  Id TEXT NOT NULL,
+  Email TEXT,
+  PRIMARY KEY (Id),
+  CONSTRAINT Example_Email_key UNIQUE (Email)
+);
+",
+                write_unique("unique").as_str()
+            );
+        }
+
+        #[test]
+        fn unique_nulls_not_distinct() {
+            assert_eq!(
+                "CREATE TABLE Example (
+  Id TEXT NOT NULL,
+  Email TEXT,
+  PRIMARY KEY (Id),
+  CONSTRAINT Example_Email_key UNIQUE NULLS NOT DISTINCT (Email)
+);
+",
+                write_unique("unique nulls not distinct").as_str()
+            );
+        }
+
+        #[test]
+        fn guarded_index() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "table Example {
+                    Id text [primary key]
+                    Name text
+                    index NameIndex? on (Name)
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            let mut buff = Vec::new();
+
+            write_schema(&schema, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write schema to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "CREATE TABLE Example (
+  Id TEXT NOT NULL,
+  Name TEXT NOT NULL,
+  PRIMARY KEY (Id)
+);
+CREATE INDEX IF NOT EXISTS NameIndex ON Example (Name);
+",
+                utf8_buff.as_str()
+            );
+        }
+
+        #[test]
+        fn if_not_exists_annotation() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "@if_not_exists
+
+                enum State { Working }
+
+                table Example {
+                    Id text [primary key]
+                    State State
+                    index StateIndex on (State)
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            let mut buff = Vec::new();
+
+            write_schema(&schema, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write schema to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(
+                "DO $$ BEGIN
+CREATE TYPE State AS ENUM (
+  'Working'
+);
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+CREATE TABLE IF NOT EXISTS Example (
+  Id TEXT NOT NULL,
+  State State NOT NULL,
+  PRIMARY KEY (Id)
+);
+CREATE INDEX IF NOT EXISTS StateIndex ON Example (State);
+",
+                utf8_buff.as_str()
+            );
+        }
     }
 }
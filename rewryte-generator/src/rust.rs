@@ -1,15 +1,149 @@
 use {
     crate::Error,
-    heck::{KebabCase, SnakeCase},
-    rewryte_parser::models::{Enum, Item, Schema, Table, Types},
-    std::io,
+    heck::{CamelCase, KebabCase, SnakeCase},
+    proc_macro2::{Literal, TokenStream},
+    rewryte_parser::{
+        models::{Column, ColumnDefault, Enum, Item, Schema, Table, Types},
+        str_range, Context,
+    },
+    std::{convert::TryFrom, io},
 };
 
 #[derive(Default, Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Options {
     pub juniper: bool,
+    /// Derive async-graphql's `SimpleObject` and `Enum`, an alternative to `juniper` that can not be used with it.
+    pub async_graphql: bool,
+    /// Derive serde's `Serialize` and `Deserialize`, enum variants are renamed to kebab-case like SQLite stores them.
     pub serde: bool,
+    /// Leave nullable fields out when serializing them with serde while they are `None`, instead of writing `null`.
+    pub skip_none: bool,
+    /// Fill in the column's default when deserializing input that leaves out a column with a default, with serde.
+    ///
+    /// Defaults that can not be written in Rust, like raw SQL expressions, leave the field required.
+    pub serde_default: bool,
+    /// Derive sqlx's `FromRow` and `Type`, enum variants are renamed to kebab-case like SQLite stores them.
     pub sqlx: bool,
+    /// Generate a `Default` impl for tables where every column is nullable or has a default.
+    pub derive_default: bool,
+    /// Generate a `new` constructor taking every column that has no default and is not serial.
+    pub constructors: bool,
+    /// Mark generated structs and enums as `#[non_exhaustive]`, so adding a column or variant is not a breaking change.
+    ///
+    /// Other crates can then no longer build a struct with a literal or match an enum without a wildcard arm,
+    /// they have to go through the `Default` impl of `derive_default` or the `constructors` instead.
+    pub non_exhaustive: bool,
+    /// Convert table and enum names to PascalCase for the Rust types, the database names are kept for everything else.
+    pub pascal_case_types: bool,
+    /// Implement `TryFrom` for the database rows as well, so rows can be converted with `try_into`.
+    ///
+    /// The impls call the `FromRow` impls, so the error is the same `anyhow::Error`.
+    pub try_from_row: bool,
+    /// Generate `TABLE` and `COLUMNS` constants holding the table's and every column's database name.
+    pub table_constants: bool,
+    /// Generate a `{Table}Column` enum with a variant for every column, `as_str` gives the column's database name.
+    ///
+    /// Unlike `COLUMNS`, hand-built queries can match on it and the compiler catches a misspelt column.
+    pub column_enum: bool,
+    /// Implement the databases' `Search` traits for tables with `[searchable]` columns.
+    ///
+    /// SQLite matches the columns with `LIKE`, PostgreSQL with a full-text search over all of them.
+    pub search: bool,
+    /// Generate a `#[repr(transparent)]` newtype for every single column primary key, `UserId(i32)` for `User`.
+    ///
+    /// The key and every column referencing it are generated as the newtype, so ids of different tables can not be
+    /// mixed up.
+    pub id_newtypes: bool,
+    /// Format the generated code over multiple lines, for output that is meant to be read or checked in.
+    ///
+    /// Needs the `pretty` feature, without it the code is written compactly.
+    pub pretty: bool,
+}
+
+impl Options {
+    /// Enables an option by the name used in the `models!` extras, returning `false` if the name is unknown.
+    pub fn enable(&mut self, extra: &str) -> bool {
+        match extra {
+            "juniper" => self.juniper = true,
+            // Documented as `async-graphql` at first, so that name is still accepted
+            "async_graphql" | "async-graphql" => self.async_graphql = true,
+            "serde" => self.serde = true,
+            "skip_none" => self.skip_none = true,
+            "serde_default" => self.serde_default = true,
+            "sqlx" => self.sqlx = true,
+            "default" => self.derive_default = true,
+            "constructors" => self.constructors = true,
+            "non_exhaustive" => self.non_exhaustive = true,
+            "pascal_case_types" => self.pascal_case_types = true,
+            "try_from_row" => self.try_from_row = true,
+            "table_constants" => self.table_constants = true,
+            "column_enum" => self.column_enum = true,
+            "search" => self.search = true,
+            "id_newtypes" => self.id_newtypes = true,
+            "pretty" => self.pretty = true,
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Checks that no two enabled options conflict with each other.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.juniper && self.async_graphql {
+            return Err(Error::ConflictingOptions("juniper", "async_graphql"));
+        }
+
+        // The newtypes are not async-graphql types, so the objects using them could not derive
+        if self.id_newtypes && self.async_graphql {
+            return Err(Error::ConflictingOptions("async_graphql", "id_newtypes"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Warns about the parts of a schema the options can not generate, which are left out of the models.
+///
+/// The warnings are pushed onto the context the schema was parsed with, `input` being the parsed source.
+pub fn check_options(ctx: &mut Context, input: &str, schema: &Schema, options: Options) {
+    for decl in schema.items.iter().filter_map(|item| match item {
+        Item::Table(decl) => Some(decl),
+        _ => None,
+    }) {
+        let columns = decl
+            .columns
+            .iter()
+            .filter(|c| !c.skips("rust"))
+            .collect::<Vec<_>>();
+
+        if options.derive_default {
+            if let Some(column) = columns.iter().find(|c| default_value(c, None).is_none()) {
+                ctx.warning(
+                    "Missing default",
+                    str_range(input, column.name),
+                    format!(
+                        "`{}` does not implement `Default`, column `{}` has no default value",
+                        type_ident(decl.name, options),
+                        column.name
+                    ),
+                );
+            }
+        }
+
+        // SQLite has no arrays, so there is no way to read a table that uses them
+        #[cfg(feature = "sqlite")]
+        if let Some(column) = columns.iter().find(|c| matches!(c.typ, Types::Array(_))) {
+            ctx.warning(
+                "Unsupported column type",
+                str_range(input, column.name),
+                format!(
+                    "`{}` does not implement SQLite's `FromRow`, column `{}` is an array",
+                    type_ident(decl.name, options),
+                    column.name
+                ),
+            );
+        }
+    }
 }
 
 pub fn write_schema(
@@ -17,8 +151,15 @@ pub fn write_schema(
     writer: &mut impl io::Write,
     options: Options,
 ) -> Result<(), Error> {
+    options.check()?;
+
+    let ids = Ids::new(schema, options);
+
     for item in &schema.items {
-        write_item(item, writer, options)?;
+        match item {
+            Item::Table(decl) => write_table_in(decl, &ids, writer, options)?,
+            item => write_item(item, writer, options)?,
+        }
     }
 
     Ok(())
@@ -26,6 +167,7 @@ pub fn write_schema(
 
 pub fn write_item(item: &Item, writer: &mut impl io::Write, options: Options) -> Result<(), Error> {
     match &item {
+        Item::Comment(_) => {}
         Item::Enum(decl) => write_enum(decl, writer, options)?,
         Item::Table(decl) => write_table(decl, writer, options)?,
     }
@@ -34,54 +176,72 @@ pub fn write_item(item: &Item, writer: &mut impl io::Write, options: Options) ->
 }
 
 pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) -> Result<(), Error> {
-    let ident = quote::format_ident!("{}", decl.name);
+    let ident = type_ident(decl.name, options);
 
     let juniper_derive = if options.juniper {
-        if cfg!(feature = "feature-gate-juniper") {
-            quote::quote! {
-                #[cfg_attr(feature = "rewryte-juniper", derive(juniper::GraphQLEnum))]
-            }
-        } else {
-            quote::quote! {
-                #[derive(juniper::GraphQLEnum)]
-            }
-        }
+        gated_attr(
+            cfg!(feature = "feature-gate-juniper"),
+            "rewryte-juniper",
+            quote::quote! { derive(juniper::GraphQLEnum) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    let async_graphql_derive = if options.async_graphql {
+        gated_attr(
+            cfg!(feature = "feature-gate-async-graphql"),
+            "rewryte-async-graphql",
+            quote::quote! { derive(async_graphql::Enum) },
+        )
     } else {
         quote::quote! {}
     };
 
     let serde_derive = if options.serde {
-        if cfg!(feature = "feature-gate-serde") {
-            quote::quote! {
-                #[cfg_attr(feature = "rewryte-serde", derive(serde::Deserialize, serde::Serialize))]
-            }
-        } else {
-            quote::quote! {
-                #[derive(serde::Deserialize, serde::Serialize)]
-            }
-        }
+        gated_attr(
+            cfg!(feature = "feature-gate-serde"),
+            "rewryte-serde",
+            quote::quote! { derive(serde::Deserialize, serde::Serialize) },
+        )
     } else {
         quote::quote! {}
     };
 
     let sqlx_derive = if options.sqlx {
-        if cfg!(feature = "feature-gate-sqlx") {
-            quote::quote! {
-                #[cfg_attr(feature = "rewryte-sqlx", derive(sqlx::Type))]
-            }
-        } else {
-            quote::quote! {
-                #[derive(sqlx::Type)]
-            }
-        }
+        gated_attr(
+            cfg!(feature = "feature-gate-sqlx"),
+            "rewryte-sqlx",
+            quote::quote! { derive(sqlx::Type) },
+        )
     } else {
         quote::quote! {}
     };
 
+    let non_exhaustive = non_exhaustive_attr(options);
+    let rename = rename_attr(decl.name, &ident, options);
+
     let variants = decl
         .variants
         .iter()
-        .map(|v| quote::format_ident!("{}", v))
+        .map(|v| quote::format_ident!("{}", v.name))
+        .collect::<Vec<_>>();
+
+    let juniper_variants_description = decl
+        .variants
+        .iter()
+        .map(|v| match v.doc {
+            Some(doc) if options.juniper => {
+                let description = crate::doc_lines(doc).collect::<Vec<_>>().join("\n");
+
+                gated_attr(
+                    cfg!(feature = "feature-gate-juniper"),
+                    "rewryte-juniper",
+                    quote::quote! { graphql(description = #description) },
+                )
+            }
+            _ => quote::quote! {},
+        })
         .collect::<Vec<_>>();
 
     let serde_variants_rename = decl
@@ -89,83 +249,121 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) ->
         .iter()
         .map(|v| {
             if options.serde {
-                let kebab = v.to_kebab_case();
+                let kebab = v.name.to_kebab_case();
 
-                if cfg!(feature = "feature-gate-serde") {
-                    quote::quote! {
-                        #[cfg_attr(feature = "rewryte-serde", serde(rename = #kebab))]
-                    }
-                } else {
-                    quote::quote! {
-                        #[serde(rename = #kebab)]
-                    }
-                }
+                gated_attr(
+                    cfg!(feature = "feature-gate-serde"),
+                    "rewryte-serde",
+                    quote::quote! { serde(rename = #kebab) },
+                )
             } else {
                 quote::quote! {}
             }
         })
         .collect::<Vec<_>>();
 
-        let sqlx_variants_rename = decl
-            .variants
-            .iter()
-            .map(|v| {
-                if options.sqlx {
-                    let kebab = v.to_kebab_case();
-    
-                    if cfg!(feature = "feature-gate-sqlx") {
-                        quote::quote! {
-                            #[cfg_attr(feature = "rewryte-sqlx", sqlx(rename = #kebab))]
-                        }
-                    } else {
-                        quote::quote! {
-                            #[sqlx(rename = #kebab)]
-                        }
-                    }
-                } else {
-                    quote::quote! {}
-                }
-            })
-            .collect::<Vec<_>>();
+    let sqlx_variants_rename = decl
+        .variants
+        .iter()
+        .map(|v| {
+            if options.sqlx {
+                let kebab = v.name.to_kebab_case();
 
-    writeln!(
-        writer,
-        "{}",
+                gated_attr(
+                    cfg!(feature = "feature-gate-sqlx"),
+                    "rewryte-sqlx",
+                    quote::quote! { sqlx(rename = #kebab) },
+                )
+            } else {
+                quote::quote! {}
+            }
+        })
+        .collect::<Vec<_>>();
+
+    write_tokens(
         quote::quote! {
             #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
             #juniper_derive
+            #async_graphql_derive
             #serde_derive
             #sqlx_derive
+            #non_exhaustive
+            #rename
             pub enum #ident {
                 #(
+                    #juniper_variants_description
                     #serde_variants_rename
                     #sqlx_variants_rename
                     #variants,
                 )*
             }
-        }
+        },
+        writer,
+        options,
     )?;
 
-    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    // The variant names as written, the values PostgreSQL's enum type is created with, `Display` and `FromStr` use
+    // them as well. SQLite, serde, and sqlx use kebab-case instead, so only the PostgreSQL impls match these.
+    let variant_names = decl.variants.iter().map(|v| v.name).collect::<Vec<_>>();
+
     {
-        let variants_kebab = decl
-            .variants
-            .iter()
-            .map(|s| s.to_kebab_case())
-            .collect::<Vec<String>>();
+        let name = decl.name;
+        let idents = std::iter::repeat(ident.clone());
+
+        write_tokens(
+            quote::quote! {
+                impl ::std::fmt::Display for #ident {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        let s = match self {
+                            #(
+                                #idents::#variants => #variant_names,
+                            )*
+                        };
+
+                        f.write_str(s)
+                    }
+                }
+            },
+            writer,
+            options,
+        )?;
 
+        let idents = std::iter::repeat(ident.clone());
+
+        write_tokens(
+            quote::quote! {
+                impl ::std::str::FromStr for #ident {
+                    type Err = ::rewryte::ParseEnumError;
+
+                    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                        match s {
+                            #(
+                                #variant_names => ::std::result::Result::Ok(#idents::#variants),
+                            )*
+                            s => ::std::result::Result::Err(::rewryte::ParseEnumError {
+                                name: #name,
+                                value: ::std::string::ToString::to_string(s),
+                            }),
+                        }
+                    }
+                }
+            },
+            writer,
+            options,
+        )?;
+    }
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    {
         #[cfg(feature = "postgres")]
         {
+            // The unquoted type name is folded to lowercase when it is created, so it is compared ignoring case
             let name = decl.name;
             let idents = std::iter::repeat(ident.clone());
             let num_variants = decl.variants.len();
 
-            let variant_names = &decl.variants;
-
             {
-                writeln!(
-                    writer,
-                    "{}",
+                write_tokens(
                     quote::quote! {
                         impl<'r> ::rewryte::postgres::types::FromSql<'r> for #ident {
                             fn from_sql(_type: &::rewryte::postgres::types::Type, buf: &'r [u8]) -> ::std::result::Result<
@@ -174,7 +372,7 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) ->
                             > {
                                 match ::std::str::from_utf8(buf)? {
                                     #(
-                                        #variants_kebab => ::std::result::Result::Ok(#idents::#variants),
+                                        #variant_names => ::std::result::Result::Ok(#idents::#variants),
                                     )*
                                     s => {
                                         ::std::result::Result::Err(
@@ -185,7 +383,7 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) ->
                             }
 
                             fn accepts(type_: &::rewryte::postgres::types::Type) -> bool {
-                                if type_.name() != #name {
+                                if !type_.name().eq_ignore_ascii_case(#name) {
                                     return false;
                                 }
 
@@ -208,19 +406,82 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) ->
                                 }
                             }
                         }
-                    }
+                    },
+                    writer,
+                    options,
                 )?;
             }
         }
 
+        #[cfg(feature = "postgres")]
+        {
+            let name = decl.name;
+            let idents = std::iter::repeat(ident.clone());
+            let num_variants = decl.variants.len();
+
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::postgres::types::ToSql for #ident {
+                        fn to_sql(&self, _type: &::rewryte::postgres::types::Type, out: &mut ::rewryte::postgres::types::private::BytesMut) -> ::std::result::Result<
+                            ::rewryte::postgres::types::IsNull,
+                            ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>
+                        > {
+                            let s = match self {
+                                #(
+                                    #idents::#variants => #variant_names,
+                                )*
+                            };
+
+                            out.extend_from_slice(s.as_bytes());
+
+                            ::std::result::Result::Ok(::rewryte::postgres::types::IsNull::No)
+                        }
+
+                        fn accepts(type_: &::rewryte::postgres::types::Type) -> bool {
+                            if !type_.name().eq_ignore_ascii_case(#name) {
+                                return false;
+                            }
+
+                            match *type_.kind() {
+                                ::rewryte::postgres::types::Kind::Enum(ref variants) => {
+                                    if variants.len() != #num_variants {
+                                        return false;
+                                    }
+
+                                    variants.iter().all(|v| {
+                                        match &**v {
+                                            #(
+                                                #variant_names => true,
+                                            )*
+                                            _ => false,
+                                        }
+                                    })
+                                }
+                                _ => false,
+                            }
+                        }
+
+                        ::rewryte::postgres::types::to_sql_checked!();
+                    }
+                },
+                writer,
+                options,
+            )?;
+        }
+
         #[cfg(feature = "sqlite")]
         {
+            // SQLite has no enum type, the variants are stored as kebab-case text
+            let variants_kebab = decl
+                .variants
+                .iter()
+                .map(|v| v.name.to_kebab_case())
+                .collect::<Vec<String>>();
+
             {
                 let idents = std::iter::repeat(ident.clone());
 
-                writeln!(
-                    writer,
-                    "{}",
+                write_tokens(
                     quote::quote! {
                         impl ::rewryte::sqlite::types::ToSql for #ident {
                             fn to_sql(&self) -> ::rewryte::sqlite::Result<::rewryte::sqlite::types::ToSqlOutput> {
@@ -231,16 +492,16 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) ->
                                 }
                             }
                         }
-                    }
+                    },
+                    writer,
+                    options,
                 )?;
             }
 
             {
                 let idents = std::iter::repeat(ident.clone());
 
-                writeln!(
-                    writer,
-                    "{}",
+                write_tokens(
                     quote::quote! {
                         impl ::rewryte::sqlite::types::FromSql for #ident {
                             fn column_result(value: ::rewryte::sqlite::types::ValueRef) -> ::rewryte::sqlite::types::FromSqlResult<Self> {
@@ -252,7 +513,9 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) ->
                                 })
                             }
                         }
-                    }
+                    },
+                    writer,
+                    options,
                 )?;
             }
         }
@@ -261,131 +524,743 @@ pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) ->
     Ok(())
 }
 
-pub fn write_table(
+/// The tables with a single column primary key, the keys that get an id newtype with `id_newtypes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ids<'a> {
+    keys: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Ids<'a> {
+    pub fn new(schema: &Schema<'a>, options: Options) -> Self {
+        Self::from_tables(
+            schema.items.iter().filter_map(|item| match item {
+                Item::Table(decl) => Some(decl),
+                _ => None,
+            }),
+            options,
+        )
+    }
+
+    pub fn from_tables<'t>(
+        tables: impl IntoIterator<Item = &'t Table<'a>>,
+        options: Options,
+    ) -> Self
+    where
+        'a: 't,
+    {
+        let keys = tables
+            .into_iter()
+            .filter_map(|decl| id_column(decl, options).map(|column| (decl.name, column.name)))
+            .collect();
+
+        Ids { keys }
+    }
+
+    /// The newtype a column is generated as, the table's own if it is its key, or the referenced table's.
+    pub fn newtype(
+        &self,
+        decl: &Table,
+        column: &Column,
+        options: Options,
+    ) -> Option<proc_macro2::Ident> {
+        if !options.id_newtypes {
+            return None;
+        }
+
+        if self.keys.contains(&(decl.name, column.name)) {
+            return Some(id_ident(decl.name, options));
+        }
+
+        decl.foreign_keys.iter().find_map(|foreign_key| {
+            match (foreign_key.local.as_slice(), foreign_key.foreign.as_slice()) {
+                ([local], [foreign])
+                    if *local == column.name
+                        && self.keys.contains(&(foreign_key.table, foreign)) =>
+                {
+                    Some(id_ident(foreign_key.table, options))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+/// The table's primary key if it can be wrapped in a newtype, it has to be a single column that is `Eq` and `Hash`.
+///
+/// With juniper it also has to be a type juniper has a scalar for, otherwise the objects holding it could not derive.
+fn id_column<'t, 'a>(decl: &'t Table<'a>, options: Options) -> Option<&'t Column<'a>> {
+    match decl.primary_keys.as_slice() {
+        [key] => decl
+            .columns
+            .iter()
+            .find(|c| c.name == *key)
+            .filter(|c| !is_float(&c.typ) && !matches!(c.typ, Types::Array(_)))
+            .filter(|c| !options.juniper || is_juniper_scalar(&c.typ)),
+        _ => None,
+    }
+}
+
+/// Checks if juniper 0.14 has a scalar for a key type, there is none for the other integer widths, and `Uuid` and the
+/// chrono types need its `uuid` and `chrono` features.
+fn is_juniper_scalar(typ: &Types) -> bool {
+    matches!(
+        typ,
+        Types::Number
+            | Types::Int
+            | Types::Serial
+            | Types::MediumInt
+            | Types::Varchar(_)
+            | Types::Text
+            | Types::Uuid
+            | Types::DateTime
+            | Types::Date
+            | Types::Time
+    )
+}
+
+fn id_ident(table: &str, options: Options) -> proc_macro2::Ident {
+    quote::format_ident!("{}Id", type_ident(table, options))
+}
+
+fn column_enum_ident(table: &str, options: Options) -> proc_macro2::Ident {
+    quote::format_ident!("{}Column", type_ident(table, options))
+}
+
+/// Writes the enum naming a table's columns, skipped columns are still in the database so they are included.
+fn write_column_enum(
     decl: &Table,
     writer: &mut impl io::Write,
     options: Options,
 ) -> Result<(), Error> {
-    let ident = quote::format_ident!("{}", decl.name);
+    let ident = column_enum_ident(decl.name, options);
+    let non_exhaustive = non_exhaustive_attr(options);
 
-    let juniper_derive = if options.juniper {
-        if cfg!(feature = "feature-gate-juniper") {
-            quote::quote! {
-                #[cfg_attr(feature = "rewryte-juniper", derive(juniper::GraphQLObject))]
+    let variants = decl
+        .columns
+        .iter()
+        .map(|c| quote::format_ident!("{}", c.name.to_camel_case()))
+        .collect::<Vec<_>>();
+
+    let names = decl.columns.iter().map(|c| c.name);
+
+    write_tokens(
+        quote::quote! {
+            #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+            #non_exhaustive
+            pub enum #ident {
+                #( #variants, )*
             }
-        } else {
-            quote::quote! {
-                #[derive(juniper::GraphQLObject)]
+
+            impl #ident {
+                /// Every column, in the order they are declared.
+                pub const ALL: &'static [#ident] = &[#( #ident::#variants ),*];
+
+                /// The name of the column in the database.
+                pub fn as_str(&self) -> &'static str {
+                    match self {
+                        #( #ident::#variants => #names, )*
+                    }
+                }
             }
+
+            impl ::std::fmt::Display for #ident {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(self.as_str())
+                }
+            }
+        },
+        writer,
+        options,
+    )
+}
+
+/// Writes the id newtype of a table, along with the database conversions of its inner type.
+fn write_id_newtype(
+    decl: &Table,
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    let column = match id_column(decl, options) {
+        Some(column) => column,
+        None => return Ok(()),
+    };
+
+    let ident = id_ident(decl.name, options);
+    let inner = field_type(&column.typ, options);
+
+    let derive = if matches!(column.typ, Types::Varchar(_) | Types::Text) {
+        quote::quote! { #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)] }
+    } else {
+        quote::quote! { #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)] }
+    };
+
+    let serde = if options.serde {
+        let derive = gated_attr(
+            cfg!(feature = "feature-gate-serde"),
+            "rewryte-serde",
+            quote::quote! { derive(serde::Deserialize, serde::Serialize) },
+        );
+        let transparent = gated_attr(
+            cfg!(feature = "feature-gate-serde"),
+            "rewryte-serde",
+            quote::quote! { serde(transparent) },
+        );
+
+        quote::quote! {
+            #derive
+            #transparent
         }
     } else {
         quote::quote! {}
     };
 
-    let serde_derive = if options.serde {
-        if cfg!(feature = "feature-gate-serde") {
-            quote::quote! {
-                #[cfg_attr(feature = "rewryte-serde", derive(serde::Deserialize, serde::Serialize))]
-            }
-        } else {
-            quote::quote! {
-                #[derive(serde::Deserialize, serde::Serialize)]
-            }
-        }
+    // A scalar with the inner value's representation, so objects holding the newtype can still derive
+    let juniper = if options.juniper {
+        gated_attr(
+            cfg!(feature = "feature-gate-juniper"),
+            "rewryte-juniper",
+            quote::quote! { derive(juniper::GraphQLScalarValue) },
+        )
     } else {
         quote::quote! {}
     };
 
-    let sqlx_derive = if options.sqlx {
-        if cfg!(feature = "feature-gate-sqlx") {
-            quote::quote! {
-                #[cfg_attr(feature = "rewryte-sqlx", derive(sqlx::FromRow))]
-            }
-        } else {
-            quote::quote! {
-                #[derive(sqlx::FromRow)]
-            }
+    let sqlx = if options.sqlx {
+        let derive = gated_attr(
+            cfg!(feature = "feature-gate-sqlx"),
+            "rewryte-sqlx",
+            quote::quote! { derive(sqlx::Type) },
+        );
+        let transparent = gated_attr(
+            cfg!(feature = "feature-gate-sqlx"),
+            "rewryte-sqlx",
+            quote::quote! { sqlx(transparent) },
+        );
+
+        quote::quote! {
+            #derive
+            #transparent
         }
     } else {
         quote::quote! {}
     };
 
-    let field_names = decl
-        .columns
-        .iter()
-        .map(|c| quote::format_ident!("{}", c.name.to_snake_case()))
-        .collect::<Vec<_>>();
+    write_tokens(
+        quote::quote! {
+            #derive
+            #juniper
+            #serde
+            #sqlx
+            #[repr(transparent)]
+            pub struct #ident(pub #inner);
+        },
+        writer,
+        options,
+    )?;
 
-    let field_types = decl
-        .columns
-        .iter()
-        .map(|c| {
-            (
-                c.null,
-                match c.typ {
-                    Types::Char => quote::quote! { char },
-                    Types::Varchar | Types::Text => quote::quote! { ::std::string::String },
-                    Types::Number | Types::Int | Types::Serial | Types::MediumInt => {
-                        quote::quote! { i32 }
+    #[cfg(feature = "postgres")]
+    {
+        write_tokens(
+            quote::quote! {
+                impl<'r> ::rewryte::postgres::types::FromSql<'r> for #ident {
+                    fn from_sql(type_: &::rewryte::postgres::types::Type, buf: &'r [u8]) -> ::std::result::Result<
+                        #ident,
+                        ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>
+                    > {
+                        <#inner as ::rewryte::postgres::types::FromSql<'r>>::from_sql(type_, buf).map(#ident)
                     }
-                    Types::SmallInt => quote::quote! { i16 },
-                    Types::BigInt => quote::quote! { i64 },
-                    Types::Float | Types::Real | Types::Decimal => quote::quote! { f64 },
-                    Types::Numeric => quote::quote! { f32 },
-                    Types::DateTime => quote::quote! { ::chrono::DateTime<chrono::Utc> },
-                    Types::Boolean => quote::quote! { bool },
-                    Types::Raw(raw) => {
-                        let raw_ident = quote::format_ident!("{}", raw);
-
-                        quote::quote! { #raw_ident }
+
+                    fn accepts(type_: &::rewryte::postgres::types::Type) -> bool {
+                        <#inner as ::rewryte::postgres::types::FromSql<'r>>::accepts(type_)
                     }
-                },
-            )
-        })
-        .map(|(null, t)| {
-            if null {
-                quote::quote! { ::std::option::Option<#t> }
-            } else {
-                t
-            }
-        })
-        .collect::<Vec<_>>();
+                }
+            },
+            writer,
+            options,
+        )?;
 
-    writeln!(
-        writer,
-        "{}",
-        quote::quote! {
-            #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-            #juniper_derive
-            #serde_derive
-            #sqlx_derive
-            pub struct #ident {
-                #(
+        write_tokens(
+            quote::quote! {
+                impl ::rewryte::postgres::types::ToSql for #ident {
+                    fn to_sql(&self, type_: &::rewryte::postgres::types::Type, out: &mut ::rewryte::postgres::types::private::BytesMut) -> ::std::result::Result<
+                        ::rewryte::postgres::types::IsNull,
+                        ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>
+                    > {
+                        ::rewryte::postgres::types::ToSql::to_sql(&self.0, type_, out)
+                    }
+
+                    fn accepts(type_: &::rewryte::postgres::types::Type) -> bool {
+                        <#inner as ::rewryte::postgres::types::ToSql>::accepts(type_)
+                    }
+
+                    ::rewryte::postgres::types::to_sql_checked!();
+                }
+            },
+            writer,
+            options,
+        )?;
+    }
+
+    #[cfg(feature = "sqlite")]
+    {
+        write_tokens(
+            quote::quote! {
+                impl ::rewryte::sqlite::types::ToSql for #ident {
+                    fn to_sql(&self) -> ::rewryte::sqlite::Result<::rewryte::sqlite::types::ToSqlOutput> {
+                        ::rewryte::sqlite::types::ToSql::to_sql(&self.0)
+                    }
+                }
+            },
+            writer,
+            options,
+        )?;
+
+        write_tokens(
+            quote::quote! {
+                impl ::rewryte::sqlite::types::FromSql for #ident {
+                    fn column_result(value: ::rewryte::sqlite::types::ValueRef) -> ::rewryte::sqlite::types::FromSqlResult<Self> {
+                        <#inner as ::rewryte::sqlite::types::FromSql>::column_result(value).map(#ident)
+                    }
+                }
+            },
+            writer,
+            options,
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn write_table(
+    decl: &Table,
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    write_table_in(
+        decl,
+        &Ids::from_tables(std::iter::once(decl), options),
+        writer,
+        options,
+    )
+}
+
+/// Writes a table, its foreign keys use the id newtypes of the tables in `id_newtypes`.
+pub fn write_table_in(
+    decl: &Table,
+    id_newtypes: &Ids,
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    let ident = type_ident(decl.name, options);
+
+    if options.id_newtypes {
+        write_id_newtype(decl, writer, options)?;
+    }
+
+    let juniper_derive = if options.juniper {
+        gated_attr(
+            cfg!(feature = "feature-gate-juniper"),
+            "rewryte-juniper",
+            quote::quote! { derive(juniper::GraphQLObject) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    let async_graphql_derive = if options.async_graphql {
+        gated_attr(
+            cfg!(feature = "feature-gate-async-graphql"),
+            "rewryte-async-graphql",
+            quote::quote! { derive(async_graphql::SimpleObject) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    let serde_derive = if options.serde {
+        gated_attr(
+            cfg!(feature = "feature-gate-serde"),
+            "rewryte-serde",
+            quote::quote! { derive(serde::Deserialize, serde::Serialize) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    let sqlx_derive = if options.sqlx {
+        gated_attr(
+            cfg!(feature = "feature-gate-sqlx"),
+            "rewryte-sqlx",
+            quote::quote! { derive(sqlx::FromRow) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    let non_exhaustive = non_exhaustive_attr(options);
+    let rename = rename_attr(decl.name, &ident, options);
+
+    let columns = decl
+        .columns
+        .iter()
+        .filter(|c| !c.skips("rust"))
+        .collect::<Vec<_>>();
+
+    let field_names = columns
+        .iter()
+        .map(|c| match c.rust_name {
+            Some(name) => quote::format_ident!("{}", name),
+            None => quote::format_ident!("{}", c.name.to_snake_case()),
+        })
+        .collect::<Vec<_>>();
+
+    let newtypes = columns
+        .iter()
+        .map(|c| id_newtypes.newtype(decl, c, options))
+        .collect::<Vec<_>>();
+
+    let field_types = columns
+        .iter()
+        .zip(newtypes.iter())
+        .map(|(c, newtype)| match newtype {
+            Some(newtype) => (c.null, quote::quote! { #newtype }),
+            None => (c.null, field_type(&c.typ, options)),
+        })
+        .map(|(null, t)| {
+            if null {
+                quote::quote! { ::std::option::Option<#t> }
+            } else {
+                t
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let field_attrs = columns
+        .iter()
+        .zip(field_names.iter())
+        .zip(newtypes.iter())
+        .map(|((c, name), newtype)| {
+            let skip = if options.juniper && c.skips("graphql") {
+                gated_attr(
+                    cfg!(feature = "feature-gate-juniper"),
+                    "rewryte-juniper",
+                    quote::quote! { graphql(skip) },
+                )
+            } else if options.async_graphql && c.skips("graphql") {
+                gated_attr(
+                    cfg!(feature = "feature-gate-async-graphql"),
+                    "rewryte-async-graphql",
+                    quote::quote! { graphql(skip) },
+                )
+            } else {
+                quote::quote! {}
+            };
+
+            // The name the field has without `as`, so it is serialized like every other field
+            let rename = if c.rust_name.is_some() {
+                rename_attr(&c.name.to_snake_case(), name, options)
+            } else {
+                quote::quote! {}
+            };
+
+            let skip_none = if options.serde && options.skip_none && c.null {
+                gated_attr(
+                    cfg!(feature = "feature-gate-serde"),
+                    "rewryte-serde",
+                    quote::quote! { serde(skip_serializing_if = "Option::is_none") },
+                )
+            } else {
+                quote::quote! {}
+            };
+
+            let serde_default = match serde_default_value(c, newtype.as_ref(), options) {
+                Some(_) => {
+                    let path = format!("{}::{}", ident, serde_default_ident(name));
+
+                    gated_attr(
+                        cfg!(feature = "feature-gate-serde"),
+                        "rewryte-serde",
+                        quote::quote! { serde(default = #path) },
+                    )
+                }
+                None => quote::quote! {},
+            };
+
+            quote::quote! {
+                #skip
+                #rename
+                #skip_none
+                #serde_default
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Floats are not `Eq`, `Ord`, or `Hash`, so a table with one only gets the partial traits
+    let derive = if columns.iter().any(|c| is_float(&c.typ)) {
+        quote::quote! { #[derive(Clone, Debug, PartialEq, PartialOrd)] }
+    } else {
+        quote::quote! { #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)] }
+    };
+
+    write_tokens(
+        quote::quote! {
+            #derive
+            #juniper_derive
+            #async_graphql_derive
+            #serde_derive
+            #sqlx_derive
+            #non_exhaustive
+            #rename
+            pub struct #ident {
+                #(
+                    #field_attrs
                     pub #field_names: #field_types,
                 )*
             }
-        }
+        },
+        writer,
+        options,
     )?;
 
+    if options.derive_default {
+        write_default(&columns, &newtypes, &ident, &field_names, writer, options)?;
+    }
+
+    let serde_defaults = columns
+        .iter()
+        .zip(&newtypes)
+        .zip(&field_names)
+        .zip(&field_types)
+        .filter_map(|(((c, newtype), name), typ)| {
+            let value = serde_default_value(c, newtype.as_ref(), options)?;
+            let default = serde_default_ident(name);
+
+            Some(quote::quote! {
+                #[doc(hidden)]
+                #[allow(dead_code)]
+                fn #default() -> #typ {
+                    #value
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if !serde_defaults.is_empty() {
+        write_tokens(
+            quote::quote! {
+                impl #ident {
+                    #( #serde_defaults )*
+                }
+            },
+            writer,
+            options,
+        )?;
+    }
+
+    if options.constructors {
+        write_constructor(
+            &columns,
+            &newtypes,
+            &ident,
+            &field_names,
+            &field_types,
+            writer,
+            options,
+        )?;
+    }
+
+    let version = columns.iter().position(|c| c.version);
+
+    if let Some(version) = version {
+        let name = &field_names[version];
+
+        write_tokens(
+            quote::quote! {
+                impl #ident {
+                    /// Checks if a freshly fetched row still has the version this one was read with.
+                    pub fn same_version(&self, current: &Self) -> bool {
+                        self.#name == current.#name
+                    }
+                }
+            },
+            writer,
+            options,
+        )?;
+    }
+
+    if options.table_constants {
+        let table_name = decl.name;
+
+        // Skipped columns are still in the database, so they are listed as well
+        let column_names = decl.columns.iter().map(|c| c.name);
+
+        write_tokens(
+            quote::quote! {
+                impl #ident {
+                    /// The name of the table in the database.
+                    pub const TABLE: &'static str = #table_name;
+
+                    /// The names of the table's columns in the database, in the order they are declared.
+                    pub const COLUMNS: &'static [&'static str] = &[#( #column_names ),*];
+                }
+            },
+            writer,
+            options,
+        )?;
+    }
+
+    if options.column_enum {
+        write_column_enum(decl, writer, options)?;
+    }
+
     #[cfg(any(feature = "postgres", feature = "sqlite"))]
     {
-        let ids = (0..(decl.columns.len())).map(|n| n).collect::<Vec<usize>>();
+        // Skipped columns are still in the row, so these are the indexes of the table's columns
+        let ids = columns
+            .iter()
+            .filter_map(|c| decl.column_index(c.name))
+            .collect::<Vec<usize>>();
+
+        // Every column is listed by name so the row always matches the indexes above, whatever order the
+        // columns are in the database
+        let select = format!(
+            "SELECT {} FROM {}",
+            decl.columns
+                .iter()
+                .map(|c| c.name)
+                .collect::<Vec<_>>()
+                .join(", "),
+            decl.name
+        );
+
+        write_tokens(
+            quote::quote! {
+                impl #ident {
+                    /// Selects every column in the order `FromRow` reads them.
+                    pub const SELECT: &'static str = #select;
+                }
+            },
+            writer,
+            options,
+        )?;
+
+        let searchable = decl
+            .columns
+            .iter()
+            .filter(|c| c.searchable)
+            .collect::<Vec<_>>();
+
+        // Skipped key columns are still in the table, so the key is read from every column
+        let keys = decl
+            .primary_keys
+            .iter()
+            .filter_map(|name| decl.columns.iter().find(|c| c.name == *name))
+            .collect::<Vec<_>>();
+
+        let key_type = match &keys[..] {
+            [key] => match id_newtypes.newtype(decl, key, options) {
+                Some(newtype) => quote::quote! { #newtype },
+                None => field_type(&key.typ, options),
+            },
+            keys => {
+                let types = keys.iter().map(|c| field_type(&c.typ, options));
+
+                quote::quote! { ( #( #types ),* ) }
+            }
+        };
+
+        let key_params = if keys.len() == 1 {
+            vec![quote::quote! { key }]
+        } else {
+            (0..keys.len())
+                .map(proc_macro2::Literal::usize_unsuffixed)
+                .map(|i| quote::quote! { &key.#i })
+                .collect::<Vec<_>>()
+        };
+
+        // The key and version are bound from `to_params` by their position, so they can not be skipped.
+        // A date and time version is compared through `normalize` if given, for databases storing it as text.
+        let update_if_unchanged =
+            |placeholder: &str, now: &str, normalize: Option<&str>| -> Option<String> {
+                let version = version?;
+
+                let keys = decl
+                    .primary_keys
+                    .iter()
+                    .map(|name| columns.iter().position(|c| c.name == *name))
+                    .collect::<Option<Vec<_>>>()?;
+
+                if keys.is_empty() {
+                    return None;
+                }
+
+                let column = columns[version];
+
+                let bump = if column.typ == Types::DateTime {
+                    format!("{} = {}", column.name, now)
+                } else {
+                    format!("{0} = {0} + 1", column.name)
+                };
+
+                let sets = columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != version && !keys.contains(i))
+                    .map(|(i, c)| format!("{} = {}{}", c.name, placeholder, i + 1))
+                    .chain(std::iter::once(bump))
+                    .collect::<Vec<_>>();
+
+                let conditions = keys
+                    .iter()
+                    .map(|i| format!("{} = {}{}", columns[*i].name, placeholder, i + 1))
+                    .chain(std::iter::once(match normalize {
+                        Some(normalize) if column.typ == Types::DateTime => format!(
+                            "{1}({0}) = {1}({2}{3})",
+                            column.name,
+                            normalize,
+                            placeholder,
+                            version + 1
+                        ),
+                        _ => format!("{} = {}{}", column.name, placeholder, version + 1),
+                    }))
+                    .collect::<Vec<_>>();
+
+                Some(format!(
+                    "UPDATE {} SET {} WHERE {}",
+                    decl.name,
+                    sets.join(", "),
+                    conditions.join(" AND ")
+                ))
+            };
+
+        let find = |placeholder: &str| {
+            format!(
+                "{} WHERE {}",
+                select,
+                keys.iter()
+                    .enumerate()
+                    .map(|(i, c)| format!("{} = {}{}", c.name, placeholder, i + 1))
+                    .collect::<Vec<_>>()
+                    .join(" AND ")
+            )
+        };
+
         let messages = ids
             .iter()
-            .map(|n| {
-                format!(
-                    "Failed to get data for row index {}: `{}`",
-                    n,
-                    decl.columns[*n].name.to_snake_case()
-                )
-            })
+            .zip(field_names.iter())
+            .map(|(n, name)| format!("Failed to get data for row index {}: `{}`", n, name))
+            .collect::<Vec<_>>();
+
+        // Looked up as `{prefix}_{field}`, both drivers ignore case when finding a column by name, so it does not matter
+        // that PostgreSQL lowercases unquoted aliases
+        let prefixed_names = columns
+            .iter()
+            .map(|c| c.name.to_snake_case())
             .collect::<Vec<_>>();
 
         #[cfg(feature = "postgres")]
         {
-            writeln!(
-                writer,
-                "{}",
+            write_tokens(
                 quote::quote! {
                     impl ::rewryte::postgres::FromRow for #ident {
                         fn from_row(row: ::rewryte::postgres::Row) -> ::anyhow::Result<Self>
@@ -401,18 +1276,15 @@ pub fn write_table(
                             })
                         }
                     }
-                }
+                },
+                writer,
+                options,
             )?;
-        }
 
-        #[cfg(feature = "sqlite")]
-        {
-            writeln!(
-                writer,
-                "{}",
+            write_tokens(
                 quote::quote! {
-                    impl ::rewryte::sqlite::FromRow for #ident {
-                        fn from_row(row: &::rewryte::sqlite::Row<'_>) -> ::anyhow::Result<Self>
+                    impl ::rewryte::postgres::FromRowPrefixed for #ident {
+                        fn from_row_prefixed(row: &::rewryte::postgres::Row, prefix: &str) -> ::anyhow::Result<Self>
                         where
                             Self: Sized,
                         {
@@ -420,15 +1292,2726 @@ pub fn write_table(
 
                             ::std::result::Result::Ok(Self {
                                 #(
-                                    #field_names: row.get(#ids).context(#messages)?,
+                                    #field_names: row
+                                        .try_get(::std::format!("{}_{}", prefix, #prefixed_names).as_str())
+                                        .with_context(|| ::std::format!("Failed to get data for column `{}_{}`", prefix, #prefixed_names))?,
                                 )*
                             })
                         }
                     }
-                }
+                },
+                writer,
+                options,
             )?;
         }
-    }
 
-    Ok(())
+        #[cfg(feature = "postgres")]
+        if options.try_from_row {
+            write_tokens(
+                quote::quote! {
+                    impl ::std::convert::TryFrom<::rewryte::postgres::Row> for #ident {
+                        type Error = ::anyhow::Error;
+
+                        fn try_from(row: ::rewryte::postgres::Row) -> ::anyhow::Result<Self> {
+                            <Self as ::rewryte::postgres::FromRow>::from_row(row)
+                        }
+                    }
+                },
+                writer,
+                options,
+            )?;
+        }
+
+        #[cfg(feature = "postgres")]
+        if options.search && !searchable.is_empty() {
+            // Null columns would make the whole document null, so they are searched as empty text
+            let document = searchable
+                .iter()
+                .map(|c| {
+                    if c.null {
+                        format!("coalesce({}, '')", c.name)
+                    } else {
+                        c.name.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" || ' ' || ");
+
+            let search = format!(
+                "{} WHERE to_tsvector({}) @@ plainto_tsquery($1)",
+                select, document
+            );
+
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::postgres::Search for #ident {
+                        const SEARCH: &'static str = #search;
+                    }
+                },
+                writer,
+                options,
+            )?;
+        }
+
+        #[cfg(feature = "postgres")]
+        {
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::postgres::ToParams for #ident {
+                        fn to_params(&self) -> ::std::vec::Vec<&(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync)> {
+                            ::std::vec![
+                                #(
+                                    &self.#field_names as &(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync),
+                                )*
+                            ]
+                        }
+                    }
+                },
+                writer,
+                options,
+            )?;
+        }
+
+        #[cfg(feature = "postgres")]
+        if let Some(update) = update_if_unchanged("$", "timezone('utc', now())", None) {
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::postgres::Versioned for #ident {
+                        const UPDATE_IF_UNCHANGED: &'static str = #update;
+                    }
+                },
+                writer,
+                options,
+            )?;
+        }
+
+        #[cfg(feature = "postgres")]
+        if !keys.is_empty() {
+            let find = find("$");
+
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::postgres::Find for #ident {
+                        type Key = #key_type;
+
+                        const FIND: &'static str = #find;
+
+                        fn key_params(key: &Self::Key) -> ::std::vec::Vec<&(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync)> {
+                            ::std::vec![
+                                #(
+                                    #key_params as &(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync),
+                                )*
+                            ]
+                        }
+                    }
+                },
+                writer,
+                options,
+            )?;
+        }
+
+        // SQLite has no arrays, so there is no way to read a table that uses them
+        #[cfg(feature = "sqlite")]
+        if !columns.iter().any(|c| matches!(c.typ, Types::Array(_))) {
+            let getters = columns
+                .iter()
+                .zip(ids.iter())
+                .zip(messages.iter())
+                .map(|((column, id), message)| {
+                    sqlite_getter(
+                        column,
+                        quote::quote! { #id },
+                        quote::quote! { context(#message) },
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let prefixed_getters = columns
+                .iter()
+                .zip(prefixed_names.iter())
+                .map(|(column, name)| {
+                    sqlite_getter(
+                        column,
+                        quote::quote! { ::std::format!("{}_{}", prefix, #name).as_str() },
+                        quote::quote! {
+                            with_context(|| ::std::format!("Failed to get data for column `{}_{}`", prefix, #name))
+                        },
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::sqlite::FromRow for #ident {
+                        fn from_row(row: &::rewryte::sqlite::Row<'_>) -> ::anyhow::Result<Self>
+                        where
+                            Self: Sized,
+                        {
+                            use ::anyhow::Context;
+
+                            ::std::result::Result::Ok(Self {
+                                #(
+                                    #field_names: #getters,
+                                )*
+                            })
+                        }
+                    }
+                },
+                writer,
+                options,
+            )?;
+
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::sqlite::FromRowPrefixed for #ident {
+                        fn from_row_prefixed(row: &::rewryte::sqlite::Row<'_>, prefix: &str) -> ::anyhow::Result<Self>
+                        where
+                            Self: Sized,
+                        {
+                            use ::anyhow::Context;
+
+                            ::std::result::Result::Ok(Self {
+                                #(
+                                    #field_names: #prefixed_getters,
+                                )*
+                            })
+                        }
+                    }
+                },
+                writer,
+                options,
+            )?;
+
+            if options.try_from_row {
+                write_tokens(
+                    quote::quote! {
+                        impl<'r, 's> ::std::convert::TryFrom<&'r ::rewryte::sqlite::Row<'s>> for #ident {
+                            type Error = ::anyhow::Error;
+
+                            fn try_from(row: &'r ::rewryte::sqlite::Row<'s>) -> ::anyhow::Result<Self> {
+                                <Self as ::rewryte::sqlite::FromRow>::from_row(row)
+                            }
+                        }
+                    },
+                    writer,
+                    options,
+                )?;
+            }
+
+            if options.search && !searchable.is_empty() {
+                let search = format!(
+                    "{} WHERE {}",
+                    select,
+                    searchable
+                        .iter()
+                        .map(|c| format!("{} LIKE '%' || ?1 || '%' ESCAPE '\\'", c.name))
+                        .collect::<Vec<_>>()
+                        .join(" OR ")
+                );
+
+                write_tokens(
+                    quote::quote! {
+                        impl ::rewryte::sqlite::Search for #ident {
+                            const SEARCH: &'static str = #search;
+                        }
+                    },
+                    writer,
+                    options,
+                )?;
+            }
+
+            let params = columns
+                .iter()
+                .zip(field_names.iter())
+                .map(|(column, name)| match (&column.typ, column.null) {
+                    (Types::Float, false) | (Types::Real, false) => quote::quote! {
+                        ::std::boxed::Box::new(f64::from(self.#name))
+                    },
+                    (Types::Float, true) | (Types::Real, true) => quote::quote! {
+                        ::std::boxed::Box::new(self.#name.map(f64::from))
+                    },
+                    _ => quote::quote! {
+                        ::std::boxed::Box::new(&self.#name)
+                    },
+                })
+                .collect::<Vec<_>>();
+
+            write_tokens(
+                quote::quote! {
+                    impl ::rewryte::sqlite::ToParams for #ident {
+                        fn to_params(&self) -> ::std::vec::Vec<::std::boxed::Box<dyn ::rewryte::sqlite::types::ToSql + '_>> {
+                            ::std::vec![
+                                #(
+                                    #params as ::std::boxed::Box<dyn ::rewryte::sqlite::types::ToSql + '_>,
+                                )*
+                            ]
+                        }
+                    }
+                },
+                writer,
+                options,
+            )?;
+
+            // The version is written as text, which chrono formats differently than `DATETIME`
+            if let Some(update) =
+                update_if_unchanged("?", "DATETIME('now', 'utc')", Some("DATETIME"))
+            {
+                write_tokens(
+                    quote::quote! {
+                        impl ::rewryte::sqlite::Versioned for #ident {
+                            const UPDATE_IF_UNCHANGED: &'static str = #update;
+                        }
+                    },
+                    writer,
+                    options,
+                )?;
+            }
+
+            // The key is bound as it is, so a 32-bit float key could not be widened like the other parameters
+            if !keys.is_empty()
+                && !keys
+                    .iter()
+                    .any(|c| matches!(c.typ, Types::Float | Types::Real))
+            {
+                let find = find("?");
+
+                write_tokens(
+                    quote::quote! {
+                        impl ::rewryte::sqlite::Find for #ident {
+                            type Key = #key_type;
+
+                            const FIND: &'static str = #find;
+
+                            fn key_params(key: &Self::Key) -> ::std::vec::Vec<&dyn ::rewryte::sqlite::types::ToSql> {
+                                ::std::vec![
+                                    #(
+                                        #key_params as &dyn ::rewryte::sqlite::types::ToSql,
+                                    )*
+                                ]
+                            }
+                        }
+                    },
+                    writer,
+                    options,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a column from a SQLite row, SQLite only stores 64-bit floats so 32-bit fields are narrowed after reading them.
+#[cfg(feature = "sqlite")]
+fn sqlite_getter(column: &Column, index: TokenStream, context: TokenStream) -> TokenStream {
+    match (&column.typ, column.null) {
+        (Types::Float, false) | (Types::Real, false) => quote::quote! {
+            row.get::<_, f64>(#index).map(|value| value as f32).#context?
+        },
+        (Types::Float, true) | (Types::Real, true) => quote::quote! {
+            row.get::<_, ::std::option::Option<f64>>(#index)
+                .map(|value| value.map(|value| value as f32))
+                .#context?
+        },
+        _ => quote::quote! {
+            row.get(#index).#context?
+        },
+    }
+}
+
+/// Writes a generated item, passing it through `prettyplease` first if pretty output is enabled.
+pub(crate) fn write_tokens(
+    tokens: TokenStream,
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    #[cfg(feature = "pretty")]
+    if options.pretty {
+        let file = syn::parse2::<syn::File>(tokens)?;
+
+        write!(writer, "{}", prettyplease::unparse(&file))?;
+
+        return Ok(());
+    }
+
+    #[cfg(not(feature = "pretty"))]
+    let _ = options;
+
+    writeln!(writer, "{}", tokens)?;
+
+    Ok(())
+}
+
+/// Writes a `Default` impl that mirrors the database defaults.
+///
+/// Nothing is written if a column is not nullable and has no default that can be turned into a Rust value.
+fn write_default(
+    columns: &[&Column],
+    newtypes: &[Option<proc_macro2::Ident>],
+    ident: &proc_macro2::Ident,
+    field_names: &[proc_macro2::Ident],
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    let defaults = match columns
+        .iter()
+        .zip(newtypes)
+        .map(|(c, newtype)| default_value(c, newtype.as_ref()))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(defaults) => defaults,
+        None => return Ok(()),
+    };
+
+    write_tokens(
+        quote::quote! {
+            impl ::std::default::Default for #ident {
+                fn default() -> Self {
+                    Self {
+                        #(
+                            #field_names: #defaults,
+                        )*
+                    }
+                }
+            }
+        },
+        writer,
+        options,
+    )?;
+
+    Ok(())
+}
+
+/// The Rust type a column type is generated as, without the `Option` of nullable columns.
+pub fn type_name(typ: &Types) -> String {
+    field_type(typ, Options::default())
+        .to_string()
+        .replace(' ', "")
+}
+
+/// Raw types are the schema's enums, so they are named the same way as the generated enums.
+pub(crate) fn field_type(typ: &Types, options: Options) -> TokenStream {
+    match typ {
+        Types::Char => quote::quote! { char },
+        Types::Varchar(_) | Types::Text => quote::quote! { ::std::string::String },
+        Types::Number | Types::Int | Types::Serial | Types::MediumInt => quote::quote! { i32 },
+        Types::SmallInt | Types::SmallSerial => quote::quote! { i16 },
+        Types::BigInt | Types::BigSerial => quote::quote! { i64 },
+        Types::Float | Types::Real => quote::quote! { f32 },
+        Types::Double | Types::Numeric | Types::Decimal => quote::quote! { f64 },
+        Types::Money => quote::quote! { ::rewryte::Money },
+        Types::DateTime => quote::quote! { ::chrono::DateTime<chrono::Utc> },
+        Types::Date => quote::quote! { ::chrono::NaiveDate },
+        Types::Time => quote::quote! { ::chrono::NaiveTime },
+        Types::Interval => quote::quote! { ::rewryte::Interval },
+        Types::Boolean => quote::quote! { bool },
+        Types::Uuid => quote::quote! { ::uuid::Uuid },
+        Types::Array(inner) => {
+            let inner = field_type(inner, options);
+
+            quote::quote! { ::std::vec::Vec<#inner> }
+        }
+        Types::Raw(raw) => {
+            let raw_ident = type_ident(raw, options);
+
+            quote::quote! { #raw_ident }
+        }
+    }
+}
+
+/// Checks if a column type is generated as `f32` or `f64`, looking through arrays.
+pub(crate) fn is_float(typ: &Types) -> bool {
+    match typ {
+        Types::Float | Types::Real | Types::Double | Types::Numeric | Types::Decimal => true,
+        Types::Money => !cfg!(feature = "with-decimal"),
+        Types::Array(inner) => is_float(inner),
+        _ => false,
+    }
+}
+
+/// Writes a `new` constructor, columns with a default or that are serial are filled in instead of taken as parameters.
+fn write_constructor(
+    columns: &[&Column],
+    newtypes: &[Option<proc_macro2::Ident>],
+    ident: &proc_macro2::Ident,
+    field_names: &[proc_macro2::Ident],
+    field_types: &[TokenStream],
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    let mut params = Vec::new();
+    let mut fields = Vec::new();
+
+    for (((column, newtype), name), typ) in columns
+        .iter()
+        .zip(newtypes)
+        .zip(field_names)
+        .zip(field_types)
+    {
+        match constructor_value(column, newtype.as_ref()) {
+            Some(value) => fields.push(quote::quote! { #name: #value }),
+            None => {
+                params.push(quote::quote! { #name: #typ });
+                fields.push(quote::quote! { #name });
+            }
+        }
+    }
+
+    write_tokens(
+        quote::quote! {
+            impl #ident {
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(#( #params ),*) -> Self {
+                    Self {
+                        #( #fields, )*
+                    }
+                }
+            }
+        },
+        writer,
+        options,
+    )?;
+
+    Ok(())
+}
+
+fn constructor_value(column: &Column, newtype: Option<&proc_macro2::Ident>) -> Option<TokenStream> {
+    match (&column.default, &column.typ, newtype) {
+        (ColumnDefault::None, typ, Some(newtype)) if typ.is_serial() && !column.null => {
+            Some(quote::quote! { #newtype(::std::default::Default::default()) })
+        }
+        (ColumnDefault::None, typ, None) if typ.is_serial() && !column.null => {
+            Some(quote::quote! { ::std::default::Default::default() })
+        }
+        (ColumnDefault::None, _, _) => None,
+        _ => default_value(column, newtype),
+    }
+}
+
+fn serde_default_ident(field: &proc_macro2::Ident) -> proc_macro2::Ident {
+    quote::format_ident!("default_{}", field)
+}
+
+/// The value serde fills in for a column missing from the input, only columns with a default that can be written in
+/// Rust get one. Nullable columns without a default are already `None` when missing.
+fn serde_default_value(
+    column: &Column,
+    newtype: Option<&proc_macro2::Ident>,
+    options: Options,
+) -> Option<TokenStream> {
+    if !options.serde || !options.serde_default {
+        return None;
+    }
+
+    match column.default {
+        ColumnDefault::None | ColumnDefault::Null => None,
+        _ => default_value(column, newtype),
+    }
+}
+
+fn default_value(column: &Column, newtype: Option<&proc_macro2::Ident>) -> Option<TokenStream> {
+    let value = match column.default {
+        ColumnDefault::None | ColumnDefault::Null if column.null => {
+            return Some(quote::quote! { ::std::option::Option::None });
+        }
+        ColumnDefault::None | ColumnDefault::Null => return None,
+        ColumnDefault::Now => quote::quote! { ::chrono::Utc::now() },
+        ColumnDefault::CurrentDate => quote::quote! { ::chrono::Utc::now().naive_utc().date() },
+        ColumnDefault::CurrentTime => quote::quote! { ::chrono::Utc::now().naive_utc().time() },
+        ColumnDefault::Uuid => quote::quote! { ::uuid::Uuid::new_v4() },
+        ColumnDefault::Bool(value) => quote::quote! { #value },
+        ColumnDefault::Raw(raw) => raw_default_value(&column.typ, raw)?,
+    };
+
+    let value = match newtype {
+        Some(newtype) => quote::quote! { #newtype(#value) },
+        None => value,
+    };
+
+    if column.null {
+        Some(quote::quote! { ::std::option::Option::Some(#value) })
+    } else {
+        Some(value)
+    }
+}
+
+/// Turns a raw SQL default into a Rust literal, only string, number, and boolean literals are supported.
+///
+/// The literal has to fit the column's type, integers for integer columns, numbers for float columns, and `true`,
+/// `false`, `1` or `0` for booleans. Anything else, including every default of a type without a literal, is `None`.
+fn raw_default_value(typ: &Types, raw: &str) -> Option<TokenStream> {
+    match typ {
+        Types::Char | Types::Varchar(_) | Types::Text => {
+            if raw.len() < 2 || !raw.starts_with('\'') || !raw.ends_with('\'') {
+                return None;
+            }
+
+            let unquoted = raw[1..(raw.len() - 1)].replace("''", "'");
+
+            if let Types::Char = typ {
+                let mut chars = unquoted.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(quote::quote! { #c }),
+                    _ => None,
+                }
+            } else {
+                Some(quote::quote! { ::std::string::String::from(#unquoted) })
+            }
+        }
+        Types::Boolean => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" => Some(quote::quote! { true }),
+            "false" | "0" => Some(quote::quote! { false }),
+            _ => None,
+        },
+        Types::Number
+        | Types::Int
+        | Types::Serial
+        | Types::MediumInt
+        | Types::SmallInt
+        | Types::SmallSerial
+        | Types::BigInt
+        | Types::BigSerial => {
+            let (negative, digits) = split_sign(raw);
+
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+
+            let value = raw.parse::<i64>().ok()?;
+
+            let fits = match typ {
+                Types::SmallInt | Types::SmallSerial => i16::try_from(value).is_ok(),
+                Types::BigInt | Types::BigSerial => true,
+                _ => i32::try_from(value).is_ok(),
+            };
+
+            if !fits {
+                return None;
+            }
+
+            let literal = Literal::u64_unsuffixed(value.unsigned_abs());
+
+            Some(signed(negative, literal))
+        }
+        Types::Float | Types::Real | Types::Double | Types::Numeric | Types::Decimal => {
+            let (negative, digits) = split_sign(raw);
+
+            // Rust's float parsing also takes `inf` and `NaN`, which are not SQL numbers
+            if !digits.bytes().any(|b| b.is_ascii_digit())
+                || !digits
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'+' | b'-'))
+            {
+                return None;
+            }
+
+            let value = digits.parse::<f64>().ok()?;
+
+            let finite = match typ {
+                Types::Float | Types::Real => (value as f32).is_finite(),
+                _ => value.is_finite(),
+            };
+
+            if !finite {
+                return None;
+            }
+
+            // Unsuffixed float literals always have a `.` or an exponent, so integers come out as `0.0`
+            let literal = Literal::f64_unsuffixed(value);
+
+            Some(signed(negative, literal))
+        }
+        _ => None,
+    }
+}
+
+fn split_sign(raw: &str) -> (bool, &str) {
+    match raw.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, raw),
+    }
+}
+
+fn signed(negative: bool, literal: Literal) -> TokenStream {
+    if negative {
+        quote::quote! { -#literal }
+    } else {
+        quote::quote! { #literal }
+    }
+}
+
+/// Wraps an attribute in `#[cfg_attr(feature = ...)]` when the matching `feature-gate-*` feature is enabled.
+pub(crate) fn gated_attr(gated: bool, feature: &str, attr: TokenStream) -> TokenStream {
+    if gated {
+        quote::quote! {
+            #[cfg_attr(feature = #feature, #attr)]
+        }
+    } else {
+        quote::quote! {
+            #[#attr]
+        }
+    }
+}
+
+pub(crate) fn type_ident(name: &str, options: Options) -> proc_macro2::Ident {
+    if options.pascal_case_types {
+        quote::format_ident!("{}", name.to_camel_case())
+    } else {
+        quote::format_ident!("{}", name)
+    }
+}
+
+/// Keeps serde, sqlx, and async-graphql using the name a renamed Rust type or field would otherwise have had.
+fn rename_attr(name: &str, ident: &proc_macro2::Ident, options: Options) -> TokenStream {
+    if ident == name {
+        return quote::quote! {};
+    }
+
+    let serde = if options.serde {
+        gated_attr(
+            cfg!(feature = "feature-gate-serde"),
+            "rewryte-serde",
+            quote::quote! { serde(rename = #name) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    let sqlx = if options.sqlx {
+        gated_attr(
+            cfg!(feature = "feature-gate-sqlx"),
+            "rewryte-sqlx",
+            quote::quote! { sqlx(rename = #name) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    let async_graphql = if options.async_graphql {
+        gated_attr(
+            cfg!(feature = "feature-gate-async-graphql"),
+            "rewryte-async-graphql",
+            quote::quote! { graphql(name = #name) },
+        )
+    } else {
+        quote::quote! {}
+    };
+
+    quote::quote! {
+        #serde
+        #sqlx
+        #async_graphql
+    }
+}
+
+fn non_exhaustive_attr(options: Options) -> TokenStream {
+    if options.non_exhaustive {
+        quote::quote! {
+            #[non_exhaustive]
+        }
+    } else {
+        quote::quote! {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::rust::{write_enum, write_table},
+        rewryte_parser::models::*,
+    };
+
+    fn render(table: &Table, options: super::Options) -> String {
+        let mut buff = Vec::new();
+
+        write_table(table, &mut buff, options).expect("Unable to write table to buffer");
+
+        String::from_utf8(buff).expect("Unable to convert buff into string")
+    }
+
+    fn column(
+        name: &'static str,
+        typ: Types<'static>,
+        null: bool,
+        default: ColumnDefault<'static>,
+    ) -> Column<'static> {
+        Column {
+            name,
+            doc: None,
+            typ,
+            null,
+            default,
+            skip: vec![],
+            rust_name: None,
+            collate: None,
+            searchable: false,
+            version: false,
+        }
+    }
+
+    #[test]
+    fn derive_default() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Name", Types::Text, false, ColumnDefault::Raw("'it''s'")),
+                column("Count", Types::Int, false, ColumnDefault::Raw("-1")),
+                column("Enabled", Types::Boolean, false, ColumnDefault::Raw("true")),
+                column("Hidden", Types::Boolean, false, ColumnDefault::Raw("0")),
+                column("Total", Types::BigInt, false, ColumnDefault::Raw("1")),
+                column("Rating", Types::Float, false, ColumnDefault::Raw("0")),
+                column("Ratio", Types::Double, false, ColumnDefault::Raw("-1.5")),
+                column("Note", Types::Text, true, ColumnDefault::None),
+                column("Created", Types::DateTime, false, ColumnDefault::Now),
+                column("Key", Types::Uuid, false, ColumnDefault::Uuid),
+            ],
+            primary_keys: vec![],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                derive_default: true,
+                ..super::Options::default()
+            },
+        );
+
+        let expected = quote::quote! {
+            impl ::std::default::Default for Example {
+                fn default() -> Self {
+                    Self {
+                        name: ::std::string::String::from("it's"),
+                        count: -1,
+                        enabled: true,
+                        hidden: false,
+                        total: 1,
+                        rating: 0.0,
+                        ratio: -1.5,
+                        note: ::std::option::Option::None,
+                        created: ::chrono::Utc::now(),
+                        key: ::uuid::Uuid::new_v4(),
+                    }
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn derive_default_mismatched() {
+        for (typ, raw) in [
+            (Types::Int, "1.5"),
+            (Types::Int, "'a'"),
+            (Types::SmallInt, "40000"),
+            (Types::Boolean, "2"),
+            (Types::Double, "'NaN'"),
+            (Types::Float, "inf"),
+            (Types::Money, "0"),
+            (Types::Interval, "0"),
+        ] {
+            assert!(
+                super::raw_default_value(&typ, raw).is_none(),
+                "`{}` was accepted for {:?}",
+                raw,
+                typ
+            );
+        }
+    }
+
+    #[test]
+    fn derive_default_missing() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Created", Types::DateTime, false, ColumnDefault::Now),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                derive_default: true,
+                ..super::Options::default()
+            },
+        );
+
+        assert!(!rendered.contains("Default for"), "{}", rendered);
+    }
+
+    fn option_warnings(schema: &str, options: super::Options) -> Vec<String> {
+        let mut ctx = rewryte_parser::Context::new(0);
+
+        let parsed = rewryte_parser::parse(&mut ctx, schema).expect("Unable to parse schema");
+
+        super::check_options(&mut ctx, schema, &parsed, options);
+
+        ctx.warnings()
+            .iter()
+            .flat_map(|diag| diag.labels.iter().map(|label| label.message.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn derive_default_missing_warning() {
+        const SCHEMA: &str = "table Example {
+            Id text [primary key]
+            Created dateTime [default: now()]
+        }
+
+        table Defaulted {
+            Name text [default: 'none']
+        }";
+
+        let options = super::Options {
+            derive_default: true,
+            ..super::Options::default()
+        };
+
+        assert_eq!(
+            vec!["`Example` does not implement `Default`, column `Id` has no default value"],
+            option_warnings(SCHEMA, options)
+        );
+        assert!(option_warnings(SCHEMA, super::Options::default()).is_empty());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_array_warning() {
+        const SCHEMA: &str = "table Example {
+            Id text [primary key]
+            Tags text[]
+        }";
+
+        assert_eq!(
+            vec!["`Example` does not implement SQLite's `FromRow`, column `Tags` is an array"],
+            option_warnings(SCHEMA, super::Options::default())
+        );
+    }
+
+    #[test]
+    fn date_time() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Day", Types::Date, false, ColumnDefault::CurrentDate),
+                column("At", Types::Time, false, ColumnDefault::CurrentTime),
+            ],
+            primary_keys: vec![],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                derive_default: true,
+                ..super::Options::default()
+            },
+        );
+
+        let fields = quote::quote! {
+            pub day: ::chrono::NaiveDate,
+            pub at: ::chrono::NaiveTime,
+        };
+
+        let defaults = quote::quote! {
+            day: ::chrono::Utc::now().naive_utc().date(),
+            at: ::chrono::Utc::now().naive_utc().time(),
+        };
+
+        assert!(rendered.contains(&fields.to_string()), "{}", rendered);
+        assert!(rendered.contains(&defaults.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn money() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Price", Types::Money, false, ColumnDefault::None)],
+            primary_keys: vec![],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let field = quote::quote! { pub price: ::rewryte::Money, };
+
+        assert!(rendered.contains(&field.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn floats() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Float", Types::Float, false, ColumnDefault::None),
+                column("Real", Types::Real, false, ColumnDefault::None),
+                column("Double", Types::Double, false, ColumnDefault::None),
+                column("Numeric", Types::Numeric, false, ColumnDefault::None),
+                column("Decimal", Types::Decimal, true, ColumnDefault::None),
+            ],
+            primary_keys: vec![],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            #[derive(Clone, Debug, PartialEq, PartialOrd)]
+            pub struct Example {
+                pub float: f32,
+                pub real: f32,
+                pub double: f64,
+                pub numeric: f64,
+                pub decimal: ::std::option::Option<f64>,
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_to_params() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Rating", Types::Float, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            impl ::rewryte::sqlite::ToParams for Example {
+                fn to_params(&self) -> ::std::vec::Vec<::std::boxed::Box<dyn ::rewryte::sqlite::types::ToSql + '_>> {
+                    ::std::vec![
+                        ::std::boxed::Box::new(&self.id) as ::std::boxed::Box<dyn ::rewryte::sqlite::types::ToSql + '_>,
+                        ::std::boxed::Box::new(self.rating.map(f64::from)) as ::std::boxed::Box<dyn ::rewryte::sqlite::types::ToSql + '_>,
+                    ]
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn select_columns() {
+        let mut secret = column("Secret", Types::Text, false, ColumnDefault::None);
+
+        secret.skip = vec!["rust"];
+
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                secret,
+                column("Name", Types::Text, false, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            impl Example {
+                #[doc = r" Selects every column in the order `FromRow` reads them."]
+                pub const SELECT: &'static str = "SELECT Id, Secret, Name FROM Example";
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        let expected = quote::quote! {
+            name: row.get(2usize).context("Failed to get data for row index 2: `name`")?,
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_find() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Name", Types::Text, false, ColumnDefault::None),
+                column("Id", Types::BigInt, false, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            impl ::rewryte::sqlite::Find for Example {
+                type Key = i64;
+
+                const FIND: &'static str = "SELECT Name, Id FROM Example WHERE Id = ?1";
+
+                fn key_params(key: &Self::Key) -> ::std::vec::Vec<&dyn ::rewryte::sqlite::types::ToSql> {
+                    ::std::vec![
+                        key as &dyn ::rewryte::sqlite::types::ToSql,
+                    ]
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    fn versioned_table() -> Table<'static> {
+        Table {
+            name: "Setting",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Key", Types::Text, false, ColumnDefault::None),
+                column("Value", Types::Text, false, ColumnDefault::None),
+                Column {
+                    version: true,
+                    ..column("Updated", Types::DateTime, false, ColumnDefault::Now)
+                },
+                column("Note", Types::Text, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Key"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        }
+    }
+
+    #[test]
+    fn same_version() {
+        let rendered = render(&versioned_table(), super::Options::default());
+
+        let expected = quote::quote! {
+            impl Setting {
+                #[doc = r" Checks if a freshly fetched row still has the version this one was read with."]
+                pub fn same_version(&self, current: &Self) -> bool {
+                    self.updated == current.updated
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_versioned() {
+        let rendered = render(&versioned_table(), super::Options::default());
+
+        let expected = quote::quote! {
+            impl ::rewryte::sqlite::Versioned for Setting {
+                const UPDATE_IF_UNCHANGED: &'static str = "UPDATE Setting SET Value = ?2, Note = ?4, Updated = DATETIME('now', 'utc') WHERE Key = ?1 AND DATETIME(Updated) = DATETIME(?3)";
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        // The version can only be compared if it is a field
+        let mut table = versioned_table();
+
+        table.columns[2].skip = vec!["rust"];
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(!rendered.contains("Versioned"), "{}", rendered);
+        assert!(!rendered.contains("same_version"), "{}", rendered);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn postgres_versioned() {
+        let mut table = versioned_table();
+
+        table.columns[2] = Column {
+            version: true,
+            ..column("Revision", Types::BigInt, false, ColumnDefault::Raw("0"))
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            impl ::rewryte::postgres::Versioned for Setting {
+                const UPDATE_IF_UNCHANGED: &'static str = "UPDATE Setting SET Value = $2, Note = $4, Revision = Revision + 1 WHERE Key = $1 AND Revision = $3";
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn find_without_primary_key() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Name", Types::Text, false, ColumnDefault::None)],
+            primary_keys: vec![],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(!rendered.contains("Find"), "{}", rendered);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn postgres_find_composite() {
+        let table = Table {
+            name: "StoryChapter",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("StoryId", Types::Text, false, ColumnDefault::None),
+                column("Place", Types::Int, false, ColumnDefault::None),
+            ],
+            primary_keys: vec!["StoryId", "Place"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            impl ::rewryte::postgres::Find for StoryChapter {
+                type Key = (::std::string::String, i32);
+
+                const FIND: &'static str = "SELECT StoryId, Place FROM StoryChapter WHERE StoryId = $1 AND Place = $2";
+
+                fn key_params(key: &Self::Key) -> ::std::vec::Vec<&(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync)> {
+                    ::std::vec![
+                        &key.0 as &(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync),
+                        &key.1 as &(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync),
+                    ]
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn postgres_to_params() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Rating", Types::Float, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            impl ::rewryte::postgres::ToParams for Example {
+                fn to_params(&self) -> ::std::vec::Vec<&(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync)> {
+                    ::std::vec![
+                        &self.id as &(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync),
+                        &self.rating as &(dyn ::rewryte::postgres::types::ToSql + ::std::marker::Sync),
+                    ]
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn async_graphql() {
+        let options = super::Options {
+            async_graphql: true,
+            pascal_case_types: true,
+            ..super::Options::default()
+        };
+
+        let table = Table {
+            name: "story_chapter",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                Column {
+                    skip: vec!["graphql"],
+                    ..column("Secret", Types::Text, false, ColumnDefault::None)
+                },
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let decl = Enum {
+            name: "State",
+            not_exists: false,
+            variants: vec![Variant {
+                name: "Working",
+                doc: None,
+            }],
+        };
+
+        let rendered = render(&table, options);
+
+        let (derive, name, skip) = if cfg!(feature = "feature-gate-async-graphql") {
+            (
+                quote::quote! { #[cfg_attr(feature = "rewryte-async-graphql", derive(async_graphql::SimpleObject))] },
+                quote::quote! { #[cfg_attr(feature = "rewryte-async-graphql", graphql(name = "story_chapter"))] },
+                quote::quote! { #[cfg_attr(feature = "rewryte-async-graphql", graphql(skip))] },
+            )
+        } else {
+            (
+                quote::quote! { #[derive(async_graphql::SimpleObject)] },
+                quote::quote! { #[graphql(name = "story_chapter")] },
+                quote::quote! { #[graphql(skip)] },
+            )
+        };
+
+        let expected = quote::quote! {
+            #derive
+            #name
+            pub struct StoryChapter {
+                pub id: ::std::string::String,
+                #skip
+                pub secret: ::std::string::String,
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        assert!(!rendered.contains("juniper"), "{}", rendered);
+
+        let mut buff = Vec::new();
+
+        write_enum(&decl, &mut buff, options).expect("Unable to write enum to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let expected = if cfg!(feature = "feature-gate-async-graphql") {
+            quote::quote! { #[cfg_attr(feature = "rewryte-async-graphql", derive(async_graphql::Enum))] }
+        } else {
+            quote::quote! { #[derive(async_graphql::Enum)] }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn async_graphql_with_juniper() {
+        let mut options = super::Options::default();
+
+        assert!(options.enable("juniper"));
+        assert!(options.enable("async_graphql"));
+        // The first documented name
+        assert!(options.enable("async-graphql"));
+        assert!(!options.enable("async_graphq"));
+
+        let schema = Schema {
+            database: None,
+            if_not_exists: false,
+            items: vec![],
+        };
+
+        match super::write_schema(&schema, &mut Vec::new(), options) {
+            Err(crate::Error::ConflictingOptions("juniper", "async_graphql")) => {}
+            res => panic!("expected conflicting options, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn derive_gating() {
+        let options = super::Options {
+            juniper: true,
+            serde: true,
+            sqlx: true,
+            ..super::Options::default()
+        };
+
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Id", Types::Text, false, ColumnDefault::None)],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let decl = Enum {
+            name: "State",
+            not_exists: false,
+            variants: vec![Variant {
+                name: "Working",
+                doc: None,
+            }],
+        };
+
+        let mut buff = Vec::new();
+
+        write_enum(&decl, &mut buff, options).expect("Unable to write enum to buffer");
+
+        let renders = [
+            (render(&table, options), "GraphQLObject"),
+            (
+                String::from_utf8(buff).expect("Unable to convert buff into string"),
+                "GraphQLEnum",
+            ),
+        ];
+
+        for (rendered, juniper) in renders.iter() {
+            let juniper = quote::format_ident!("{}", juniper);
+
+            let expected = if cfg!(feature = "feature-gate-juniper") {
+                quote::quote! {
+                    #[cfg_attr(feature = "rewryte-juniper", derive(juniper::#juniper))]
+                }
+            } else {
+                quote::quote! {
+                    #[derive(juniper::#juniper)]
+                }
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+            let expected = if cfg!(feature = "feature-gate-serde") {
+                quote::quote! {
+                    #[cfg_attr(feature = "rewryte-serde", derive(serde::Deserialize, serde::Serialize))]
+                }
+            } else {
+                quote::quote! {
+                    #[derive(serde::Deserialize, serde::Serialize)]
+                }
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+    }
+
+    #[test]
+    fn constructors() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Serial, false, ColumnDefault::None),
+                column("Name", Types::Text, false, ColumnDefault::None),
+                column("Note", Types::Text, true, ColumnDefault::None),
+                column("Count", Types::Int, false, ColumnDefault::Raw("0")),
+                column("Extra", Types::Text, true, ColumnDefault::Null),
+                column("Created", Types::DateTime, false, ColumnDefault::Now),
+                column("Score", Types::Int, false, ColumnDefault::Raw("abs(-1)")),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                constructors: true,
+                ..super::Options::default()
+            },
+        );
+
+        let expected = quote::quote! {
+            impl Example {
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(
+                    name: ::std::string::String,
+                    note: ::std::option::Option<::std::string::String>,
+                    score: i32
+                ) -> Self {
+                    Self {
+                        id: ::std::default::Default::default(),
+                        name,
+                        note,
+                        count: 0,
+                        extra: ::std::option::Option::None,
+                        created: ::chrono::Utc::now(),
+                        score,
+                    }
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn array() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column(
+                    "Tags",
+                    Types::Array(Box::new(Types::Text)),
+                    false,
+                    ColumnDefault::None,
+                ),
+                column(
+                    "Grid",
+                    Types::Array(Box::new(Types::Array(Box::new(Types::Int)))),
+                    true,
+                    ColumnDefault::None,
+                ),
+            ],
+            primary_keys: vec![],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            pub struct Example {
+                pub tags: ::std::vec::Vec<::std::string::String>,
+                pub grid: ::std::option::Option<::std::vec::Vec<::std::vec::Vec<i32> > >,
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        if cfg!(feature = "sqlite") {
+            assert!(!rendered.contains("sqlite"), "{}", rendered);
+        }
+    }
+
+    #[test]
+    fn pascal_case_types() {
+        let table = Table {
+            name: "user_accounts",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Id", Types::Text, false, ColumnDefault::None)],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(
+            rendered.contains(&quote::quote! { pub struct user_accounts }.to_string()),
+            "{}",
+            rendered
+        );
+
+        let rendered = render(
+            &table,
+            super::Options {
+                pascal_case_types: true,
+                serde: true,
+                ..super::Options::default()
+            },
+        );
+
+        assert!(
+            rendered.contains(&quote::quote! { pub struct UserAccounts }.to_string()),
+            "{}",
+            rendered
+        );
+        assert!(
+            rendered.contains(&quote::quote! { serde(rename = "user_accounts") }.to_string()),
+            "{}",
+            rendered
+        );
+
+        let decl = Enum {
+            name: "account_state",
+            not_exists: false,
+            variants: vec![Variant {
+                name: "Working",
+                doc: None,
+            }],
+        };
+
+        let mut buff = Vec::new();
+
+        write_enum(
+            &decl,
+            &mut buff,
+            super::Options {
+                pascal_case_types: true,
+                ..super::Options::default()
+            },
+        )
+        .expect("Unable to write enum to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert!(
+            rendered.contains(&quote::quote! { pub enum AccountState }.to_string()),
+            "{}",
+            rendered
+        );
+
+        if cfg!(feature = "postgres") {
+            assert!(
+                rendered.contains(
+                    &quote::quote! { !type_.name().eq_ignore_ascii_case("account_state") }
+                        .to_string()
+                ),
+                "{}",
+                rendered
+            );
+        }
+
+        let table = Table {
+            columns: vec![
+                column(
+                    "State",
+                    Types::Raw("account_state"),
+                    false,
+                    ColumnDefault::None,
+                ),
+                column(
+                    "History",
+                    Types::Array(Box::new(Types::Raw("account_state"))),
+                    false,
+                    ColumnDefault::None,
+                ),
+            ],
+            ..table
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                pascal_case_types: true,
+                ..super::Options::default()
+            },
+        );
+
+        assert!(
+            rendered.contains(&quote::quote! { pub state: AccountState, }.to_string()),
+            "{}",
+            rendered
+        );
+        assert!(
+            rendered.contains(
+                &quote::quote! { pub history: ::std::vec::Vec<AccountState>, }.to_string()
+            ),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn skip() {
+        let mut hash = column("Hash", Types::Text, false, ColumnDefault::None);
+        hash.skip = vec!["rust"];
+
+        let mut secret = column("Secret", Types::Text, false, ColumnDefault::None);
+        secret.skip = vec!["graphql"];
+
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                hash,
+                secret,
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                juniper: true,
+                ..super::Options::default()
+            },
+        );
+
+        let graphql_skip = if cfg!(feature = "feature-gate-juniper") {
+            quote::quote! { #[cfg_attr(feature = "rewryte-juniper", graphql(skip))] }
+        } else {
+            quote::quote! { #[graphql(skip)] }
+        };
+
+        let expected = quote::quote! {
+            pub struct Example {
+                pub id: ::std::string::String,
+                #graphql_skip
+                pub secret: ::std::string::String,
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        assert!(!rendered.contains("hash"), "{}", rendered);
+
+        if cfg!(feature = "postgres") {
+            let expected = quote::quote! {
+                id: row.try_get(0usize).context("Failed to get data for row index 0: `id`")?,
+                secret: row.try_get(2usize).context("Failed to get data for row index 2: `secret`")?,
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+    }
+
+    #[test]
+    fn rust_name() {
+        let mut kind = column("Type", Types::Text, false, ColumnDefault::None);
+        kind.rust_name = Some("kind");
+
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Id", Types::Text, false, ColumnDefault::None), kind],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                serde: true,
+                ..super::Options::default()
+            },
+        );
+
+        let serde_rename = if cfg!(feature = "feature-gate-serde") {
+            quote::quote! { #[cfg_attr(feature = "rewryte-serde", serde(rename = "type"))] }
+        } else {
+            quote::quote! { #[serde(rename = "type")] }
+        };
+
+        let expected = quote::quote! {
+            pub struct Example {
+                pub id: ::std::string::String,
+                #serde_rename
+                pub kind: ::std::string::String,
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        if cfg!(feature = "postgres") {
+            let expected = quote::quote! {
+                id: row.try_get(0usize).context("Failed to get data for row index 0: `id`")?,
+                kind: row.try_get(1usize).context("Failed to get data for row index 1: `kind`")?,
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+
+        if cfg!(feature = "sqlite") {
+            assert!(
+                rendered.contains("SELECT Id, Type FROM Example"),
+                "{}",
+                rendered
+            );
+        }
+    }
+
+    #[test]
+    fn skip_none() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Name", Types::Text, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let skip_none = if cfg!(feature = "feature-gate-serde") {
+            quote::quote! { #[cfg_attr(feature = "rewryte-serde", serde(skip_serializing_if = "Option::is_none"))] }
+        } else {
+            quote::quote! { #[serde(skip_serializing_if = "Option::is_none")] }
+        };
+
+        let expected = quote::quote! {
+            pub struct Example {
+                pub id: ::std::string::String,
+                #skip_none
+                pub name: ::std::option::Option<::std::string::String>,
+            }
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                serde: true,
+                skip_none: true,
+                ..super::Options::default()
+            },
+        );
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        assert_eq!(
+            rendered.matches("skip_serializing_if").count(),
+            1,
+            "{}",
+            rendered
+        );
+
+        // Without serde there is nothing to skip the field for
+        let rendered = render(
+            &table,
+            super::Options {
+                skip_none: true,
+                ..super::Options::default()
+            },
+        );
+
+        assert!(!rendered.contains("skip_serializing_if"), "{}", rendered);
+    }
+
+    #[test]
+    fn serde_default() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Name", Types::Text, false, ColumnDefault::Raw("'guest'")),
+                column("Active", Types::Boolean, true, ColumnDefault::Bool(true)),
+                column("Note", Types::Text, true, ColumnDefault::Null),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let (name, active) = if cfg!(feature = "feature-gate-serde") {
+            (
+                quote::quote! { #[cfg_attr(feature = "rewryte-serde", serde(default = "Example::default_name"))] },
+                quote::quote! { #[cfg_attr(feature = "rewryte-serde", serde(default = "Example::default_active"))] },
+            )
+        } else {
+            (
+                quote::quote! { #[serde(default = "Example::default_name")] },
+                quote::quote! { #[serde(default = "Example::default_active")] },
+            )
+        };
+
+        let expected = [
+            quote::quote! {
+                pub struct Example {
+                    pub id: ::std::string::String,
+                    #name
+                    pub name: ::std::string::String,
+                    #active
+                    pub active: ::std::option::Option<bool>,
+                    pub note: ::std::option::Option<::std::string::String>,
+                }
+            },
+            quote::quote! {
+                impl Example {
+                    #[doc(hidden)]
+                    #[allow(dead_code)]
+                    fn default_name() -> ::std::string::String {
+                        ::std::string::String::from("guest")
+                    }
+
+                    #[doc(hidden)]
+                    #[allow(dead_code)]
+                    fn default_active() -> ::std::option::Option<bool> {
+                        ::std::option::Option::Some(true)
+                    }
+                }
+            },
+        ];
+
+        let rendered = render(
+            &table,
+            super::Options {
+                serde: true,
+                serde_default: true,
+                ..super::Options::default()
+            },
+        );
+
+        for expected in expected.iter() {
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+
+        // Without serde there is nothing to fill the defaults in for
+        let rendered = render(
+            &table,
+            super::Options {
+                serde_default: true,
+                ..super::Options::default()
+            },
+        );
+
+        assert!(!rendered.contains("default_name"), "{}", rendered);
+    }
+
+    #[test]
+    fn id_newtypes() {
+        let reference = |local| ForeignKey {
+            local: vec![local],
+            table: "User",
+            foreign: vec!["Id"],
+            delete: Action::NoAction,
+            update: Action::NoAction,
+            deferrable: false,
+            name: None,
+        };
+
+        let user = Table {
+            name: "User",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Serial, false, ColumnDefault::None),
+                column("Name", Types::Text, false, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let post = Table {
+            name: "Post",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Author", Types::Int, false, ColumnDefault::None),
+                column("Editor", Types::Int, true, ColumnDefault::None),
+                column("Views", Types::Int, false, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![reference("Author"), reference("Editor")],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let schema = Schema {
+            database: None,
+            if_not_exists: false,
+            items: vec![Item::Table(user), Item::Table(post)],
+        };
+
+        let mut buff = Vec::new();
+
+        super::write_schema(
+            &schema,
+            &mut buff,
+            super::Options {
+                id_newtypes: true,
+                constructors: true,
+                ..super::Options::default()
+            },
+        )
+        .expect("Unable to write schema to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let expected = [
+            quote::quote! {
+                #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+                #[repr(transparent)]
+                pub struct UserId(pub i32);
+            },
+            quote::quote! {
+                #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+                #[repr(transparent)]
+                pub struct PostId(pub ::std::string::String);
+            },
+            quote::quote! {
+                pub struct User {
+                    pub id: UserId,
+                    pub name: ::std::string::String,
+                }
+            },
+            quote::quote! {
+                pub struct Post {
+                    pub id: PostId,
+                    pub author: UserId,
+                    pub editor: ::std::option::Option<UserId>,
+                    pub views: i32,
+                }
+            },
+            quote::quote! {
+                pub fn new(name: ::std::string::String) -> Self {
+                    Self {
+                        id: UserId(::std::default::Default::default()),
+                        name,
+                    }
+                }
+            },
+        ];
+
+        for expected in expected.iter() {
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+
+        if cfg!(feature = "postgres") {
+            assert!(
+                rendered.contains(&quote::quote! { type Key = PostId; }.to_string()),
+                "{}",
+                rendered
+            );
+        }
+    }
+
+    #[test]
+    fn id_newtypes_without_schema() {
+        // Without the rest of the schema, only the table's own key is known
+        let table = Table {
+            name: "Post",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Int, false, ColumnDefault::None),
+                column("Author", Types::Int, false, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![ForeignKey {
+                local: vec!["Author"],
+                table: "User",
+                foreign: vec!["Id"],
+                delete: Action::NoAction,
+                update: Action::NoAction,
+                deferrable: false,
+                name: None,
+            }],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                id_newtypes: true,
+                ..super::Options::default()
+            },
+        );
+
+        let expected = quote::quote! {
+            pub struct Post {
+                pub id: PostId,
+                pub author: i32,
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn id_newtypes_juniper() {
+        let table = Table {
+            name: "Post",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Id", Types::Int, false, ColumnDefault::None)],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let options = super::Options {
+            id_newtypes: true,
+            juniper: true,
+            ..super::Options::default()
+        };
+
+        assert!(options.check().is_ok());
+
+        let rendered = render(&table, options);
+
+        let scalar = if cfg!(feature = "feature-gate-juniper") {
+            quote::quote! {
+                #[cfg_attr(feature = "rewryte-juniper", derive(juniper::GraphQLScalarValue))]
+            }
+        } else {
+            quote::quote! {
+                #[derive(juniper::GraphQLScalarValue)]
+            }
+        };
+
+        let expected = quote::quote! {
+            #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+            #scalar
+            #[repr(transparent)]
+            pub struct PostId(pub i32);
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        // juniper has no 64-bit integer scalar to wrap, so the key stays a plain integer
+        let table = Table {
+            columns: vec![column("Id", Types::BigInt, false, ColumnDefault::None)],
+            ..table
+        };
+
+        let rendered = render(&table, options);
+
+        assert!(!rendered.contains("PostId"), "{}", rendered);
+    }
+
+    #[test]
+    fn table_constants() {
+        let mut password = column("Password", Types::Text, false, ColumnDefault::None);
+        password.skip = vec!["rust"];
+
+        let table = Table {
+            name: "user_accounts",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                password,
+                column("Type", Types::Text, false, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                table_constants: true,
+                pascal_case_types: true,
+                ..super::Options::default()
+            },
+        );
+
+        let expected = quote::quote! {
+            impl UserAccounts {
+                #[doc = r" The name of the table in the database."]
+                pub const TABLE: &'static str = "user_accounts";
+
+                #[doc = r" The names of the table's columns in the database, in the order they are declared."]
+                pub const COLUMNS: &'static [&'static str] = &["Id", "Password", "Type"];
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(!rendered.contains("COLUMNS"), "{}", rendered);
+    }
+
+    #[test]
+    fn column_enum() {
+        let mut password = column("Password", Types::Text, false, ColumnDefault::None);
+        password.skip = vec!["rust"];
+
+        let table = Table {
+            name: "user_accounts",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                password,
+                column("last_login", Types::DateTime, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                column_enum: true,
+                pascal_case_types: true,
+                ..super::Options::default()
+            },
+        );
+
+        let expected = quote::quote! {
+            #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+            pub enum UserAccountsColumn {
+                Id,
+                Password,
+                LastLogin,
+            }
+
+            impl UserAccountsColumn {
+                #[doc = r" Every column, in the order they are declared."]
+                pub const ALL: &'static [UserAccountsColumn] = &[
+                    UserAccountsColumn::Id,
+                    UserAccountsColumn::Password,
+                    UserAccountsColumn::LastLogin
+                ];
+
+                #[doc = r" The name of the column in the database."]
+                pub fn as_str(&self) -> &'static str {
+                    match self {
+                        UserAccountsColumn::Id => "Id",
+                        UserAccountsColumn::Password => "Password",
+                        UserAccountsColumn::LastLogin => "last_login",
+                    }
+                }
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(!rendered.contains("UserAccountsColumn"), "{}", rendered);
+    }
+
+    #[test]
+    fn search() {
+        let mut title = column("Title", Types::Text, false, ColumnDefault::None);
+        title.searchable = true;
+
+        let mut body = column("Body", Types::Text, true, ColumnDefault::None);
+        body.searchable = true;
+
+        let table = Table {
+            name: "Post",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                title,
+                body,
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                search: true,
+                ..super::Options::default()
+            },
+        );
+
+        if cfg!(feature = "postgres") {
+            let expected = quote::quote! {
+                impl ::rewryte::postgres::Search for Post {
+                    const SEARCH: &'static str = "SELECT Id, Title, Body FROM Post WHERE to_tsvector(Title || ' ' || coalesce(Body, '')) @@ plainto_tsquery($1)";
+                }
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+
+        if cfg!(feature = "sqlite") {
+            let expected = quote::quote! {
+                impl ::rewryte::sqlite::Search for Post {
+                    const SEARCH: &'static str = "SELECT Id, Title, Body FROM Post WHERE Title LIKE '%' || ?1 || '%' ESCAPE '\\' OR Body LIKE '%' || ?1 || '%' ESCAPE '\\'";
+                }
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(!rendered.contains("Search"), "{}", rendered);
+    }
+
+    #[test]
+    fn try_from_row() {
+        let table = Table {
+            name: "User",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Id", Types::Text, false, ColumnDefault::None)],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                try_from_row: true,
+                ..super::Options::default()
+            },
+        );
+
+        if cfg!(feature = "postgres") {
+            let expected = quote::quote! {
+                impl ::std::convert::TryFrom<::rewryte::postgres::Row> for User {
+                    type Error = ::anyhow::Error;
+
+                    fn try_from(row: ::rewryte::postgres::Row) -> ::anyhow::Result<Self> {
+                        <Self as ::rewryte::postgres::FromRow>::from_row(row)
+                    }
+                }
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+
+        if cfg!(feature = "sqlite") {
+            let expected = quote::quote! {
+                impl<'r, 's> ::std::convert::TryFrom<&'r ::rewryte::sqlite::Row<'s>> for User
+            };
+
+            assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+        }
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(!rendered.contains("TryFrom"), "{}", rendered);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_from_row_prefixed() {
+        let table = Table {
+            name: "User",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Rating", Types::Float, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            ::std::result::Result::Ok(Self {
+                id: row
+                    .get(::std::format!("{}_{}", prefix, "id").as_str())
+                    .with_context(|| ::std::format!("Failed to get data for column `{}_{}`", prefix, "id"))?,
+                rating: row
+                    .get::<_, ::std::option::Option<f64>>(::std::format!("{}_{}", prefix, "rating").as_str())
+                    .map(|value| value.map(|value| value as f32))
+                    .with_context(|| ::std::format!("Failed to get data for column `{}_{}`", prefix, "rating"))?,
+            })
+        };
+
+        assert!(
+            rendered.contains("impl :: rewryte :: sqlite :: FromRowPrefixed for User"),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn variant_descriptions() {
+        let decl = Enum {
+            name: "State",
+            not_exists: false,
+            variants: vec![
+                Variant {
+                    name: "Working",
+                    doc: Some("Still being written,\n    or edited"),
+                },
+                Variant {
+                    name: "Finished",
+                    doc: None,
+                },
+            ],
+        };
+
+        let mut buff = Vec::new();
+
+        write_enum(
+            &decl,
+            &mut buff,
+            super::Options {
+                juniper: true,
+                ..super::Options::default()
+            },
+        )
+        .expect("Unable to write enum to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let description = if cfg!(feature = "feature-gate-juniper") {
+            quote::quote! {
+                #[cfg_attr(feature = "rewryte-juniper", graphql(description = "Still being written,\nor edited"))]
+            }
+        } else {
+            quote::quote! {
+                #[graphql(description = "Still being written,\nor edited")]
+            }
+        };
+
+        let expected = quote::quote! {
+            #description
+            Working,
+            Finished,
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn enum_display_from_str() {
+        let decl = Enum {
+            name: "StoryState",
+            not_exists: false,
+            variants: vec![
+                Variant {
+                    name: "InProgress",
+                    doc: None,
+                },
+                Variant {
+                    name: "Finished",
+                    doc: None,
+                },
+            ],
+        };
+
+        let mut buff = Vec::new();
+
+        write_enum(&decl, &mut buff, super::Options::default())
+            .expect("Unable to write enum to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let expected = quote::quote! {
+            let s = match self {
+                StoryState::InProgress => "InProgress",
+                StoryState::Finished => "Finished",
+            };
+
+            f.write_str(s)
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        let expected = quote::quote! {
+            match s {
+                "InProgress" => ::std::result::Result::Ok(StoryState::InProgress),
+                "Finished" => ::std::result::Result::Ok(StoryState::Finished),
+                s => ::std::result::Result::Err(::rewryte::ParseEnumError {
+                    name: "StoryState",
+                    value: ::std::string::ToString::to_string(s),
+                }),
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn non_exhaustive() {
+        let options = super::Options {
+            non_exhaustive: true,
+            ..super::Options::default()
+        };
+
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Id", Types::Text, false, ColumnDefault::None)],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, options);
+
+        let expected = quote::quote! {
+            #[non_exhaustive]
+            pub struct Example
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+
+        let decl = Enum {
+            name: "State",
+            not_exists: false,
+            variants: vec![
+                Variant {
+                    name: "Working",
+                    doc: None,
+                },
+                Variant {
+                    name: "Finished",
+                    doc: None,
+                },
+            ],
+        };
+
+        let mut buff = Vec::new();
+
+        write_enum(&decl, &mut buff, options).expect("Unable to write enum to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let expected = quote::quote! {
+            #[non_exhaustive]
+            pub enum State
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
+
+    #[test]
+    fn exhaustive_by_default() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![column("Id", Types::Text, false, ColumnDefault::None)],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        assert!(!rendered.contains("non_exhaustive"), "{}", rendered);
+    }
+
+    #[cfg(feature = "pretty")]
+    #[test]
+    fn pretty() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Count", Types::Int, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(
+            &table,
+            super::Options {
+                pretty: true,
+                ..super::Options::default()
+            },
+        );
+
+        assert!(
+            rendered.contains(
+                "pub struct Example {\n    pub id: ::std::string::String,\n    pub count: ::std::option::Option<i32>,\n}"
+            ),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn compact_by_default() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                column("Id", Types::Text, false, ColumnDefault::None),
+                column("Count", Types::Int, true, ColumnDefault::None),
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let rendered = render(&table, super::Options::default());
+
+        let expected = quote::quote! {
+            pub struct Example {
+                pub id: ::std::string::String,
+                pub count: ::std::option::Option<i32>,
+            }
+        };
+
+        assert!(rendered.contains(&expected.to_string()), "{}", rendered);
+    }
 }
@@ -0,0 +1,514 @@
+use {
+    crate::{
+        rust::{gated_attr, is_float, type_ident, write_tokens, Options},
+        Error,
+    },
+    heck::{CamelCase, SnakeCase},
+    proc_macro2::TokenStream,
+    rewryte_parser::models::{Action, Enum, Item, Schema, Table, Types},
+    std::{borrow::Cow, io},
+};
+
+/// Writes every table as a sea-orm entity module, along with an active enum for every enum.
+///
+/// Only the `serde` and `pretty` options are used, the other options have no sea-orm equivalent.
+pub fn write_schema(
+    schema: &Schema,
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    for item in &schema.items {
+        write_item(item, writer, options)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_item(item: &Item, writer: &mut impl io::Write, options: Options) -> Result<(), Error> {
+    match &item {
+        Item::Comment(_) => {}
+        Item::Enum(decl) => write_enum(decl, writer, options)?,
+        Item::Table(decl) => write_table(decl, writer, options)?,
+    }
+
+    Ok(())
+}
+
+pub fn write_enum(decl: &Enum, writer: &mut impl io::Write, options: Options) -> Result<(), Error> {
+    let ident = type_ident(decl.name, options);
+    let name = decl.name;
+
+    let serde_derive = serde_derive(options);
+
+    let variants = decl
+        .variants
+        .iter()
+        .map(|v| quote::format_ident!("{}", v.name))
+        .collect::<Vec<_>>();
+
+    // The values are the variant names, as that is what the SQL formats create the enum with
+    let values = decl.variants.iter().map(|v| v.name).collect::<Vec<_>>();
+
+    write_tokens(
+        quote::quote! {
+            #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, sea_orm::EnumIter, sea_orm::DeriveActiveEnum)]
+            #serde_derive
+            #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = #name)]
+            pub enum #ident {
+                #(
+                    #[sea_orm(string_value = #values)]
+                    #variants,
+                )*
+            }
+        },
+        writer,
+        options,
+    )?;
+
+    Ok(())
+}
+
+/// Writes a table as a module holding its `Model`, `Relation`, and `ActiveModelBehavior`, the module is named after
+/// the table in snake case.
+///
+/// A relation variant is named after the table it references, or after the table and the column if the table is
+/// referenced more than once. `Related` is only implemented when there is a single reference to another table.
+///
+/// The referenced columns are assumed to not be renamed with `as`, the other table's columns are not known here.
+pub fn write_table(
+    decl: &Table,
+    writer: &mut impl io::Write,
+    options: Options,
+) -> Result<(), Error> {
+    let module = quote::format_ident!("{}", decl.name.to_snake_case());
+    let table_name = decl.name;
+
+    let serde_derive = serde_derive(options);
+
+    let columns = decl
+        .columns
+        .iter()
+        .filter(|c| !c.skips("rust"))
+        .collect::<Vec<_>>();
+
+    let field_names = columns
+        .iter()
+        .map(|c| match c.rust_name {
+            Some(name) => quote::format_ident!("{}", name),
+            None => quote::format_ident!("{}", c.name.to_snake_case()),
+        })
+        .collect::<Vec<_>>();
+
+    let field_attrs = columns
+        .iter()
+        .zip(field_names.iter())
+        .map(|(c, name)| {
+            let mut attrs = Vec::new();
+
+            if decl.primary_keys.contains(&c.name) {
+                attrs.push(quote::quote! { primary_key });
+
+                if !c.typ.is_serial() {
+                    attrs.push(quote::quote! { auto_increment = false });
+                }
+            }
+
+            if *name != c.name {
+                let column_name = c.name;
+
+                attrs.push(quote::quote! { column_name = #column_name });
+            }
+
+            if let Some(column_type) = column_type(&c.typ) {
+                attrs.push(quote::quote! { column_type = #column_type });
+            }
+
+            if c.null {
+                attrs.push(quote::quote! { nullable });
+            }
+
+            if decl.unique_keys.len() == 1 && decl.unique_keys.contains(&c.name) {
+                attrs.push(quote::quote! { unique });
+            }
+
+            if attrs.is_empty() {
+                quote::quote! {}
+            } else {
+                quote::quote! { #[sea_orm(#( #attrs ),*)] }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let field_types = columns
+        .iter()
+        .map(|c| {
+            let typ = field_type(&c.typ, options);
+
+            if c.null {
+                quote::quote! { ::std::option::Option<#typ> }
+            } else {
+                typ
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let derive = if columns.iter().any(|c| is_float(&c.typ)) {
+        quote::quote! { #[derive(Clone, Debug, PartialEq, sea_orm::DeriveEntityModel)] }
+    } else {
+        quote::quote! { #[derive(Clone, Debug, PartialEq, Eq, sea_orm::DeriveEntityModel)] }
+    };
+
+    let mut relations = Vec::new();
+    let mut related = Vec::new();
+
+    for foreign_key in &decl.foreign_keys {
+        let count = decl
+            .foreign_keys
+            .iter()
+            .filter(|fk| fk.table == foreign_key.table)
+            .count();
+
+        let variant = if count == 1 {
+            quote::format_ident!("{}", foreign_key.table.to_camel_case())
+        } else {
+            quote::format_ident!(
+                "{}{}",
+                foreign_key.table.to_camel_case(),
+                foreign_key.local.join("_").to_camel_case()
+            )
+        };
+
+        let foreign_module = quote::format_ident!("{}", foreign_key.table.to_snake_case());
+
+        let local = foreign_key
+            .local
+            .iter()
+            .map(|local| {
+                let name = decl
+                    .columns
+                    .iter()
+                    .find(|c| c.name == *local)
+                    .and_then(|c| c.rust_name)
+                    .map(str::to_camel_case)
+                    .unwrap_or_else(|| local.to_camel_case());
+
+                format!("Column::{}", name)
+            })
+            .collect::<Vec<_>>();
+
+        let foreign = foreign_key
+            .foreign
+            .iter()
+            .map(|foreign| {
+                format!(
+                    "super::{}::Column::{}",
+                    foreign_module,
+                    foreign.to_camel_case()
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let entity = quote::quote! { super::#foreign_module::Entity };
+
+        let belongs_to = format!("super::{}::Entity", foreign_module);
+        let from = column_list(&local);
+        let to = column_list(&foreign);
+        let on_delete = action_name(&foreign_key.delete);
+        let on_update = action_name(&foreign_key.update);
+
+        relations.push(quote::quote! {
+            #[sea_orm(
+                belongs_to = #belongs_to,
+                from = #from,
+                to = #to,
+                on_update = #on_update,
+                on_delete = #on_delete
+            )]
+            #variant
+        });
+
+        // A table referencing itself would need a `Linked` instead
+        if count == 1 && foreign_key.table != decl.name {
+            related.push(quote::quote! {
+                impl Related<#entity> for Entity {
+                    fn to() -> RelationDef {
+                        Relation::#variant.def()
+                    }
+                }
+            });
+        }
+    }
+
+    write_tokens(
+        quote::quote! {
+            pub mod #module {
+                use sea_orm::entity::prelude::*;
+
+                #derive
+                #serde_derive
+                #[sea_orm(table_name = #table_name)]
+                pub struct Model {
+                    #(
+                        #field_attrs
+                        pub #field_names: #field_types,
+                    )*
+                }
+
+                #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+                pub enum Relation {
+                    #( #relations, )*
+                }
+
+                #( #related )*
+
+                impl ActiveModelBehavior for ActiveModel {}
+            }
+        },
+        writer,
+        options,
+    )?;
+
+    Ok(())
+}
+
+/// The sea-orm column type of a column type, `None` if sea-orm should infer it from the field type.
+pub fn column_type(typ: &Types) -> Option<Cow<'static, str>> {
+    let column_type = match typ {
+        Types::Char => Some("Char(Some(1))"),
+        Types::Varchar(None) => Some("String(None)"),
+        Types::Varchar(Some(length)) => {
+            return Some(Cow::Owned(format!("String(Some({}))", length)))
+        }
+        Types::Text => Some("Text"),
+        Types::Number | Types::Int | Types::Serial | Types::MediumInt => Some("Integer"),
+        Types::SmallInt | Types::SmallSerial => Some("SmallInteger"),
+        Types::BigInt | Types::BigSerial => Some("BigInteger"),
+        Types::Float | Types::Real => Some("Float"),
+        Types::Double => Some("Double"),
+        Types::Numeric | Types::Decimal => Some("Decimal(None)"),
+        Types::Money => Some("Decimal(Some((19, 4)))"),
+        Types::DateTime => Some("TimestampWithTimeZone"),
+        Types::Date => Some("Date"),
+        Types::Time => Some("Time"),
+        // sea-orm has no Rust type for an interval, so it is the nanoseconds the SQLite schema stores
+        Types::Interval => Some("BigInteger"),
+        Types::Boolean => Some("Boolean"),
+        Types::Uuid => Some("Uuid"),
+        Types::Array(_) | Types::Raw(_) => None,
+    };
+
+    column_type.map(Cow::Borrowed)
+}
+
+/// The same types as the plain models, except enums live in the parent of the entity module.
+fn field_type(typ: &Types, options: Options) -> TokenStream {
+    match typ {
+        Types::Array(inner) => {
+            let inner = field_type(inner, options);
+
+            quote::quote! { ::std::vec::Vec<#inner> }
+        }
+        Types::Raw(raw) => {
+            let raw_ident = type_ident(raw, options);
+
+            quote::quote! { super::#raw_ident }
+        }
+        Types::Interval => quote::quote! { i64 },
+        // sea-orm reads `DECIMAL` columns as its own decimal, it has no use for the runtime's `Money`
+        Types::Money => quote::quote! { ::sea_orm::prelude::Decimal },
+        typ => crate::rust::field_type(typ, options),
+    }
+}
+
+/// A single column as is, multiple columns as a tuple, the form sea-orm takes for composite relations.
+fn column_list(columns: &[String]) -> String {
+    match columns {
+        [column] => column.clone(),
+        columns => format!("({})", columns.join(", ")),
+    }
+}
+
+fn action_name(action: &Action) -> &'static str {
+    match action {
+        Action::NoAction => "NoAction",
+        Action::Restrict => "Restrict",
+        Action::SetNull => "SetNull",
+        Action::SetDefault => "SetDefault",
+        Action::Cascade => "Cascade",
+    }
+}
+
+fn serde_derive(options: Options) -> TokenStream {
+    if options.serde {
+        gated_attr(
+            cfg!(feature = "feature-gate-serde"),
+            "rewryte-serde",
+            quote::quote! { derive(serde::Deserialize, serde::Serialize) },
+        )
+    } else {
+        quote::quote! {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::sea_orm::write_schema,
+        rewryte_parser::{parse, Context},
+    };
+
+    #[test]
+    fn composite_relation() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "table Comment {
+                Id text [primary key]
+                StoryId text
+                ChapterId text
+                foreign key (StoryId, ChapterId) references StoryChapter (StoryId, ChapterId)
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_schema(&schema, &mut buff, Default::default())
+            .expect("Unable to write schema to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let expected = quote::quote! {
+            #[sea_orm(
+                belongs_to = "super::story_chapter::Entity",
+                from = "(Column::StoryId, Column::ChapterId)",
+                to = "(super::story_chapter::Column::StoryId, super::story_chapter::Column::ChapterId)",
+                on_update = "NoAction",
+                on_delete = "NoAction"
+            )]
+            StoryChapter
+        };
+
+        assert!(rendered.contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn relation() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "table Story {
+                Id text [primary key]
+                Name text
+            }
+
+            table Chapter {
+                Id serial [primary key]
+                StoryId text [ref: Story.Id (delete: cascade)]
+                Title text!
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_schema(&schema, &mut buff, Default::default())
+            .expect("Unable to write schema to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        let expected = quote::quote! {
+            pub mod chapter {
+                use sea_orm::entity::prelude::*;
+
+                #[derive(Clone, Debug, PartialEq, Eq, sea_orm::DeriveEntityModel)]
+                #[sea_orm(table_name = "Chapter")]
+                pub struct Model {
+                    #[sea_orm(primary_key, column_name = "Id", column_type = "Integer")]
+                    pub id: i32,
+                    #[sea_orm(column_name = "StoryId", column_type = "Text")]
+                    pub story_id: ::std::string::String,
+                    #[sea_orm(column_name = "Title", column_type = "Text", nullable)]
+                    pub title: ::std::option::Option<::std::string::String>,
+                }
+
+                #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+                pub enum Relation {
+                    #[sea_orm(
+                        belongs_to = "super::story::Entity",
+                        from = "Column::StoryId",
+                        to = "super::story::Column::Id",
+                        on_update = "NoAction",
+                        on_delete = "Cascade"
+                    )]
+                    Story,
+                }
+
+                impl Related<super::story::Entity> for Entity {
+                    fn to() -> RelationDef {
+                        Relation::Story.def()
+                    }
+                }
+
+                impl ActiveModelBehavior for ActiveModel {}
+            }
+        };
+
+        let chapter = rendered
+            .lines()
+            .nth(1)
+            .expect("Missing the `Chapter` entity");
+
+        assert_eq!(expected.to_string(), chapter);
+        assert!(rendered
+            .lines()
+            .next()
+            .expect("Missing the `Story` entity")
+            .contains("primary_key , auto_increment = false"));
+    }
+
+    #[test]
+    fn pascal_case_enum_column() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "enum account_state {
+                Working
+            }
+
+            table Account {
+                Id serial [primary key]
+                State account_state
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        let mut buff = Vec::new();
+
+        write_schema(
+            &schema,
+            &mut buff,
+            crate::rust::Options {
+                pascal_case_types: true,
+                ..Default::default()
+            },
+        )
+        .expect("Unable to write schema to buffer");
+
+        let rendered = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert!(
+            rendered.contains(&quote::quote! { pub enum AccountState }.to_string()),
+            "{}",
+            rendered
+        );
+        assert!(
+            rendered.contains(&quote::quote! { pub state: super::AccountState, }.to_string()),
+            "{}",
+            rendered
+        );
+    }
+}
@@ -1,16 +1,28 @@
 use {
-    crate::Error,
-    rewryte_parser::models::{Column, ColumnDefault, Enum, ForeignKey, Item, Schema, Table, Types},
-    std::io,
+    crate::{Error, GeneratorOptions},
+    rewryte_parser::models::{
+        Column, ColumnDefault, Enum, ForeignKey, Index, Item, Schema, Table, Types,
+    },
+    std::{borrow::Cow, io},
 };
 
-pub fn write_schema(schema: &Schema, writer: &mut impl io::Write) -> Result<(), Error> {
-    for (i, item) in schema.items.iter().enumerate() {
-        write_item(item, writer)?;
+pub fn write_schema(
+    schema: &Schema,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    let items = schema
+        .items
+        .iter()
+        .filter(|item| !matches!(item, Item::Comment(_)))
+        .collect::<Vec<_>>();
+
+    for (i, item) in items.iter().enumerate() {
+        write_item(item, writer, options)?;
 
         writeln!(writer)?;
 
-        if i != schema.items.len() - 1 {
+        if i != items.len() - 1 {
             writeln!(writer)?;
         }
     }
@@ -18,10 +30,15 @@ pub fn write_schema(schema: &Schema, writer: &mut impl io::Write) -> Result<(),
     Ok(())
 }
 
-pub fn write_item(item: &Item, writer: &mut impl io::Write) -> Result<(), Error> {
+pub fn write_item(
+    item: &Item,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
     match &item {
+        Item::Comment(_) => {}
         Item::Enum(decl) => write_enum(decl, writer)?,
-        Item::Table(decl) => write_table(decl, writer)?,
+        Item::Table(decl) => write_table(decl, writer, options)?,
     }
 
     Ok(())
@@ -32,63 +49,98 @@ pub fn write_enum(_decl: &Enum, _writer: &mut impl io::Write) -> Result<(), Erro
     Ok(())
 }
 
-pub fn write_table(decl: &Table, writer: &mut impl io::Write) -> Result<(), Error> {
-    write!(writer, "CREATE TABLE")?;
+pub fn write_table(
+    decl: &Table,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    if let Some(doc) = decl.doc {
+        write_comment(doc, "", writer)?;
+    }
+
+    write!(writer, "{}", options.keyword("CREATE TABLE"))?;
 
     if decl.not_exists {
-        write!(writer, " IF NOT EXISTS")?;
+        write!(writer, " {}", options.keyword("IF NOT EXISTS"))?;
     }
 
     write!(writer, " {} (", decl.name)?;
 
     writeln!(writer)?;
 
-    for column in &decl.columns {
-        write_column(column, writer)?;
+    let serial_key = serial_primary_key(decl);
 
-        write!(writer, ",")?;
+    for (i, column) in decl.columns.iter().enumerate() {
+        if i != 0 {
+            write!(writer, ",")?;
 
-        writeln!(writer)?;
-    }
+            writeln!(writer)?;
+        }
 
-    write!(writer, "  PRIMARY KEY (")?;
+        if let Some(doc) = column.doc {
+            write_comment(doc, &options.indentation(), writer)?;
+        }
+
+        if column.typ == Types::Interval {
+            write_comment(
+                "Stored as a number of nanoseconds.",
+                &options.indentation(),
+                writer,
+            )?;
+        }
 
-    for (i, primary) in decl.primary_keys.iter().enumerate() {
-        write!(writer, "{}", primary)?;
+        write_column(column, writer, options)?;
 
-        if i != decl.primary_keys.len() - 1 {
-            write!(writer, ", ")?;
+        if serial_key == Some(column.name) {
+            write!(writer, " {}", options.keyword("PRIMARY KEY AUTOINCREMENT"))?;
         }
-    }
 
-    write!(writer, ")")?;
+        if options.enforce_varchar_length {
+            write_length_check(column, writer, options)?;
+        }
+    }
 
-    if !decl.foreign_keys.is_empty() {
+    if serial_key.is_none() {
         write!(writer, ",")?;
         writeln!(writer)?;
 
-        for (i, foreign_key) in decl.foreign_keys.iter().enumerate() {
-            write_foreign_key(foreign_key, writer)?;
+        write!(
+            writer,
+            "{}{} (",
+            options.indentation(),
+            options.keyword("PRIMARY KEY")
+        )?;
 
-            if i != decl.foreign_keys.len() - 1 {
-                write!(writer, ",")?;
+        for (i, primary) in decl.primary_keys.iter().enumerate() {
+            write!(writer, "{}", primary)?;
 
-                writeln!(writer)?;
+            if i != decl.primary_keys.len() - 1 {
+                write!(writer, ", ")?;
             }
         }
 
-        if decl.unique_keys.is_empty() {
-            writeln!(writer)?;
-        }
-    } else if decl.unique_keys.is_empty() {
+        write!(writer, ")")?;
+    }
+
+    for foreign_key in &decl.foreign_keys {
+        write!(writer, ",")?;
         writeln!(writer)?;
+
+        write_foreign_key(decl.name, foreign_key, writer, options)?;
     }
 
     if !decl.unique_keys.is_empty() {
         write!(writer, ",")?;
         writeln!(writer)?;
 
-        write!(writer, "  UNIQUE (")?;
+        write!(
+            writer,
+            "{}{} {} {} (",
+            options.indentation(),
+            options.keyword("CONSTRAINT"),
+            decl.unique_constraint_name(),
+            options.keyword("UNIQUE")
+        )?;
 
         for (i, unique) in decl.unique_keys.iter().enumerate() {
             write!(writer, "{}", unique)?;
@@ -99,64 +151,211 @@ pub fn write_table(decl: &Table, writer: &mut impl io::Write) -> Result<(), Erro
         }
 
         write!(writer, ")")?;
+    }
 
+    writeln!(writer)?;
+
+    write!(writer, ")")?;
+
+    if decl.without_rowid && !decl.primary_keys.is_empty() {
+        write!(writer, " {}", options.keyword("WITHOUT ROWID"))?;
+    }
+
+    write!(writer, ";")?;
+
+    for index in &decl.indexes {
         writeln!(writer)?;
+
+        write_index(decl, index, writer, options)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_index(
+    decl: &Table,
+    index: &Index,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(writer, "{}", options.keyword("CREATE INDEX"))?;
+
+    if index.not_exists || decl.not_exists {
+        write!(writer, " {}", options.keyword("IF NOT EXISTS"))?;
     }
 
-    write!(writer, ");")?;
+    write!(
+        writer,
+        " {} {} {} (",
+        index.name,
+        options.keyword("ON"),
+        decl.name
+    )?;
+
+    for (i, column) in index.columns.iter().enumerate() {
+        write!(writer, "{}", column)?;
+
+        if i != index.columns.len() - 1 {
+            write!(writer, ", ")?;
+        }
+    }
+
+    write!(writer, ")")?;
+
+    if let Some(predicate) = index.predicate {
+        write!(writer, " {} {}", options.keyword("WHERE"), predicate)?;
+    }
+
+    write!(writer, ";")?;
+
+    Ok(())
+}
+
+/// Finds the column that has to be written as `INTEGER PRIMARY KEY AUTOINCREMENT`.
+///
+/// SQLite only allows `AUTOINCREMENT` on a column level primary key of a rowid table, so this is only the
+/// case when the table's sole primary key is a serial column.
+fn serial_primary_key<'a>(decl: &Table<'a>) -> Option<&'a str> {
+    if decl.without_rowid {
+        return None;
+    }
+
+    match decl.primary_keys.as_slice() {
+        [primary] => decl
+            .columns
+            .iter()
+            .find(|c| c.name == *primary)
+            .filter(|c| c.typ.is_serial())
+            .map(|c| c.name),
+        _ => None,
+    }
+}
+
+/// Writes a documentation comment as `--` lines, SQLite keeps comments inside of a `CREATE TABLE` in its schema table.
+pub fn write_comment(doc: &str, indent: &str, writer: &mut impl io::Write) -> Result<(), Error> {
+    for line in crate::doc_lines(doc) {
+        write!(writer, "{}--", indent)?;
+
+        if !line.is_empty() {
+            write!(writer, " {}", line)?;
+        }
+
+        writeln!(writer)?;
+    }
 
     Ok(())
 }
 
-pub fn write_column(column: &Column, writer: &mut impl io::Write) -> Result<(), Error> {
-    write!(writer, "  {} ", column.name,)?;
+pub fn write_column(
+    column: &Column,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    write!(writer, "{}{} ", options.indentation(), column.name)?;
+
+    write_types(&column.typ, writer, options)?;
 
-    write_types(&column.typ, writer)?;
+    if let Some(collate) = column.collate {
+        write!(writer, " {} {}", options.keyword("COLLATE"), collate)?;
+    }
 
     if !column.null {
-        write!(writer, " NOT NULL")?;
+        write!(writer, " {}", options.keyword("NOT NULL"))?;
     }
 
-    write_column_default(&column.default, writer)?;
+    write_column_default(&column.default, writer, options)?;
 
     Ok(())
 }
 
-pub fn write_types(types: &Types, writer: &mut impl io::Write) -> Result<(), Error> {
-    write!(
-        writer,
-        "{}",
-        match types {
-            Types::Char | Types::Text => "TEXT",
-            Types::Varchar => "VARCHAR",
-            Types::Number | Types::SmallInt | Types::MediumInt | Types::Int | Types::Serial => {
-                "INTEGER"
-            }
-            Types::BigInt => "BIGINT",
-            Types::Float | Types::Real | Types::Numeric => "REAL",
-            Types::Decimal => "DECIMAL",
-            Types::DateTime => "DATETIME",
-            Types::Boolean => "BOOLEAN",
-            Types::Raw(raw) => raw,
-        }
-    )?;
+/// Writes a column's type, raw types are written as they are in the schema whatever the keyword case.
+pub fn write_types(
+    types: &Types,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    match types {
+        Types::Raw(raw) => write!(writer, "{}", raw)?,
+        types => write!(writer, "{}", options.keyword(&type_name(types)?))?,
+    }
+
+    Ok(())
+}
+
+/// SQLite ignores the length of a `VARCHAR`, so it is only enforced by a `CHECK` on the column.
+pub fn write_length_check(
+    column: &Column,
+    writer: &mut impl io::Write,
+    options: GeneratorOptions,
+) -> Result<(), Error> {
+    if let Types::Varchar(Some(length)) = column.typ {
+        write!(
+            writer,
+            " {} (length({}) <= {})",
+            options.keyword("CHECK"),
+            column.name,
+            length
+        )?;
+    }
 
     Ok(())
 }
 
+/// The SQLite type a column type is created as, arrays are not supported.
+pub fn type_name<'t>(types: &'t Types) -> Result<Cow<'t, str>, Error> {
+    Ok(Cow::Borrowed(match types {
+        Types::Char | Types::Text => "TEXT",
+        Types::Varchar(None) => "VARCHAR",
+        Types::Varchar(Some(length)) => return Ok(Cow::Owned(format!("VARCHAR({})", length))),
+        Types::Number
+        | Types::SmallInt
+        | Types::MediumInt
+        | Types::Int
+        | Types::Serial
+        | Types::SmallSerial
+        | Types::BigSerial => "INTEGER",
+        // SQLite has no interval type, so it is stored as nanoseconds
+        Types::BigInt | Types::Interval => "BIGINT",
+        Types::Float | Types::Real | Types::Double | Types::Numeric => "REAL",
+        Types::Decimal => "DECIMAL",
+        Types::Money => "DECIMAL(19, 4)",
+        Types::DateTime => "DATETIME",
+        Types::Date => "DATE",
+        Types::Time => "TIME",
+        Types::Boolean => "BOOLEAN",
+        Types::Uuid => "BLOB",
+        Types::Array(_) => return Err(Error::UnsupportedType("SQLite", types.to_string())),
+        Types::Raw(raw) => raw,
+    }))
+}
+
 pub fn write_column_default(
     column_default: &ColumnDefault,
     writer: &mut impl io::Write,
+    options: GeneratorOptions,
 ) -> Result<(), Error> {
     if column_default != &ColumnDefault::None {
-        write!(writer, " DEFAULT")?;
+        write!(writer, " {}", options.keyword("DEFAULT"))?;
 
         match column_default {
             ColumnDefault::Now => {
-                write!(writer, " (DATETIME('now', 'utc'))")?;
+                write!(writer, " ({}('now', 'utc'))", options.keyword("DATETIME"))?;
+            }
+            ColumnDefault::CurrentDate => {
+                write!(writer, " {}", options.keyword("CURRENT_DATE"))?;
+            }
+            ColumnDefault::CurrentTime => {
+                write!(writer, " {}", options.keyword("CURRENT_TIME"))?;
             }
             ColumnDefault::Null => {
-                write!(writer, " NULL")?;
+                write!(writer, " {}", options.keyword("NULL"))?;
+            }
+            ColumnDefault::Uuid => {
+                // UUIDs are stored as blobs, so 16 random bytes, these are not a valid version 4 UUID
+                write!(writer, " (randomblob(16))")?;
+            }
+            ColumnDefault::Bool(value) => {
+                write!(writer, " {}", if *value { 1 } else { 0 })?;
             }
             ColumnDefault::Raw(raw) => {
                 write!(writer, " {}", raw)?;
@@ -169,54 +368,492 @@ pub fn write_column_default(
 }
 
 pub fn write_foreign_key(
+    table: &str,
     foreign_key: &ForeignKey,
     writer: &mut impl io::Write,
+    options: GeneratorOptions,
 ) -> Result<(), Error> {
     write!(
         writer,
-        "  FOREIGN KEY ({}) REFERENCES {}({}) ON UPDATE {} ON DELETE {}",
-        foreign_key.local,
+        "{}{} {} {} ({}) {} {}({}) {} {} {} {}",
+        options.indentation(),
+        options.keyword("CONSTRAINT"),
+        foreign_key.constraint_name(table),
+        options.keyword("FOREIGN KEY"),
+        foreign_key.local.join(", "),
+        options.keyword("REFERENCES"),
         foreign_key.table,
-        foreign_key.foreign,
-        foreign_key.update,
-        foreign_key.delete,
+        foreign_key.foreign.join(", "),
+        options.keyword("ON UPDATE"),
+        options.keyword(&foreign_key.update.to_string()),
+        options.keyword("ON DELETE"),
+        options.keyword(&foreign_key.delete.to_string()),
     )?;
 
+    if foreign_key.deferrable {
+        write!(
+            writer,
+            " {}",
+            options.keyword("DEFERRABLE INITIALLY DEFERRED")
+        )?;
+    }
+
     Ok(())
 }
 
 // TODO: Maybe I can clean this up
 #[cfg(test)]
 mod tests {
-    use {crate::sqlite::write_table, rewryte_parser::models::*};
+    use {
+        crate::{
+            sqlite::{
+                write_column, write_column_default, write_foreign_key, write_table, write_types,
+            },
+            GeneratorOptions,
+        },
+        rewryte_parser::models::*,
+    };
+
+    fn assert_type(expected: &str, types: Types) {
+        let mut buff = Vec::new();
+
+        write_types(&types, &mut buff, GeneratorOptions::default())
+            .expect("Unable to write type to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(expected, utf8_buff.as_str());
+    }
+
+    #[test]
+    fn uuid() {
+        assert_type("BLOB", Types::Uuid);
+    }
+
+    #[test]
+    fn serial_types() {
+        assert_type("INTEGER", Types::Serial);
+        assert_type("INTEGER", Types::SmallSerial);
+        assert_type("INTEGER", Types::BigSerial);
+    }
+
+    #[test]
+    fn floats() {
+        assert_type("REAL", Types::Real);
+        assert_type("REAL", Types::Double);
+    }
+
+    #[test]
+    fn money() {
+        assert_type("DECIMAL(19, 4)", Types::Money);
+    }
+
+    #[test]
+    fn date_time() {
+        assert_type("DATETIME", Types::DateTime);
+        assert_type("DATE", Types::Date);
+        assert_type("TIME", Types::Time);
+        assert_type("BIGINT", Types::Interval);
+    }
+
+    #[test]
+    fn current_date_time_default() {
+        let cases = [
+            (ColumnDefault::CurrentDate, " DEFAULT CURRENT_DATE"),
+            (ColumnDefault::CurrentTime, " DEFAULT CURRENT_TIME"),
+        ];
+
+        for (default, expected) in cases.iter() {
+            let mut buff = Vec::new();
+
+            write_column_default(default, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write default to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(*expected, utf8_buff.as_str());
+        }
+    }
+
+    #[test]
+    fn uuid_default() {
+        let mut buff = Vec::new();
+
+        write_column_default(&ColumnDefault::Uuid, &mut buff, GeneratorOptions::default())
+            .expect("Unable to write default to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(" DEFAULT (randomblob(16))", utf8_buff.as_str());
+    }
+
+    #[test]
+    fn bool_default() {
+        for (value, expected) in [(true, " DEFAULT 1"), (false, " DEFAULT 0")].iter() {
+            let mut buff = Vec::new();
+
+            write_column_default(
+                &ColumnDefault::Bool(*value),
+                &mut buff,
+                GeneratorOptions::default(),
+            )
+            .expect("Unable to write default to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(*expected, utf8_buff.as_str());
+        }
+    }
+
+    #[test]
+    fn collate() {
+        let column = Column {
+            name: "Name",
+            doc: None,
+            typ: Types::Text,
+            null: false,
+            default: ColumnDefault::Raw("'a'"),
+            skip: vec![],
+            rust_name: None,
+            collate: Some("nocase"),
+            searchable: false,
+            version: false,
+        };
+
+        let mut buff = Vec::new();
+
+        write_column(&column, &mut buff, GeneratorOptions::default())
+            .expect("Unable to write column to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "  Name TEXT COLLATE nocase NOT NULL DEFAULT 'a'",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn null_default() {
+        let cases = [
+            (false, ColumnDefault::None, "  Value TEXT NOT NULL"),
+            (true, ColumnDefault::None, "  Value TEXT"),
+            (true, ColumnDefault::Null, "  Value TEXT DEFAULT NULL"),
+        ];
+
+        for (null, default, expected) in cases.iter() {
+            let column = Column {
+                name: "Value",
+                doc: None,
+                typ: Types::Text,
+                null: *null,
+                default: default.clone(),
+                skip: vec![],
+                rust_name: None,
+                collate: None,
+                searchable: false,
+                version: false,
+            };
+
+            let mut buff = Vec::new();
+
+            write_column(&column, &mut buff, GeneratorOptions::default())
+                .expect("Unable to write column to buffer");
+
+            let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+            assert_eq!(*expected, utf8_buff.as_str());
+        }
+    }
+
+    #[test]
+    fn deferrable() {
+        let foreign_key = ForeignKey {
+            local: vec!["ParentId"],
+            table: "Node",
+            foreign: vec!["Id"],
+            delete: Action::default(),
+            update: Action::default(),
+            deferrable: true,
+            name: None,
+        };
+
+        let mut buff = Vec::new();
+
+        write_foreign_key("Node", &foreign_key, &mut buff, GeneratorOptions::default())
+            .expect("Unable to write foreign key to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "  CONSTRAINT Node_ParentId_fkey FOREIGN KEY (ParentId) REFERENCES Node(Id) ON UPDATE NO ACTION ON DELETE NO ACTION DEFERRABLE INITIALLY DEFERRED",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn multi_column_foreign_key() {
+        let foreign_key = ForeignKey {
+            local: vec!["StoryId", "ChapterId"],
+            table: "StoryChapter",
+            foreign: vec!["StoryId", "ChapterId"],
+            delete: Action::default(),
+            update: Action::default(),
+            deferrable: false,
+            name: Some("fk_comment_chapter"),
+        };
+
+        let mut buff = Vec::new();
+
+        write_foreign_key(
+            "Comment",
+            &foreign_key,
+            &mut buff,
+            GeneratorOptions::default(),
+        )
+        .expect("Unable to write foreign key to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "  CONSTRAINT fk_comment_chapter FOREIGN KEY (StoryId, ChapterId) REFERENCES StoryChapter(StoryId, ChapterId) ON UPDATE NO ACTION ON DELETE NO ACTION",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn array() {
+        let mut buff = Vec::new();
+
+        match write_types(
+            &Types::Array(Box::new(Types::Text)),
+            &mut buff,
+            GeneratorOptions::default(),
+        ) {
+            Err(crate::Error::UnsupportedType("SQLite", typ)) => assert_eq!("text[]", typ),
+            res => panic!("expected an unsupported type error, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn serial() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                Column {
+                    name: "Id",
+                    doc: None,
+                    typ: Types::BigSerial,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+                Column {
+                    name: "Name",
+                    doc: None,
+                    typ: Types::Text,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec!["Name"],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let mut buff = Vec::new();
+
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "CREATE TABLE Example (
+  Id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+  Name TEXT NOT NULL,
+  CONSTRAINT Example_Name_key UNIQUE (Name)
+);",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn without_rowid() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                Column {
+                    name: "Id",
+                    doc: None,
+                    typ: Types::Serial,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+                Column {
+                    name: "Name",
+                    doc: None,
+                    typ: Types::Text,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: true,
+            indexes: vec![],
+        };
+
+        let mut buff = Vec::new();
+
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "CREATE TABLE Example (
+  Id INTEGER NOT NULL,
+  Name TEXT NOT NULL,
+  PRIMARY KEY (Id)
+) WITHOUT ROWID;",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn serial_composite_key() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                Column {
+                    name: "Id",
+                    doc: None,
+                    typ: Types::Serial,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+                Column {
+                    name: "Name",
+                    doc: None,
+                    typ: Types::Text,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+            ],
+            primary_keys: vec!["Id", "Name"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let mut buff = Vec::new();
+
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "CREATE TABLE Example (
+  Id INTEGER NOT NULL,
+  Name TEXT NOT NULL,
+  PRIMARY KEY (Id, Name)
+);",
+            utf8_buff.as_str()
+        );
+    }
 
     #[test]
     fn simple() {
         let table = Table {
             name: "Example",
+            doc: None,
             not_exists: true,
             columns: vec![
                 Column {
                     name: "Id",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Name",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
             ],
             primary_keys: vec!["Id"],
             foreign_keys: vec![],
             unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
         };
 
         let mut buff = Vec::new();
 
-        write_table(&table, &mut buff).expect("Unable to write table to buffer");
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
 
         let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
 
@@ -234,29 +871,47 @@ mod tests {
     fn multiple_primary_keys() {
         let table = Table {
             name: "Example",
+            doc: None,
             not_exists: true,
             columns: vec![
                 Column {
                     name: "Key",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Value",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
             ],
             primary_keys: vec!["Key", "Value"],
             foreign_keys: vec![],
             unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
         };
 
         let mut buff = Vec::new();
 
-        write_table(&table, &mut buff).expect("Unable to write table to buffer");
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
 
         let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
 
@@ -274,41 +929,67 @@ mod tests {
     fn foreign_keys() {
         let table = Table {
             name: "Example",
+            doc: None,
             not_exists: true,
             columns: vec![
                 Column {
                     name: "Id",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Name",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Other",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
             ],
             primary_keys: vec!["Id"],
             foreign_keys: vec![ForeignKey {
-                local: "Other",
+                local: vec!["Other"],
                 table: "Other",
-                foreign: "Id",
+                foreign: vec!["Id"],
                 delete: Action::default(),
                 update: Action::default(),
+                deferrable: false,
+                name: None,
             }],
             unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
         };
 
         let mut buff = Vec::new();
 
-        write_table(&table, &mut buff).expect("Unable to write table to buffer");
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
 
         let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
 
@@ -318,7 +999,7 @@ mod tests {
   Name TEXT NOT NULL,
   Other TEXT NOT NULL,
   PRIMARY KEY (Id),
-  FOREIGN KEY (Other) REFERENCES Other(Id) ON UPDATE NO ACTION ON DELETE NO ACTION
+  CONSTRAINT Example_Other_fkey FOREIGN KEY (Other) REFERENCES Other(Id) ON UPDATE NO ACTION ON DELETE NO ACTION
 );",
             utf8_buff.as_str()
         );
@@ -328,35 +1009,59 @@ mod tests {
     fn unique_keys() {
         let table = Table {
             name: "Example",
+            doc: None,
             not_exists: true,
             columns: vec![
                 Column {
                     name: "Id",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Key",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Value",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
             ],
             primary_keys: vec!["Id"],
             foreign_keys: vec![],
             unique_keys: vec!["Key"],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
         };
 
         let mut buff = Vec::new();
 
-        write_table(&table, &mut buff).expect("Unable to write table to buffer");
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
 
         let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
 
@@ -366,7 +1071,7 @@ mod tests {
   Key TEXT NOT NULL,
   Value TEXT NOT NULL,
   PRIMARY KEY (Id),
-  UNIQUE (Key)
+  CONSTRAINT Example_Key_key UNIQUE (Key)
 );",
             utf8_buff.as_str()
         );
@@ -376,41 +1081,67 @@ mod tests {
     fn unique_keys_foreign_keys() {
         let table = Table {
             name: "Example",
+            doc: None,
             not_exists: true,
             columns: vec![
                 Column {
                     name: "Id",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Name",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
                 Column {
                     name: "Other",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 },
             ],
             primary_keys: vec!["Id"],
             foreign_keys: vec![ForeignKey {
-                local: "Other",
+                local: vec!["Other"],
                 table: "Other",
-                foreign: "Id",
+                foreign: vec!["Id"],
                 delete: Action::default(),
                 update: Action::default(),
+                deferrable: false,
+                name: Some("fk_example_other"),
             }],
             unique_keys: vec!["Name"],
+            unique_name: Some("uq_example_name"),
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
         };
 
         let mut buff = Vec::new();
 
-        write_table(&table, &mut buff).expect("Unable to write table to buffer");
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
 
         let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
 
@@ -420,10 +1151,289 @@ mod tests {
   Name TEXT NOT NULL,
   Other TEXT NOT NULL,
   PRIMARY KEY (Id),
-  FOREIGN KEY (Other) REFERENCES Other(Id) ON UPDATE NO ACTION ON DELETE NO ACTION,
-  UNIQUE (Name)
+  CONSTRAINT fk_example_other FOREIGN KEY (Other) REFERENCES Other(Id) ON UPDATE NO ACTION ON DELETE NO ACTION,
+  CONSTRAINT uq_example_name UNIQUE (Name)
+);",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn partial_index() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![Column {
+                name: "Value",
+                doc: None,
+                typ: Types::Text,
+                null: true,
+                default: ColumnDefault::None,
+                skip: vec![],
+                rust_name: None,
+                collate: None,
+                searchable: false,
+                version: false,
+            }],
+            primary_keys: vec!["Value"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![Index {
+                name: "ValueIndex",
+                not_exists: false,
+                columns: vec!["Value"],
+                predicate: Some("Value IS NOT NULL"),
+            }],
+        };
+
+        let mut buff = Vec::new();
+
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "CREATE TABLE Example (
+  Value TEXT,
+  PRIMARY KEY (Value)
+);
+CREATE INDEX ValueIndex ON Example (Value) WHERE Value IS NOT NULL;",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn guarded_index() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![Column {
+                name: "Value",
+                doc: None,
+                typ: Types::Text,
+                null: false,
+                default: ColumnDefault::None,
+                skip: vec![],
+                rust_name: None,
+                collate: None,
+                searchable: false,
+                version: false,
+            }],
+            primary_keys: vec!["Value"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![Index {
+                name: "ValueIndex",
+                not_exists: true,
+                columns: vec!["Value"],
+                predicate: None,
+            }],
+        };
+
+        let mut buff = Vec::new();
+
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "CREATE TABLE Example (
+  Value TEXT NOT NULL,
+  PRIMARY KEY (Value)
+);
+CREATE INDEX IF NOT EXISTS ValueIndex ON Example (Value);",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn index_of_guarded_table() {
+        // Built without the parser, which would have set the index's own flag
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: true,
+            columns: vec![Column {
+                name: "Value",
+                doc: None,
+                typ: Types::Text,
+                null: false,
+                default: ColumnDefault::None,
+                skip: vec![],
+                rust_name: None,
+                collate: None,
+                searchable: false,
+                version: false,
+            }],
+            primary_keys: vec!["Value"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![Index {
+                name: "ValueIndex",
+                not_exists: false,
+                columns: vec!["Value"],
+                predicate: None,
+            }],
+        };
+
+        let mut buff = Vec::new();
+
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "CREATE TABLE IF NOT EXISTS Example (
+  Value TEXT NOT NULL,
+  PRIMARY KEY (Value)
+);
+CREATE INDEX IF NOT EXISTS ValueIndex ON Example (Value);",
+            utf8_buff.as_str()
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let table = Table {
+            name: "Example",
+            doc: Some("An example table"),
+            not_exists: true,
+            columns: vec![
+                Column {
+                    name: "Id",
+                    doc: Some("The identifier"),
+                    typ: Types::Text,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+                Column {
+                    name: "Name",
+                    doc: None,
+                    typ: Types::Text,
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+            ],
+            primary_keys: vec!["Id"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let mut buff = Vec::new();
+
+        write_table(&table, &mut buff, Default::default())
+            .expect("Unable to write table to buffer");
+
+        let utf8_buff = String::from_utf8(buff).expect("Unable to convert buff into string");
+
+        assert_eq!(
+            "-- An example table
+CREATE TABLE IF NOT EXISTS Example (
+  -- The identifier
+  Id TEXT NOT NULL,
+  Name TEXT NOT NULL,
+  PRIMARY KEY (Id)
 );",
             utf8_buff.as_str()
         );
     }
+
+    #[test]
+    fn enforce_varchar_length() {
+        let table = Table {
+            name: "Example",
+            doc: None,
+            not_exists: false,
+            columns: vec![
+                Column {
+                    name: "Name",
+                    doc: None,
+                    typ: Types::Varchar(Some(10)),
+                    null: false,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+                Column {
+                    name: "Bio",
+                    doc: None,
+                    typ: Types::Varchar(None),
+                    null: true,
+                    default: ColumnDefault::None,
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                },
+            ],
+            primary_keys: vec!["Name"],
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            unique_name: None,
+            unique_nulls_not_distinct: false,
+            without_rowid: false,
+            indexes: vec![],
+        };
+
+        let write = |options| {
+            let mut buff = Vec::new();
+
+            write_table(&table, &mut buff, options).expect("Unable to write table to buffer");
+
+            String::from_utf8(buff).expect("Unable to convert buff into string")
+        };
+
+        assert_eq!(
+            "CREATE TABLE Example (
+  Name VARCHAR(10) NOT NULL CHECK (length(Name) <= 10),
+  Bio VARCHAR,
+  PRIMARY KEY (Name)
+);",
+            write(GeneratorOptions {
+                enforce_varchar_length: true,
+                ..GeneratorOptions::default()
+            })
+        );
+
+        assert_eq!(
+            "CREATE TABLE Example (
+  Name VARCHAR(10) NOT NULL,
+  Bio VARCHAR,
+  PRIMARY KEY (Name)
+);",
+            write(GeneratorOptions::default())
+        );
+    }
 }
@@ -0,0 +1,6 @@
+// The macros read these when they are compiled, rebuilding them, and every crate using them, when they change
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=REWRYTE_MAX_DIAGNOSTICS");
+    println!("cargo:rerun-if-env-changed=REWRYTE_DIAGNOSTIC_SOURCE");
+}
@@ -1,18 +1,10 @@
 extern crate proc_macro;
 
 use {
-    codespan_reporting::{
-        files::SimpleFiles,
-        term::{self, termcolor::NoColor, Config},
-    },
     proc_macro::TokenStream,
-    rewryte_generator::{Format, FormatType},
-    rewryte_parser::parser::{parse, Context},
-    std::{
-        fs,
-        io::{BufWriter, ErrorKind},
-        path::PathBuf,
-    },
+    rewryte_generator::{Format, FormatType, StringWriter, ToSchemaString},
+    rewryte_parser::{parse_and_report_with, Context, ReportOptions},
+    std::{convert::TryFrom, fs, io::ErrorKind, path::PathBuf},
     syn::{
         parse::{Parse, ParseStream},
         punctuated::Punctuated,
@@ -21,10 +13,125 @@ use {
     },
 };
 
+/// Resolves a schema path the same way `include_str!` does, relative to the
+/// invoking source file, falling back to `CARGO_MANIFEST_DIR` when the file
+/// can't be found there.
+fn resolve_path(lit_path: &LitStr) -> PathBuf {
+    let value = lit_path.value();
+
+    let source_relative = proc_macro::Span::call_site()
+        .local_file()
+        .and_then(|file| file.parent().map(|parent| parent.join(&value)));
+
+    match source_relative {
+        Some(path) if path.exists() => path,
+        _ => {
+            let crate_root = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+            PathBuf::from(crate_root).join(value)
+        }
+    }
+}
+
+/// How many diagnostics a failed schema puts in the compile error when `REWRYTE_MAX_DIAGNOSTICS` is not set.
+const MAX_DIAGNOSTICS: usize = 5;
+
+/// Reads how the diagnostics of a failed schema are rendered from the environment rewryte-macro was compiled in.
+///
+/// `REWRYTE_MAX_DIAGNOSTICS` sets how many are rendered, `0` renders every one, and
+/// `REWRYTE_DIAGNOSTIC_SOURCE=false` leaves out the source lines. The build script reruns when either changes, so
+/// the crates using the macros are expanded again with the new values.
+fn report_options() -> ReportOptions {
+    report_options_from(
+        option_env!("REWRYTE_MAX_DIAGNOSTICS"),
+        option_env!("REWRYTE_DIAGNOSTIC_SOURCE"),
+    )
+}
+
+fn report_options_from(max_diagnostics: Option<&str>, source: Option<&str>) -> ReportOptions {
+    let max_diagnostics = match max_diagnostics.map(|value| value.trim().parse::<usize>()) {
+        Some(Ok(0)) => None,
+        Some(Ok(max)) => Some(max),
+        Some(Err(_)) | None => Some(MAX_DIAGNOSTICS),
+    };
+
+    let source = !matches!(source, Some("0") | Some("false"));
+
+    ReportOptions {
+        max_diagnostics,
+        source,
+    }
+}
+
+/// Warns about the parts of the schema the model options leave out.
+///
+/// A macro can not emit warnings on stable, so each is the use of a deprecated constant with the warning as its note.
+fn warnings(
+    input: &str,
+    schema: &rewryte_parser::models::Schema,
+    options: rewryte_generator::rust::Options,
+) -> TokenStream {
+    let mut ctx = Context::new(0);
+
+    rewryte_generator::rust::check_options(&mut ctx, input, schema, options);
+
+    let notes = ctx.warnings().iter().map(|diag| {
+        diag.labels
+            .iter()
+            .fold(diag.message.clone(), |note, label| {
+                format!("{}: {}", note, label.message)
+            })
+    });
+
+    TokenStream::from(quote::quote! {
+        #(
+            const _: () = {
+                #[deprecated(note = #notes)]
+                #[allow(non_upper_case_globals)]
+                const rewryte_warning: () = ();
+
+                rewryte_warning
+            };
+        )*
+    })
+}
+
 fn error(path: LitStr, msg: impl std::fmt::Display) -> TokenStream {
     TokenStream::from(syn::Error::new_spanned(path, msg).to_compile_error())
 }
 
+/// Parses an optional `, ["extra", ...]` list of model options, an unknown option is an error.
+fn parse_options(input: ParseStream) -> Result<rewryte_generator::rust::Options> {
+    let mut options = rewryte_generator::rust::Options::default();
+
+    if !input.peek(syn::token::Comma) {
+        return Ok(options);
+    }
+
+    let _comma = <Comma as Parse>::parse(input)?;
+
+    if !input.peek(syn::token::Bracket) {
+        return Ok(options);
+    }
+
+    let content;
+
+    let _bracket = syn::bracketed!(content in input);
+
+    let parsed = Punctuated::<LitStr, Comma>::parse_terminated(&content)?;
+
+    for lit in parsed {
+        if !options.enable(&lit.value()) {
+            return Err(syn::Error::new_spanned(
+                &lit,
+                format!("Unknown option `{}`", lit.value()),
+            ));
+        }
+    }
+
+    Ok(options)
+}
+
 #[proc_macro]
 pub fn schema(input: TokenStream) -> TokenStream {
     let input = match syn::parse::<FormatInput>(input) {
@@ -45,63 +152,30 @@ pub fn schema(input: TokenStream) -> TokenStream {
         }
     };
 
-    let contents_str = contents.as_str();
-
-    let mut files = SimpleFiles::new();
-
-    let file_id = files.add("<inline>", contents_str);
-
-    let mut ctx = Context::new(file_id);
-
-    match parse(&mut ctx, contents_str) {
+    match parse_and_report_with("<inline>", &contents, report_options()) {
         Ok(schema) => {
-            let mut writer = BufWriter::new(Vec::new());
-
-            if let Err(err) = schema.fmt(&mut writer, input.format) {
-                return error(input.lit_path, err);
-            }
-
-            let inner = match writer.into_inner() {
-                Ok(vec) => vec,
-                Err(err) => {
-                    return error(input.lit_path, err);
+            let rendered = match input.format {
+                FormatType::Rust => schema.to_rust_string(Default::default()),
+                typ if typ.is_sql() => schema.to_sql_string(typ),
+                typ => {
+                    let mut rendered = String::new();
+
+                    schema
+                        .fmt(&mut StringWriter(&mut rendered), typ)
+                        .map(|_| rendered)
                 }
             };
 
-            let rendered = match String::from_utf8(inner) {
-                Ok(string) => string,
-                Err(err) => {
-                    return error(input.lit_path, err);
-                }
+            let rendered = match rendered {
+                Ok(rendered) => rendered,
+                Err(err) => return error(input.lit_path, err),
             };
 
             TokenStream::from(quote::quote! {
                 #rendered
             })
         }
-        Err(err) => {
-            let config = Config::default();
-
-            let mut writer = NoColor::new(Vec::new());
-
-            for diag in ctx.diagnostics() {
-                if let Err(err) = term::emit(&mut writer, &config, &files, diag) {
-                    return error(input.lit_path, err);
-                }
-            }
-
-            let emit_string = match String::from_utf8(writer.into_inner()) {
-                Ok(string) => string,
-                Err(err) => {
-                    return error(input.lit_path, err);
-                }
-            };
-
-            TokenStream::from(
-                syn::Error::new_spanned(input.lit_path, format!("{}\n\n{}", err, emit_string))
-                    .to_compile_error(),
-            )
-        }
+        Err(report) => error(input.lit_path, report),
     }
 }
 
@@ -115,15 +189,12 @@ impl Parse for FormatInput {
     fn parse(input: ParseStream) -> Result<Self> {
         let lit_format = <LitStr as Parse>::parse(input)?;
 
-        let format = match lit_format.value().as_str() {
-            "mysql" => FormatType::MySQL,
-            "postgresql" => FormatType::PostgreSQL,
-            "sqlite" => FormatType::SQLite,
-            "rust" => FormatType::Rust,
-            _ => {
+        let format = match FormatType::try_from(lit_format.value().as_str()) {
+            Ok(format) => format,
+            Err(_) => {
                 return Err(syn::Error::new_spanned(
                     lit_format,
-                    "Only the values `mysql`, `postgresql`, `sqlite`, and `rust` are allowed",
+                    "Only the values `mysql`, `postgresql`, `sqlite`, `rust`, and `sea-orm` are allowed",
                 ))
             }
         };
@@ -132,9 +203,7 @@ impl Parse for FormatInput {
 
         let lit_path = <LitStr as Parse>::parse(input)?;
 
-        let crate_root = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-
-        let path = PathBuf::from(crate_root).join(lit_path.value());
+        let path = resolve_path(&lit_path);
 
         Ok(FormatInput {
             format,
@@ -164,124 +233,196 @@ pub fn models(input: TokenStream) -> TokenStream {
         }
     };
 
-    let contents_str = contents.as_str();
-
-    let mut files = SimpleFiles::new();
-
-    let file_id = files.add("<inline>", contents_str);
+    match parse_and_report_with("<inline>", &contents, report_options()) {
+        Ok(schema) => {
+            let options = input.options;
 
-    let mut ctx = Context::new(file_id);
+            let rendered = match schema.to_rust_string(options) {
+                Ok(rendered) => rendered,
+                Err(err) => return error(input.lit_path, err),
+            };
 
-    match parse(&mut ctx, contents_str) {
-        Ok(schema) => {
-            let mut writer = BufWriter::new(Vec::new());
+            let mut stream: TokenStream = match rendered.parse() {
+                Ok(stream) => stream,
+                Err(err) => return error(input.lit_path, err),
+            };
 
-            let mut options = rewryte_generator::rust::Options::default();
+            stream.extend(warnings(&contents, &schema, options));
 
-            if let Some(extra) = input.extra {
-                let mut mapped = extra.iter().map(LitStr::value);
+            stream
+        }
+        Err(report) => error(input.lit_path, report),
+    }
+}
 
-                if mapped.by_ref().any(|value| &*value == "juniper") {
-                    options.juniper = true;
-                }
+struct ModelInput {
+    lit_path: LitStr,
+    path: PathBuf,
+    options: rewryte_generator::rust::Options,
+}
 
-                if mapped.by_ref().any(|value| &*value == "serde") {
-                    options.serde = true;
-                }
+impl Parse for ModelInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit_path = <LitStr as Parse>::parse(input)?;
 
-                if mapped.by_ref().any(|value| &*value == "sqlx") {
-                    options.sqlx = true;
-                }
-            }
+        let path = resolve_path(&lit_path);
 
-            if let Err(err) = rewryte_generator::rust::write_schema(&schema, &mut writer, options) {
-                return error(input.lit_path, err);
-            }
+        let options = parse_options(input)?;
 
-            let inner = match writer.into_inner() {
-                Ok(vec) => vec,
-                Err(err) => {
-                    return error(input.lit_path, err);
-                }
-            };
+        Ok(ModelInput {
+            lit_path,
+            path,
+            options,
+        })
+    }
+}
 
-            let rendered = match String::from_utf8(inner) {
-                Ok(string) => string,
-                Err(err) => {
-                    return error(input.lit_path, err);
-                }
-            };
+/// Generates the models like `models!`, along with a `SCHEMA_SQL` constant holding the schema in a SQL format.
+///
+/// The schema file is only read and parsed once for both. The format is `mysql`, `postgresql`, or `sqlite`.
+#[proc_macro]
+pub fn models_and_schema(input: TokenStream) -> TokenStream {
+    let input = match syn::parse::<ModelSchemaInput>(input) {
+        Ok(syntax_tree) => syntax_tree,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
 
-            match rendered.parse() {
-                Ok(stream) => stream,
-                Err(err) => error(input.lit_path, err),
-            }
+    let contents = match fs::read_to_string(&input.path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return error(
+                input.lit_path,
+                format!("File does not exist: {}", input.path.display()),
+            );
         }
         Err(err) => {
-            let config = Config::default();
+            return error(input.lit_path, err);
+        }
+    };
 
-            let mut writer = NoColor::new(Vec::new());
+    match parse_and_report_with("<inline>", &contents, report_options()) {
+        Ok(schema) => {
+            let options = input.options;
 
-            for diag in ctx.diagnostics() {
-                if let Err(err) = term::emit(&mut writer, &config, &files, diag) {
-                    return error(input.lit_path, err);
-                }
-            }
+            let models = match schema.to_rust_string(options) {
+                Ok(models) => models,
+                Err(err) => return error(input.lit_path, err),
+            };
 
-            let emit_string = match String::from_utf8(writer.into_inner()) {
-                Ok(string) => string,
-                Err(err) => {
-                    return error(input.lit_path, err);
-                }
+            let sql = match schema.to_sql_string(input.format) {
+                Ok(sql) => sql,
+                Err(err) => return error(input.lit_path, err),
             };
 
-            error(input.lit_path, format!("{}\n\n{}", err, emit_string))
+            let mut stream: TokenStream = match models.parse() {
+                Ok(stream) => stream,
+                Err(err) => return error(input.lit_path, err),
+            };
+
+            stream.extend(TokenStream::from(quote::quote! {
+                pub const SCHEMA_SQL: &str = #sql;
+            }));
+            stream.extend(warnings(&contents, &schema, options));
+
+            stream
         }
+        Err(report) => error(input.lit_path, report),
     }
 }
 
-struct ModelInput {
+struct ModelSchemaInput {
+    format: FormatType,
     lit_path: LitStr,
     path: PathBuf,
-    extra: Option<Vec<LitStr>>,
+    options: rewryte_generator::rust::Options,
 }
 
-impl Parse for ModelInput {
+impl Parse for ModelSchemaInput {
     fn parse(input: ParseStream) -> Result<Self> {
         let lit_path = <LitStr as Parse>::parse(input)?;
 
-        let crate_root = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let _ = input.parse::<Token![,]>()?;
+
+        let lit_format = <LitStr as Parse>::parse(input)?;
+
+        let format = match FormatType::try_from(lit_format.value().as_str()) {
+            Ok(format) if format.is_sql() => format,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    lit_format,
+                    "Only the values `mysql`, `postgresql`, and `sqlite` are allowed",
+                ))
+            }
+        };
+
+        let options = parse_options(input)?;
 
-        let path = PathBuf::from(crate_root).join(lit_path.value());
+        let path = resolve_path(&lit_path);
 
-        let extra = if input.peek(syn::token::Comma) {
-            let _comma = <Comma as Parse>::parse(input)?;
+        Ok(ModelSchemaInput {
+            format,
+            lit_path,
+            path,
+            options,
+        })
+    }
+}
 
-            if input.peek(syn::token::Bracket) {
-                let content;
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{report_options_from, ModelSchemaInput, MAX_DIAGNOSTICS},
+        rewryte_parser::ReportOptions,
+    };
 
-                let _bracket = syn::bracketed!(content in input);
+    #[test]
+    fn report_options_from_environment() {
+        let options = |max_diagnostics, source| ReportOptions {
+            max_diagnostics,
+            source,
+        };
 
-                let parsed = Punctuated::<LitStr, Comma>::parse_terminated(&content)?;
+        assert_eq!(
+            options(Some(MAX_DIAGNOSTICS), true),
+            report_options_from(None, None)
+        );
+        assert_eq!(
+            options(Some(2), true),
+            report_options_from(Some(" 2 "), None)
+        );
+        assert_eq!(options(None, true), report_options_from(Some("0"), None));
+        assert_eq!(
+            options(Some(MAX_DIAGNOSTICS), false),
+            report_options_from(Some("many"), Some("false"))
+        );
+        assert_eq!(
+            options(Some(MAX_DIAGNOSTICS), false),
+            report_options_from(None, Some("0"))
+        );
+    }
 
-                let mut items = Vec::with_capacity(parsed.len());
+    #[test]
+    fn models_and_schema_rejects_other_formats() {
+        let err = match syn::parse_str::<ModelSchemaInput>(r#""./schema.dal", "rust""#) {
+            Ok(_) => panic!("Rust was accepted"),
+            Err(err) => err,
+        };
 
-                for item in parsed {
-                    items.push(item);
-                }
+        assert_eq!(
+            "Only the values `mysql`, `postgresql`, and `sqlite` are allowed",
+            err.to_string()
+        );
+    }
 
-                Some(items)
-            } else {
-                None
-            }
-        } else {
-            None
+    #[test]
+    fn unknown_option() {
+        let err = match syn::parse_str::<ModelSchemaInput>(
+            r#""./schema.dal", "sqlite", ["serde", "async_graphq"]"#,
+        ) {
+            Ok(_) => panic!("An unknown option was accepted"),
+            Err(err) => err,
         };
 
-        Ok(ModelInput {
-            lit_path,
-            path,
-            extra,
-        })
+        assert_eq!("Unknown option `async_graphq`", err.to_string());
     }
 }
@@ -11,6 +11,17 @@ pub enum Error {
     UnexpectedEOS,
     #[error("Unexpected pair in stream: {0:?}")]
     UnexpectedPair(ErrorSpan),
+    #[error("Schema failed validation")]
+    Invalid,
+    #[error("Schema has too many {0}")]
+    LimitExceeded(&'static str),
+    #[error("`{0}` is declared more than once")]
+    Duplicate(String),
+    #[error(
+        "{} reference each other in a cycle, so none of them can be created first",
+        .0.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>().join(", ")
+    )]
+    Cycle(Vec<String>),
 
     #[error("Parse error")]
     Parse(#[from] PestError<Rule>),
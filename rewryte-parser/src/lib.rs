@@ -1,8 +1,15 @@
 pub mod error;
 pub mod models;
 pub mod parser;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod validate;
 
 pub use crate::{
     error::Error,
-    parser::{parse, Context},
+    parser::{
+        parse, parse_and_report, parse_and_report_with, parse_enum_decl, parse_items,
+        parse_table_decl, Context, Items, Limits, ParseOptions, ReportOptions, Snapshot,
+    },
+    validate::str_range,
 };
@@ -1,42 +1,506 @@
 use {
     crate::Error,
-    std::{convert::TryFrom, fmt},
+    std::{borrow::Cow, collections::BTreeSet, convert::TryFrom, fmt},
 };
 
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub struct Schema<'a> {
+    /// The database the schema is written for, from `@database name`, used when no format is given.
+    pub database: Option<&'a str>,
+    /// Every declaration is created `IF NOT EXISTS`, from `@if_not_exists`, the parser sets each declaration's flag.
+    pub if_not_exists: bool,
     pub items: Vec<Item<'a>>,
 }
 
+impl<'a> Schema<'a> {
+    /// Appends the items of another schema, such as one parsed from a different file.
+    ///
+    /// Tables and enums share a namespace, as they both become types in the generated code. A table or enum whose
+    /// name is already declared is left out and returned as an [`Error::Duplicate`], every other item is appended.
+    pub fn merge(&mut self, other: Schema<'a>) -> Vec<Error> {
+        let mut names = self
+            .items
+            .iter()
+            .filter_map(Item::name)
+            .collect::<BTreeSet<_>>();
+
+        let mut errors = Vec::new();
+
+        if self.database.is_none() {
+            self.database = other.database;
+        }
+
+        // The declarations keep their own flags, the annotation only holds if it held for both
+        self.if_not_exists &= other.if_not_exists;
+
+        for item in other.items {
+            match item.name() {
+                Some(name) if !names.insert(name) => {
+                    errors.push(Error::Duplicate(name.to_string()))
+                }
+                _ => self.items.push(item),
+            }
+        }
+
+        errors
+    }
+
+    /// Reorders the items so every table comes after the tables it references and the enums it uses, otherwise keeping
+    /// the order they are declared in. A comment stays with the declaration after it.
+    ///
+    /// Tables referencing each other in a cycle can not be ordered, the items are left as they are and the tables in
+    /// the cycle are returned as an [`Error::Cycle`]. One of the foreign keys then has to be left out of the schema,
+    /// and added as a deferrable constraint once the tables exist.
+    pub fn topo_sort(&mut self) -> Result<(), Error> {
+        let mut units = Vec::new();
+        let mut comments = Vec::new();
+
+        for item in self.items.drain(..) {
+            if let Item::Comment(_) = item {
+                comments.push(item);
+            } else {
+                comments.push(item);
+                units.push(std::mem::take(&mut comments));
+            }
+        }
+
+        let names = units
+            .iter()
+            .map(|unit| unit.last().and_then(Item::name))
+            .collect::<Vec<_>>();
+
+        let deps = units
+            .iter()
+            .enumerate()
+            .map(|(i, unit)| match unit.last() {
+                Some(Item::Table(decl)) => {
+                    let used = decl
+                        .columns
+                        .iter()
+                        .filter_map(|column| column.typ.raw_name())
+                        .chain(decl.foreign_keys.iter().map(|key| key.table))
+                        .collect::<BTreeSet<_>>();
+
+                    // A table referencing itself does not need to come after anything
+                    (0..names.len())
+                        .filter(|j| {
+                            *j != i && matches!(names[*j], Some(name) if used.contains(name))
+                        })
+                        .collect()
+                }
+                _ => Vec::new(),
+            })
+            .collect::<Vec<Vec<usize>>>();
+
+        let mut done = vec![false; units.len()];
+        let mut order = Vec::with_capacity(units.len());
+
+        // Always taking the first unit that is ready keeps the declared order where it can be kept
+        while let Some(i) =
+            (0..units.len()).find(|i| !done[*i] && deps[*i].iter().all(|dep| done[*dep]))
+        {
+            done[i] = true;
+            order.push(i);
+        }
+
+        if order.len() != units.len() {
+            // Every unit left waits on another one left, so following them has to come back around
+            let mut path = Vec::new();
+            let mut current = (0..units.len()).find(|i| !done[*i]).unwrap_or_default();
+
+            let cycle = loop {
+                if let Some(start) = path.iter().position(|i| *i == current) {
+                    break &path[start..];
+                }
+
+                path.push(current);
+
+                current = match deps[current].iter().find(|dep| !done[**dep]) {
+                    Some(dep) => *dep,
+                    None => break &path[..],
+                };
+            };
+
+            let cycle = cycle
+                .iter()
+                .filter_map(|i| names[*i])
+                .map(String::from)
+                .collect();
+
+            self.items = units.into_iter().flatten().chain(comments).collect();
+
+            return Err(Error::Cycle(cycle));
+        }
+
+        let mut units = units.into_iter().map(Some).collect::<Vec<_>>();
+
+        self.items = order
+            .into_iter()
+            .filter_map(|i| units[i].take())
+            .flatten()
+            .chain(comments)
+            .collect();
+
+        Ok(())
+    }
+
+    /// Every type name used by a column that is not a built-in type, including those inside arrays.
+    pub fn raw_type_names(&self) -> BTreeSet<&'a str> {
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Table(decl) => Some(decl),
+                Item::Comment(_) | Item::Enum(_) => None,
+            })
+            .flat_map(|decl| decl.columns.iter())
+            .filter_map(|column| column.typ.raw_name())
+            .collect()
+    }
+
+    /// The raw type names used by columns that match an enum declared in the schema.
+    pub fn referenced_enums(&self) -> BTreeSet<&'a str> {
+        let enums = self
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Enum(decl) => Some(decl.name),
+                Item::Comment(_) | Item::Table(_) => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        self.raw_type_names()
+            .into_iter()
+            .filter(|name| enums.contains(name))
+            .collect()
+    }
+}
+
+/// Writes the schema back out as DAL, parsing the output gives the same schema.
+///
+/// A comment item directly before a table is read back as the table's documentation.
+impl<'a> fmt::Display for Schema<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(database) = self.database {
+            writeln!(f, "@database {}", database)?;
+        }
+
+        if self.if_not_exists {
+            writeln!(f, "@if_not_exists")?;
+        }
+
+        if self.database.is_some() || self.if_not_exists {
+            writeln!(f)?;
+        }
+
+        for (i, item) in self.items.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(f, "{}", item)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub enum Item<'a> {
+    /// A comment that is not documenting a table, kept so the schema can be written back out with it.
+    Comment(&'a str),
     Enum(Enum<'a>),
     Table(Table<'a>),
 }
 
+impl<'a> Item<'a> {
+    /// The name of the declared table or enum, comments have no name.
+    pub fn name(&self) -> Option<&'a str> {
+        match self {
+            Item::Comment(_) => None,
+            Item::Enum(decl) => Some(decl.name),
+            Item::Table(decl) => Some(decl.name),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Item<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Item::Comment(comment) => write!(f, "/* {} */", comment),
+            Item::Enum(decl) => write!(f, "{}", decl),
+            Item::Table(decl) => write!(f, "{}", decl),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub struct Enum<'a> {
     pub name: &'a str,
     pub not_exists: bool,
-    pub variants: Vec<&'a str>,
+    pub variants: Vec<Variant<'a>>,
+}
+
+impl<'a> fmt::Display for Enum<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "enum {}{} {{",
+            self.name,
+            if self.not_exists { "?" } else { "" }
+        )?;
+
+        for variant in &self.variants {
+            if let Some(doc) = variant.doc {
+                writeln!(f, "    /* {} */", doc)?;
+            }
+
+            writeln!(f, "    {}", variant.name)?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Variant<'a> {
+    pub name: &'a str,
+    pub doc: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub struct Table<'a> {
     pub name: &'a str,
+    pub doc: Option<&'a str>,
     pub not_exists: bool,
     pub columns: Vec<Column<'a>>,
     pub primary_keys: Vec<&'a str>,
     pub foreign_keys: Vec<ForeignKey<'a>>,
     pub unique_keys: Vec<&'a str>,
+    /// The name of the unique constraint, from `[unique as name]`, columns can repeat the name but giving it a different one is an error.
+    pub unique_name: Option<&'a str>,
+    /// Treat nulls as equal in the unique constraint, from `[unique nulls not distinct]`, only used by PostgreSQL 15+.
+    pub unique_nulls_not_distinct: bool,
+    /// Create the table `WITHOUT ROWID`, from `[without rowid]` after the table, only used by SQLite.
+    pub without_rowid: bool,
+    pub indexes: Vec<Index<'a>>,
+}
+
+impl<'a> Table<'a> {
+    /// The position of a column in the table, which is also its position in a `SELECT` of every column.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name == name)
+    }
+
+    /// The name of the unique constraint, `{table}_{columns}_key` if one was not given.
+    ///
+    /// The generated name is the one PostgreSQL would have picked, so naming it does not change existing databases.
+    pub fn unique_constraint_name(&self) -> Cow<'a, str> {
+        match self.unique_name {
+            Some(name) => Cow::Borrowed(name),
+            None => Cow::Owned(format!("{}_{}_key", self.name, self.unique_keys.join("_"))),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Table<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(doc) = self.doc {
+            writeln!(f, "/* {} */", doc)?;
+        }
+
+        writeln!(
+            f,
+            "table {}{} {{",
+            self.name,
+            if self.not_exists { "?" } else { "" }
+        )?;
+
+        for column in &self.columns {
+            if let Some(doc) = column.doc {
+                writeln!(f, "    /* {} */", doc)?;
+            }
+
+            write!(
+                f,
+                "    {} {}{}",
+                column.name,
+                column.typ,
+                if column.null { "!" } else { "" }
+            )?;
+
+            let mut modifiers = Vec::new();
+
+            if self.primary_keys.contains(&column.name) {
+                modifiers.push("primary key".to_string());
+            }
+
+            if self.unique_keys.contains(&column.name) {
+                let unique = if self.unique_nulls_not_distinct {
+                    "unique nulls not distinct"
+                } else {
+                    "unique"
+                };
+
+                // The parser rejects conflicting names, so the name only has to be written on one column, the last
+                match self.unique_name {
+                    Some(name) if self.unique_keys.last() == Some(&column.name) => {
+                        modifiers.push(format!("{} as {}", unique, name))
+                    }
+                    _ => modifiers.push(unique.to_string()),
+                }
+            }
+
+            for foreign_key in self
+                .foreign_keys
+                .iter()
+                .filter(|fk| fk.local == [column.name])
+            {
+                modifiers.push(format!(
+                    "ref: {}.{}{}",
+                    foreign_key.table,
+                    foreign_key.foreign.join(", "),
+                    ForeignKeyOptions(foreign_key)
+                ));
+            }
+
+            if column.default != ColumnDefault::None {
+                modifiers.push(format!("default: {}", column.default));
+            }
+
+            if let Some(name) = column.collate {
+                modifiers.push(format!("collate: {}", name));
+            }
+
+            if let Some(name) = column.rust_name {
+                modifiers.push(format!("as: {}", name));
+            }
+
+            if column.searchable {
+                modifiers.push("searchable".to_string());
+            }
+
+            if column.version {
+                modifiers.push("version".to_string());
+            }
+
+            if !column.skip.is_empty() {
+                modifiers.push(format!("skip: {}", column.skip.join(", ")));
+            }
+
+            if !modifiers.is_empty() {
+                write!(f, " [{}]", modifiers.join(", "))?;
+            }
+
+            writeln!(f)?;
+        }
+
+        // Only multi-column references have to be declared on the table
+        for foreign_key in self.foreign_keys.iter().filter(|fk| fk.local.len() != 1) {
+            writeln!(
+                f,
+                "    foreign key ({}) references {} ({}){}",
+                foreign_key.local.join(", "),
+                foreign_key.table,
+                foreign_key.foreign.join(", "),
+                ForeignKeyOptions(foreign_key)
+            )?;
+        }
+
+        for index in &self.indexes {
+            write!(
+                f,
+                "    index {}{} on ({})",
+                index.name,
+                if index.not_exists { "?" } else { "" },
+                index.columns.join(", ")
+            )?;
+
+            if let Some(predicate) = index.predicate {
+                write!(f, " where {}", predicate)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        write!(f, "}}")?;
+
+        if self.without_rowid {
+            write!(f, " [without rowid]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the constraint name and reference options that follow the referenced columns.
+struct ForeignKeyOptions<'f, 'a>(&'f ForeignKey<'a>);
+
+impl<'f, 'a> fmt::Display for ForeignKeyOptions<'f, 'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let foreign_key = self.0;
+
+        if let Some(name) = foreign_key.name {
+            write!(f, " as {}", name)?;
+        }
+
+        let options = [
+            ("delete", &foreign_key.delete),
+            ("update", &foreign_key.update),
+        ]
+        .iter()
+        .filter(|(_, action)| **action != Action::NoAction)
+        .map(|(on, action)| format!("{}: {}", on, action.to_string().to_lowercase()))
+        .chain(if foreign_key.deferrable {
+            Some("deferrable".to_string())
+        } else {
+            None
+        })
+        .collect::<Vec<_>>();
+
+        if !options.is_empty() {
+            write!(f, " ({})", options.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Index<'a> {
+    pub name: &'a str,
+    /// Create the index `IF NOT EXISTS`, from `index Name? on (...)`, the indexes of a table that is are always guarded.
+    pub not_exists: bool,
+    pub columns: Vec<&'a str>,
+    /// The `WHERE` predicate of a partial index, passed through to the database as written.
+    pub predicate: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub struct Column<'a> {
     pub name: &'a str,
+    pub doc: Option<&'a str>,
     pub typ: Types<'a>,
     pub null: bool,
     pub default: ColumnDefault<'a>,
+    /// The generators that leave this column out, from `[skip: rust, graphql]`.
+    pub skip: Vec<&'a str>,
+    /// The name of the generated Rust field, from `[as: name]`, the database keeps the column's name.
+    pub rust_name: Option<&'a str>,
+    /// The collation of the column, from `[collate: nocase]`, passed through to the database as written.
+    ///
+    /// Quotes are kept, so `[collate: "en_US"]` is written as `COLLATE "en_US"`.
+    pub collate: Option<&'a str>,
+    /// Included in the generated search helper, from `[searchable]`.
+    pub searchable: bool,
+    /// Holds the row's version for optimistic concurrency, from `[version]`, at most one column of a table has it.
+    pub version: bool,
+}
+
+impl<'a> Column<'a> {
+    /// Checks if the column is skipped by a generator.
+    pub fn skips(&self, target: &str) -> bool {
+        self.skip.contains(&target)
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
@@ -45,7 +509,8 @@ pub enum Types<'a> {
 
     // Text
     Char,
-    Varchar,
+    /// Text with an optional maximum length in characters, `varchar(10)`.
+    Varchar(Option<u32>),
     Text,
 
     // Numbers
@@ -55,15 +520,31 @@ pub enum Types<'a> {
     BigInt,
     Int,
     Serial,
+    SmallSerial,
+    BigSerial,
 
     // Floats
     Float,
     Real,
+    Double,
     Numeric,
     Decimal,
+    /// An exact amount of currency, with four digits after the decimal point.
+    Money,
 
     // Date/Time
     DateTime,
+    /// A calendar date without a time of day.
+    Date,
+    /// A time of day without a date.
+    Time,
+    /// A length of time, `interval` or `duration`.
+    Interval,
+
+    Uuid,
+
+    /// A Postgres array of the inner type, `text[]`.
+    Array(Box<Types<'a>>),
 
     Raw(&'a str),
 }
@@ -72,30 +553,109 @@ impl<'a> Types<'a> {
     pub(crate) fn from_str(s: &str) -> Types<'_> {
         match s {
             "bigInt" => Types::BigInt,
+            "bigSerial" => Types::BigSerial,
             "bool" | "boolean" => Types::Boolean,
             "char" => Types::Char,
+            "date" => Types::Date,
             "dateTime" => Types::DateTime,
             "decimal" => Types::Decimal,
+            "double" => Types::Double,
             "float" => Types::Float,
             "int" => Types::Int,
+            "interval" | "duration" => Types::Interval,
             "mediumInt" => Types::MediumInt,
+            "money" => Types::Money,
             "number" => Types::Number,
             "numeric" => Types::Numeric,
             "real" => Types::Real,
             "serial" => Types::Serial,
             "smallInt" => Types::SmallInt,
+            "smallSerial" => Types::SmallSerial,
             "text" => Types::Text,
-            "varchar" => Types::Varchar,
-            t => Types::Raw(t),
+            "time" => Types::Time,
+            "uuid" => Types::Uuid,
+            "varchar" => Types::Varchar(None),
+            t => match t
+                .strip_prefix("varchar(")
+                .and_then(|t| t.strip_suffix(')'))
+                .and_then(|length| length.parse().ok())
+            {
+                Some(length) => Types::Varchar(Some(length)),
+                None => Types::Raw(t),
+            },
         }
     }
+
+    /// Checks if the type is one of the auto-incrementing serial types.
+    pub fn is_serial(&self) -> bool {
+        matches!(self, Types::Serial | Types::SmallSerial | Types::BigSerial)
+    }
+
+    /// The name of a non built-in type, looking through arrays.
+    pub fn raw_name(&self) -> Option<&'a str> {
+        match self {
+            Types::Raw(raw) => Some(raw),
+            Types::Array(inner) => inner.raw_name(),
+            _ => None,
+        }
+    }
+
+    /// The canonical DAL spelling of the type, the inverse of parsing it.
+    pub fn as_dal_str(&self) -> Cow<'a, str> {
+        Cow::Borrowed(match self {
+            Types::Boolean => "bool",
+            Types::Char => "char",
+            Types::Varchar(None) => "varchar",
+            Types::Varchar(Some(length)) => return Cow::Owned(format!("varchar({})", length)),
+            Types::Text => "text",
+            Types::Number => "number",
+            Types::SmallInt => "smallInt",
+            Types::MediumInt => "mediumInt",
+            Types::BigInt => "bigInt",
+            Types::Int => "int",
+            Types::Serial => "serial",
+            Types::SmallSerial => "smallSerial",
+            Types::BigSerial => "bigSerial",
+            Types::Float => "float",
+            Types::Real => "real",
+            Types::Double => "double",
+            Types::Numeric => "numeric",
+            Types::Decimal => "decimal",
+            Types::Money => "money",
+            Types::DateTime => "dateTime",
+            Types::Date => "date",
+            Types::Time => "time",
+            Types::Interval => "interval",
+            Types::Uuid => "uuid",
+            Types::Array(inner) => return Cow::Owned(format!("{}[]", inner)),
+            Types::Raw(raw) => raw,
+        })
+    }
+}
+
+impl<'a> fmt::Display for Types<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_dal_str())
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub enum ColumnDefault<'a> {
+    /// No default was given, nothing is written so a nullable column still defaults to `NULL`.
     None,
     Now,
+    /// The current date, from `today()`, for `date` columns.
+    CurrentDate,
+    /// The current time of day, from `currentTime()`, for `time` columns.
+    CurrentTime,
+    /// An explicit `[default: null]`, written out even though it is the same as no default.
+    ///
+    /// Only nullable columns can have it, it is rejected on `NOT NULL` columns.
     Null,
+    /// A random UUID, from `uuid()`.
+    Uuid,
+    /// A `true` or `false` literal, written in each database's own spelling.
+    Bool(bool),
     Raw(&'a str),
 }
 
@@ -105,13 +665,46 @@ impl<'a> Default for ColumnDefault<'a> {
     }
 }
 
+impl<'a> fmt::Display for ColumnDefault<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnDefault::None => Ok(()),
+            ColumnDefault::Now => write!(f, "now()"),
+            ColumnDefault::CurrentDate => write!(f, "today()"),
+            ColumnDefault::CurrentTime => write!(f, "currentTime()"),
+            ColumnDefault::Null => write!(f, "null"),
+            ColumnDefault::Uuid => write!(f, "uuid()"),
+            ColumnDefault::Bool(value) => write!(f, "{}", value),
+            ColumnDefault::Raw(raw) => write!(f, "{}", raw),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub struct ForeignKey<'a> {
-    pub local: &'a str,
+    /// The referencing columns, more than one for a table level `foreign key (a, b) references Table (x, y)`.
+    pub local: Vec<&'a str>,
     pub table: &'a str,
-    pub foreign: &'a str,
+    /// The referenced columns, in the same order as the local columns.
+    pub foreign: Vec<&'a str>,
     pub delete: Action,
     pub update: Action,
+    /// Check the reference at the end of the transaction instead of after each statement, from `(deferrable)`.
+    pub deferrable: bool,
+    /// The name of the constraint, from `[ref: Table.Column as name]`.
+    pub name: Option<&'a str>,
+}
+
+impl<'a> ForeignKey<'a> {
+    /// The name of the constraint, `{table}_{columns}_fkey` if one was not given.
+    ///
+    /// The generated name is the one PostgreSQL would have picked, so naming it does not change existing databases.
+    pub fn constraint_name(&self, table: &str) -> Cow<'a, str> {
+        match self.name {
+            Some(name) => Cow::Borrowed(name),
+            None => Cow::Owned(format!("{}_{}_fkey", table, self.local.join("_"))),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
@@ -169,17 +762,315 @@ pub(crate) struct ColumnPartial<'a> {
 }
 
 pub(crate) enum Modifier<'p> {
+    Collate {
+        name: &'p str,
+    },
     Default {
         value: &'p str,
     },
     DefaultDateTime,
+    DefaultCurrentDate,
+    DefaultCurrentTime,
+    DefaultBool {
+        value: bool,
+    },
     DefaultNull,
+    DefaultUuid,
     PrimaryKey,
+    RustName {
+        name: &'p str,
+    },
+    Searchable,
     Reference {
         table: &'p str,
         column: &'p str,
         delete: Action,
         update: Action,
+        deferrable: bool,
+        name: Option<&'p str>,
+    },
+    Skip {
+        targets: Vec<&'p str>,
+    },
+    Unique {
+        name: Option<&'p str>,
+        nulls_not_distinct: bool,
     },
-    Unique,
+    Version,
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{parse, Context},
+    };
+
+    #[test]
+    fn types_display() {
+        let types = [
+            (Types::Boolean, "bool"),
+            (Types::Char, "char"),
+            (Types::Varchar(None), "varchar"),
+            (Types::Varchar(Some(10)), "varchar(10)"),
+            (Types::Text, "text"),
+            (Types::Number, "number"),
+            (Types::SmallInt, "smallInt"),
+            (Types::MediumInt, "mediumInt"),
+            (Types::BigInt, "bigInt"),
+            (Types::Int, "int"),
+            (Types::Serial, "serial"),
+            (Types::SmallSerial, "smallSerial"),
+            (Types::BigSerial, "bigSerial"),
+            (Types::Float, "float"),
+            (Types::Real, "real"),
+            (Types::Double, "double"),
+            (Types::Numeric, "numeric"),
+            (Types::Decimal, "decimal"),
+            (Types::Money, "money"),
+            (Types::DateTime, "dateTime"),
+            (Types::Date, "date"),
+            (Types::Time, "time"),
+            (Types::Interval, "interval"),
+            (Types::Uuid, "uuid"),
+            (Types::Raw("foo"), "foo"),
+        ];
+
+        for (typ, expected) in types.iter() {
+            assert_eq!(*expected, typ.to_string());
+            assert_eq!(typ, &Types::from_str(expected));
+        }
+    }
+
+    #[test]
+    fn array_display() {
+        assert_eq!("text[]", Types::Array(Box::new(Types::Text)).to_string());
+        assert_eq!(
+            "int[][]",
+            Types::Array(Box::new(Types::Array(Box::new(Types::Int)))).to_string()
+        );
+    }
+
+    fn column(name: &'static str, typ: Types<'static>) -> Column<'static> {
+        Column {
+            name,
+            doc: None,
+            typ,
+            null: false,
+            default: ColumnDefault::None,
+            skip: vec![],
+            rust_name: None,
+            collate: None,
+            searchable: false,
+            version: false,
+        }
+    }
+
+    fn schema() -> Schema<'static> {
+        Schema {
+            database: None,
+            if_not_exists: false,
+            items: vec![
+                Item::Enum(Enum {
+                    name: "State",
+                    not_exists: false,
+                    variants: vec![],
+                }),
+                Item::Table(Table {
+                    name: "Story",
+                    doc: None,
+                    not_exists: false,
+                    columns: vec![
+                        column("Id", Types::Text),
+                        column("State", Types::Raw("State")),
+                        column("Rating", Types::Raw("Rating")),
+                        column("Tags", Types::Array(Box::new(Types::Raw("Tag")))),
+                        column("Previous", Types::Raw("State")),
+                        column("Created", Types::DateTime),
+                    ],
+                    primary_keys: vec!["Id"],
+                    foreign_keys: vec![],
+                    unique_keys: vec![],
+                    unique_name: None,
+                    unique_nulls_not_distinct: false,
+                    without_rowid: false,
+                    indexes: vec![],
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let mut ctx = Context::new(0);
+
+        let mut schema =
+            parse(&mut ctx, "enum State { Working Finished }").expect("Unable to parse schema");
+
+        let errors = schema.merge(
+            parse(&mut ctx, "table Story { Id text [primary key] Name text }")
+                .expect("Unable to parse schema"),
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            vec![Some("State"), Some("Story")],
+            schema.items.iter().map(Item::name).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn merge_duplicate() {
+        let mut ctx = Context::new(0);
+
+        let mut schema = parse(&mut ctx, "enum State { Working } table Story { Id text }")
+            .expect("Unable to parse schema");
+
+        let errors = schema.merge(
+            parse(
+                &mut ctx,
+                "table State { Id text } table Story { Id text } table Chapter { Id text }",
+            )
+            .expect("Unable to parse schema"),
+        );
+
+        assert_eq!(
+            vec![
+                "`State` is declared more than once",
+                "`Story` is declared more than once"
+            ],
+            errors.iter().map(|err| err.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Some("State"), Some("Story"), Some("Chapter")],
+            schema.items.iter().map(Item::name).collect::<Vec<_>>()
+        );
+    }
+
+    fn item_names(schema: &Schema) -> Vec<String> {
+        schema
+            .items
+            .iter()
+            .map(|item| match item {
+                Item::Comment(comment) => format!("comment: {}", comment),
+                item => item.name().unwrap_or_default().to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn topo_sort() {
+        let mut ctx = Context::new(0);
+
+        let mut schema = parse(
+            &mut ctx,
+            "table Chapter {
+                id int [primary key]
+                story int [ref: Story.id]
+                parent int! [ref: Chapter.id]
+            }
+
+            /* Every story has an author */
+            table Story {
+                id int [primary key]
+                author int [ref: Author.id]
+            }
+
+            /* The kind of an author */
+            enum Kind { Person Group }
+
+            table Author {
+                id int [primary key]
+                kind Kind
+            }
+
+            table Unrelated { id int [primary key] }",
+        )
+        .expect("Unable to parse schema");
+
+        schema.topo_sort().expect("Unable to sort schema");
+
+        assert_eq!(
+            vec![
+                "comment: The kind of an author",
+                "Kind",
+                "Author",
+                "Story",
+                "Chapter",
+                "Unrelated"
+            ],
+            item_names(&schema)
+        );
+    }
+
+    #[test]
+    fn topo_sort_cycle() {
+        let mut ctx = Context::new(0);
+
+        let mut schema = parse(
+            &mut ctx,
+            "table Author {
+                id int [primary key]
+                favorite int! [ref: Story.id]
+            }
+
+            table Story {
+                id int [primary key]
+                author int [ref: Author.id]
+            }
+
+            table Other { id int [primary key] }",
+        )
+        .expect("Unable to parse schema");
+
+        let err = schema.topo_sort().expect_err("A cycle was sorted");
+
+        assert_eq!(
+            "`Author`, `Story` reference each other in a cycle, so none of them can be created first",
+            err.to_string()
+        );
+        assert_eq!(vec!["Author", "Story", "Other"], item_names(&schema));
+    }
+
+    #[test]
+    fn raw_type_names() {
+        let names = schema().raw_type_names();
+
+        assert_eq!(
+            vec!["Rating", "State", "Tag"],
+            names.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn column_index() {
+        let schema = schema();
+
+        let decl = match &schema.items[1] {
+            Item::Table(decl) => decl,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(Some(0), decl.column_index("Id"));
+        assert_eq!(Some(3), decl.column_index("Tags"));
+        assert_eq!(None, decl.column_index("Missing"));
+    }
+
+    #[test]
+    fn referenced_enums() {
+        let names = schema().referenced_enums();
+
+        assert_eq!(vec!["State"], names.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn column_default_display() {
+        assert_eq!("", ColumnDefault::None.to_string());
+        assert_eq!("now()", ColumnDefault::Now.to_string());
+        assert_eq!("null", ColumnDefault::Null.to_string());
+        assert_eq!("uuid()", ColumnDefault::Uuid.to_string());
+        assert_eq!("true", ColumnDefault::Bool(true).to_string());
+        assert_eq!("false", ColumnDefault::Bool(false).to_string());
+        assert_eq!("0", ColumnDefault::Raw("0").to_string());
+    }
 }
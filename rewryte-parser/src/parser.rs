@@ -1,12 +1,20 @@
 use {
     crate::{
         models::{
-            Action, Column, ColumnDefault, ColumnPartial, Enum, ForeignKey, Item, Modifier, Schema,
-            Table, Types,
+            Action, Column, ColumnDefault, ColumnPartial, Enum, ForeignKey, Index, Item, Modifier,
+            Schema, Table, Types, Variant,
+        },
+        validate::{
+            str_range, validate_enum_references, validate_table, validate_unique_names,
+            warn_unused_enums,
         },
         Error,
     },
-    codespan_reporting::diagnostic::{Diagnostic, Label},
+    codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+        term::{self, termcolor::NoColor, Config, DisplayStyle},
+    },
     pest::{
         iterators::{Pair, Pairs},
         Parser as _, Span,
@@ -18,22 +26,138 @@ use {
 #[grammar = "dal.pest"]
 struct Parser;
 
+/// Upper bounds on the size of a schema, anything larger is rejected with a diagnostic instead of being parsed.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Limits {
+    /// The maximum length of the input in bytes.
+    pub max_input: usize,
+    /// The maximum number of enum and table declarations.
+    pub max_items: usize,
+    /// The maximum number of columns in a single table.
+    pub max_columns: usize,
+    /// The maximum number of variants in a single enum.
+    pub max_variants: usize,
+    /// The maximum depth of nested parentheses, groups in defaults and index predicates are parsed recursively.
+    pub max_nesting: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_input: 16 * 1024 * 1024,
+            max_items: 4096,
+            max_columns: 1024,
+            max_variants: 4096,
+            max_nesting: 128,
+        }
+    }
+}
+
+/// How strictly a schema is checked, the defaults accept every schema older versions did.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ParseOptions {
+    /// Rejects column types that are neither built-in nor a declared enum, instead of passing them through as raw
+    /// database types, which catches typos like `intt`.
+    pub strict_types: bool,
+}
+
+/// How much of the diagnostics [`parse_and_report_with`] renders.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReportOptions {
+    /// The most diagnostics to render, the rest are only counted. `None` renders every one.
+    pub max_diagnostics: Option<usize>,
+    /// Show the source lines under each diagnostic, without them each is a single `file:line:column` line.
+    pub source: bool,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            max_diagnostics: None,
+            source: true,
+        }
+    }
+}
+
+/// A point in a context's diagnostics, from [`Context::snapshot`].
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Snapshot {
+    diags: usize,
+    warnings: usize,
+}
+
+#[derive(Clone, Debug)]
 pub struct Context {
     pub(crate) diags: Vec<Diagnostic<usize>>,
-    file_id: usize,
+    /// Kept apart from the errors, so they never fail a parse.
+    pub(crate) warnings: Vec<Diagnostic<usize>>,
+    pub(crate) file_id: usize,
+    pub(crate) limits: Limits,
+    pub(crate) options: ParseOptions,
 }
 
 impl Context {
     pub fn new(file_id: usize) -> Self {
+        Self::with_limits(file_id, Limits::default())
+    }
+
+    pub fn with_limits(file_id: usize, limits: Limits) -> Self {
+        Self::with_options(file_id, limits, ParseOptions::default())
+    }
+
+    pub fn with_options(file_id: usize, limits: Limits, options: ParseOptions) -> Self {
         Self {
             diags: Vec::new(),
+            warnings: Vec::new(),
             file_id,
+            limits,
+            options,
         }
     }
 
+    /// The errors found so far, a parse fails if it added any.
     pub fn diagnostics(&self) -> &[Diagnostic<usize>] {
         &self.diags
     }
+
+    /// The warnings found so far, they are not errors unless the caller denies them.
+    pub fn warnings(&self) -> &[Diagnostic<usize>] {
+        &self.warnings
+    }
+
+    /// Adds a warning with a single label.
+    pub fn warning(
+        &mut self,
+        message: impl Into<String>,
+        range: Range<usize>,
+        label: impl Into<String>,
+    ) {
+        self.warnings.push(
+            Diagnostic::warning()
+                .with_message(message)
+                .with_labels(vec![Label::primary(self.file_id, range).with_message(label)]),
+        );
+    }
+
+    /// Marks the current diagnostics, a later [`rollback`](Context::rollback) drops every diagnostic added after it.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            diags: self.diags.len(),
+            warnings: self.warnings.len(),
+        }
+    }
+
+    /// Drops the diagnostics added since the [`snapshot`](Context::snapshot), so a failed parse can be tried again.
+    pub fn rollback(&mut self, to: Snapshot) {
+        self.diags.truncate(to.diags);
+        self.warnings.truncate(to.warnings);
+    }
+
+    /// Drops every diagnostic and warning, keeping the file ID and limits.
+    pub fn clear(&mut self) {
+        self.diags.clear();
+        self.warnings.clear();
+    }
 }
 
 #[inline]
@@ -48,311 +172,682 @@ fn span_range_single<'i>(pair: &Pair<'i, Rule>) -> Range<usize> {
     (span.start())..(span.start())
 }
 
-#[allow(dead_code)]
-pub fn parse<'i>(ctx: &mut Context, input: &'i str) -> Result<Schema<'i>, Error> {
-    let mut pairs: Pairs<'i, Rule> = Parser::parse(Rule::schema, input)?;
+#[inline]
+fn limit_exceeded(
+    ctx: &mut Context,
+    range: Range<usize>,
+    limit: &'static str,
+    max: usize,
+) -> Error {
+    ctx.diags.push(
+        Diagnostic::error()
+            .with_message("Limit exceeded")
+            .with_labels(vec![Label::primary(ctx.file_id, range)
+                .with_message(format!("the schema has more than {} {}", max, limit))]),
+    );
 
-    let mut items = Vec::new();
+    Error::LimitExceeded(limit)
+}
 
-    let pair = match pairs.next() {
-        Some(pair) if pair.as_rule() == Rule::schema => pair,
-        Some(pair) if pair.as_rule() == Rule::EOI => return Ok(Schema { items }),
-        Some(pair) => {
-            ctx.diags.push(
-                Diagnostic::error()
-                    .with_message("Unexpected token")
-                    .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
-                        .with_message(format!(
-                            "expected `schema`, found `{:?}`",
-                            pair.as_rule()
-                        ))]),
-            );
+/// Checks the size and nesting of the input before pest sees it, as deep nesting would overflow its stack.
+fn check_input(ctx: &mut Context, input: &str) -> Result<(), Error> {
+    if input.len() > ctx.limits.max_input {
+        let max = ctx.limits.max_input;
 
-            return Err(Error::UnexpectedPair(pair.as_span().into()));
-        }
-        None => {
-            return Err(Error::UnexpectedEOS);
-        }
-    };
+        return Err(limit_exceeded(ctx, 0..0, "bytes", max));
+    }
 
-    for root_group in pair.into_inner() {
-        match root_group.as_rule() {
-            Rule::decl_enum => {
-                let decl = parse_enum(ctx, root_group)?;
+    let bytes = input.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
 
-                items.push(Item::Enum(decl));
+    while i < bytes.len() {
+        match bytes[i] {
+            // Strings and comments can hold unbalanced parentheses, skip them
+            quote @ (b'\'' | b'"') => {
+                i += 1;
+
+                while i < bytes.len() && bytes[i] != quote {
+                    i += 1;
+                }
             }
-            Rule::decl_table => {
-                let decl = parse_table(ctx, root_group)?;
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+
+                while i < bytes.len() && !bytes[i..].starts_with(b"*/") {
+                    i += 1;
+                }
 
-                items.push(Item::Table(decl));
+                i += 1;
             }
-            Rule::comment => continue,
-            Rule::EOI => break,
-            _ => {
-                ctx.diags.push(
-                    Diagnostic::error()
-                        .with_message("Unexpected token")
-                        .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&root_group))
-                            .with_message(format!(
-                                "expected `enum declaration`, `table declaration`, or `comment`, found `{:?}`",
-                                root_group.as_rule()
-                            ))]),
-                );
+            b'(' => {
+                depth += 1;
+
+                if depth > ctx.limits.max_nesting {
+                    let max = ctx.limits.max_nesting;
 
-                return Err(Error::UnexpectedPair(root_group.as_span().into()));
+                    return Err(limit_exceeded(ctx, i..i + 1, "levels of nesting", max));
+                }
             }
+            b')' => depth = depth.saturating_sub(1),
+            _ => {}
         }
+
+        i += 1;
     }
 
-    Ok(Schema { items })
+    Ok(())
 }
 
-#[inline]
-fn parse_enum<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<Enum<'i>, Error> {
-    debug_assert!(
-        pair.as_rule() == Rule::decl_enum,
-        "The root pair must be a `decl_enum` to be able to parse a enum declaration"
-    );
-
-    let inner_span = pair.as_span();
-    let mut inner: Pairs<'i, Rule> = pair.into_inner();
+#[allow(dead_code)]
+pub fn parse<'i>(ctx: &mut Context, input: &'i str) -> Result<Schema<'i>, Error> {
+    let mut items = Vec::new();
+    let mut invalid = false;
 
-    let name = match inner.next() {
-        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
-        Some(pair) => {
-            ctx.diags.push(
-                Diagnostic::error()
-                    .with_message("Unexpected token")
-                    .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
-                        .with_message(format!(
-                            "expected `ident`, found `{:?}`",
-                            pair.as_rule()
-                        ))]),
-            );
+    let mut parsed = parse_items(ctx, input);
 
-            return Err(Error::UnexpectedPair(pair.as_span().into()));
+    for item in &mut parsed {
+        match item {
+            Ok(item) => items.push(item),
+            // Keep going so every invalid table is reported, not just the first
+            Err(Error::Invalid) => invalid = true,
+            Err(err) => return Err(err),
         }
-        None => {
-            ctx.diags.push(
-                Diagnostic::error()
-                    .with_message("Unexpected end of stream")
-                    .with_labels(vec![Label::primary(
-                        ctx.file_id,
-                        span_range_end(inner_span),
-                    )
-                    .with_message("here")]),
-            );
+    }
 
-            return Err(Error::UnexpectedEOS);
-        }
-    };
+    let database = parsed.database();
+    let if_not_exists = parsed.if_not_exists();
 
-    let not_exists = match inner.peek() {
-        Some(peeked) if peeked.as_rule() == Rule::exists => {
-            let _ = inner.next();
+    let before = ctx.diags.len();
 
-            true
-        }
-        _ => false,
-    };
+    validate_enum_references(ctx, input, &items);
 
-    let mut variants = Vec::with_capacity(inner.size_hint().0);
+    if invalid || ctx.diags.len() != before {
+        return Err(Error::Invalid);
+    }
 
-    for pair in inner {
-        match pair.as_rule() {
-            Rule::variant => variants.push(pair.as_str()),
-            _ => {
-                ctx.diags.push(
-                    Diagnostic::error()
-                        .with_message("Unexpected token")
-                        .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
-                            .with_message(format!(
-                                "expected `variant`, found `{:?}`",
-                                pair.as_rule()
-                            ))]),
-                );
+    warn_unused_enums(ctx, input, &items);
 
-                return Err(Error::UnexpectedPair(pair.as_span().into()));
+    order_enums(&mut items);
+
+    Ok(Schema {
+        database,
+        if_not_exists,
+        items,
+    })
+}
+
+/// Moves enums that are declared after a table using them to just before the table, so the type exists when the
+/// table is created.
+///
+/// A comment directly before a moved enum is moved with it.
+fn order_enums(items: &mut Vec<Item>) {
+    let mut i = 0;
+
+    while i < items.len() {
+        let later = match &items[i] {
+            Item::Table(decl) => decl
+                .columns
+                .iter()
+                .filter_map(|c| c.typ.raw_name())
+                .filter_map(|name| {
+                    items[(i + 1)..]
+                        .iter()
+                        .position(|item| matches!(item, Item::Enum(e) if e.name == name))
+                        .map(|pos| pos + i + 1)
+                })
+                .min(),
+            Item::Comment(_) | Item::Enum(_) => None,
+        };
+
+        match later {
+            Some(pos) => {
+                let start = match items[pos - 1] {
+                    Item::Comment(_) if pos - 1 > i => pos - 1,
+                    _ => pos,
+                };
+
+                let moved = items.drain(start..=pos).collect::<Vec<_>>();
+
+                // The table is looked at again, it may use another enum that is declared even later
+                items.splice(i..i, moved);
             }
+            None => i += 1,
         }
     }
+}
 
-    Ok(Enum {
-        name,
-        not_exists,
-        variants,
-    })
+/// Parses a schema, rendering the diagnostics without colour if it fails.
+///
+/// `name` is the file name shown in the diagnostics, the error is the parse error followed by every diagnostic.
+pub fn parse_and_report<'i>(name: &str, input: &'i str) -> Result<Schema<'i>, String> {
+    parse_and_report_with(name, input, ReportOptions::default())
 }
 
-#[inline]
-fn parse_table<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<Table<'i>, Error> {
-    debug_assert!(
-        pair.as_rule() == Rule::decl_table,
-        "The root pair must be a `decl_table` to be able to parse a table declaration"
-    );
+/// Parses a schema like [`parse_and_report`], rendering only as much of the diagnostics as the options allow.
+///
+/// Diagnostics past the limit are left out, with a line saying how many there were.
+pub fn parse_and_report_with<'i>(
+    name: &str,
+    input: &'i str,
+    options: ReportOptions,
+) -> Result<Schema<'i>, String> {
+    let mut files = SimpleFiles::new();
 
-    let inner_span = pair.as_span();
-    let mut inner: Pairs<'i, Rule> = pair.into_inner();
+    let file_id = files.add(name, input);
 
-    let name = match inner.next() {
-        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
-        Some(pair) => {
-            ctx.diags.push(
-                Diagnostic::error()
-                    .with_message("Unexpected token")
-                    .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
-                        .with_message(format!(
-                            "expected `ident`, found `{:?}`",
-                            pair.as_rule()
-                        ))]),
-            );
+    let mut ctx = Context::new(file_id);
 
-            return Err(Error::UnexpectedPair(pair.as_span().into()));
-        }
-        None => {
-            ctx.diags.push(
-                Diagnostic::error()
-                    .with_message("Unexpected end of stream")
-                    .with_labels(vec![Label::primary(
-                        ctx.file_id,
-                        span_range_end(inner_span),
-                    )
-                    .with_message("here")]),
-            );
+    let err = match parse(&mut ctx, input) {
+        Ok(schema) => return Ok(schema),
+        Err(err) => err,
+    };
 
-            return Err(Error::UnexpectedEOS);
-        }
+    let config = Config {
+        display_style: if options.source {
+            DisplayStyle::Rich
+        } else {
+            DisplayStyle::Short
+        },
+        ..Config::default()
     };
 
-    let not_exists = match inner.peek() {
-        Some(peeked) if peeked.as_rule() == Rule::exists => {
-            let _ = inner.next();
+    let diags = ctx.diagnostics();
 
-            true
-        }
-        _ => false,
-    };
+    let shown = options
+        .max_diagnostics
+        .map_or(diags.len(), |max| max.min(diags.len()));
 
-    let mut columns = Vec::new();
-    let mut primary_keys = Vec::new();
-    let mut foreign_keys = Vec::new();
-    let mut unique_keys = Vec::new();
+    let mut writer = NoColor::new(Vec::new());
 
-    for pair in inner {
-        match pair.as_rule() {
-            Rule::column => {
-                let (col, modifiers) = parse_column(ctx, pair)?;
-                let mut default = ColumnDefault::default();
+    for diag in &diags[..shown] {
+        if options.source {
+            term::emit(&mut writer, &config, &files, diag).map_err(|err| err.to_string())?;
 
-                for modifier in modifiers {
-                    match modifier {
-                        Modifier::Default { value } => default = ColumnDefault::Raw(value),
-                        Modifier::DefaultDateTime => default = ColumnDefault::Now,
-                        Modifier::DefaultNull => default = ColumnDefault::Null,
-                        Modifier::PrimaryKey => primary_keys.push(col.name),
-                        Modifier::Reference {
-                            table,
-                            column,
-                            delete,
-                            update,
-                        } => foreign_keys.push(ForeignKey {
-                            local: col.name,
-                            table,
-                            foreign: column,
-                            delete: delete.clone(),
-                            update: update.clone(),
-                        }),
-                        Modifier::Unique => unique_keys.push(col.name),
-                    }
-                }
+            continue;
+        }
 
-                columns.push(Column {
-                    name: col.name,
-                    typ: col.typ,
-                    null: col.null,
-                    default,
-                });
-            }
-            Rule::comment => continue,
-            _ => {
-                ctx.diags.push(
-                    Diagnostic::error()
-                        .with_message("Unexpected token")
-                        .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
-                            .with_message(format!(
-                                "expected `column` or `comment`, found `{:?}`",
-                                pair.as_rule()
-                            ))]),
-                );
+        // The short style only prints the message, so the labels' messages are moved into it
+        let mut diag = diag.clone();
 
-                return Err(Error::UnexpectedPair(pair.as_span().into()));
+        for label in &diag.labels {
+            if !label.message.is_empty() {
+                diag.message = format!("{}: {}", diag.message, label.message);
             }
         }
+
+        term::emit(&mut writer, &config, &files, &diag).map_err(|err| err.to_string())?;
     }
 
-    Ok(Table {
-        name,
-        not_exists,
-        columns,
-        primary_keys,
-        foreign_keys,
-        unique_keys,
-    })
-}
+    let mut emit_string = String::from_utf8(writer.into_inner()).map_err(|err| err.to_string())?;
 
-#[inline]
-fn parse_column<'i>(
-    ctx: &mut Context,
-    pair: Pair<'i, Rule>,
-) -> Result<(ColumnPartial<'i>, Vec<Modifier<'i>>), Error> {
-    debug_assert!(
-        pair.as_rule() == Rule::column,
-        "The root pair must be a `column` to be able to parse a table column definition"
-    );
+    if shown < diags.len() {
+        emit_string.push_str(&format!(
+            "\n... and {} more diagnostic(s)\n",
+            diags.len() - shown
+        ));
+    }
 
-    let inner_span = pair.as_span();
-    let mut inner: Pairs<'i, Rule> = pair.into_inner();
+    Err(format!("{}\n\n{}", err, emit_string))
+}
 
-    let name = match inner.next() {
-        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
+/// Parses a schema one item at a time, each declaration is only turned into an [`Item`] once it is reached.
+///
+/// Tables are validated as they are yielded, an invalid table yields [`Error::Invalid`] and the
+/// items after it are still parsed. Any other error ends the iterator.
+///
+/// Enum references need the whole schema, so unlike [`parse`] they are not checked, and enums are left where they are
+/// declared.
+pub fn parse_items<'c, 'i>(ctx: &'c mut Context, input: &'i str) -> Items<'c, 'i> {
+    let (pairs, error) = match schema_pairs(ctx, input) {
+        Ok(pairs) => (pairs, None),
+        Err(err) => (None, Some(err)),
+    };
+
+    Items {
+        ctx,
+        input,
+        pairs,
+        error,
+        doc: None,
+        queued: None,
+        decls: 0,
+        mixins: Vec::new(),
+        database: None,
+        if_not_exists: false,
+        timestamps: None,
+    }
+}
+
+/// The databases `@database` can name, the same names the formats use.
+const DATABASES: &[&str] = &["mysql", "postgres", "postgresql", "sqlite"];
+
+/// The iterator returned by [`parse_items`].
+pub struct Items<'c, 'i> {
+    ctx: &'c mut Context,
+    input: &'i str,
+    pairs: Option<Pairs<'i, Rule>>,
+    error: Option<Error>,
+    doc: Option<&'i str>,
+    queued: Option<Item<'i>>,
+    decls: usize,
+    mixins: Vec<Table<'i>>,
+    database: Option<&'i str>,
+    if_not_exists: bool,
+    timestamps: Option<Timestamps<'i>>,
+}
+
+/// The columns `@timestamps` adds to every table, `Created` and `Updated` unless they are named.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Timestamps<'i> {
+    created: &'i str,
+    updated: &'i str,
+}
+
+impl<'c, 'i> Items<'c, 'i> {
+    /// The database named by the schema's `@database` annotation, if it has been reached.
+    pub fn database(&self) -> Option<&'i str> {
+        self.database
+    }
+
+    /// If the schema's `@if_not_exists` annotation has been reached, every declaration after it is created
+    /// `IF NOT EXISTS`.
+    pub fn if_not_exists(&self) -> bool {
+        self.if_not_exists
+    }
+
+    /// Reports an annotation that is repeated, misplaced, or unknown.
+    fn invalid_annotation(
+        &mut self,
+        range: Range<usize>,
+        message: String,
+    ) -> Option<Result<Item<'i>, Error>> {
+        self.ctx.diags.push(
+            Diagnostic::error()
+                .with_message("Invalid annotation")
+                .with_labels(vec![
+                    Label::primary(self.ctx.file_id, range).with_message(message)
+                ]),
+        );
+
+        Some(Err(Error::Invalid))
+    }
+
+    #[inline]
+    fn fail(&mut self, err: Error) -> Option<Result<Item<'i>, Error>> {
+        self.pairs = None;
+
+        Some(Err(err))
+    }
+}
+
+impl<'c, 'i> Iterator for Items<'c, 'i> {
+    type Item = Result<Item<'i>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.error.take() {
+            return Some(Err(err));
+        }
+
+        if let Some(item) = self.queued.take() {
+            return Some(Ok(item));
+        }
+
+        loop {
+            let root_group = match self.pairs.as_mut()?.next() {
+                Some(pair) => pair,
+                None => {
+                    self.pairs = None;
+
+                    return self.doc.take().map(|doc| Ok(Item::Comment(doc)));
+                }
+            };
+
+            if self.decls == self.ctx.limits.max_items
+                && root_group.as_rule() != Rule::comment
+                && root_group.as_rule() != Rule::EOI
+            {
+                let max = self.ctx.limits.max_items;
+                let err = limit_exceeded(self.ctx, span_range_single(&root_group), "items", max);
+
+                return self.fail(err);
+            }
+
+            match root_group.as_rule() {
+                Rule::annotation_database => {
+                    let name = match root_group.into_inner().next() {
+                        Some(pair) => pair.as_str(),
+                        None => return self.fail(Error::UnexpectedEOS),
+                    };
+
+                    let range = str_range(self.input, name);
+
+                    let message = if self.database.is_some() {
+                        Some("`@database` is given more than once".to_string())
+                    } else if self.decls != 0 {
+                        Some("`@database` has to come before every declaration".to_string())
+                    } else if !DATABASES.contains(&name) {
+                        Some(format!(
+                            "expected `mysql`, `postgres`, `postgresql`, or `sqlite`, found `{}`",
+                            name
+                        ))
+                    } else {
+                        None
+                    };
+
+                    match message {
+                        Some(message) => return self.invalid_annotation(range, message),
+                        None => self.database = Some(name),
+                    }
+
+                    // A comment before the annotation is not documenting the table after it
+                    if let Some(doc) = self.doc.take() {
+                        return Some(Ok(Item::Comment(doc)));
+                    }
+                }
+                Rule::annotation_if_not_exists => {
+                    let range = span_range_single(&root_group);
+
+                    if self.if_not_exists {
+                        let message = "`@if_not_exists` is given more than once".to_string();
+
+                        return self.invalid_annotation(range, message);
+                    }
+
+                    if self.decls != 0 {
+                        let message =
+                            "`@if_not_exists` has to come before every declaration".to_string();
+
+                        return self.invalid_annotation(range, message);
+                    }
+
+                    self.if_not_exists = true;
+
+                    if let Some(doc) = self.doc.take() {
+                        return Some(Ok(Item::Comment(doc)));
+                    }
+                }
+                Rule::annotation_timestamps => {
+                    let range = span_range_single(&root_group);
+
+                    if self.timestamps.is_some() {
+                        let message = "`@timestamps` is given more than once".to_string();
+
+                        return self.invalid_annotation(range, message);
+                    }
+
+                    if self.decls != 0 {
+                        let message =
+                            "`@timestamps` has to come before every declaration".to_string();
+
+                        return self.invalid_annotation(range, message);
+                    }
+
+                    let mut created = None;
+                    let mut updated = None;
+
+                    for option in root_group.into_inner() {
+                        let range = span_range_single(&option);
+                        let rule = option.as_rule();
+
+                        let name = match option
+                            .into_inner()
+                            .next()
+                            .and_then(|column| column.into_inner().next())
+                        {
+                            Some(pair) => pair.as_str(),
+                            None => return self.fail(Error::UnexpectedEOS),
+                        };
+
+                        let (slot, label) = match rule {
+                            Rule::timestamps_created => (&mut created, "created"),
+                            _ => (&mut updated, "updated"),
+                        };
+
+                        if slot.is_some() {
+                            let message = format!("`{}` is given more than once", label);
+
+                            return self.invalid_annotation(range, message);
+                        }
+
+                        *slot = Some(name);
+                    }
+
+                    let timestamps = Timestamps {
+                        created: created.unwrap_or("Created"),
+                        updated: updated.unwrap_or("Updated"),
+                    };
+
+                    if timestamps.created == timestamps.updated {
+                        let message = format!(
+                            "`created` and `updated` are both named `{}`",
+                            timestamps.created
+                        );
+
+                        return self.invalid_annotation(range, message);
+                    }
+
+                    self.timestamps = Some(timestamps);
+
+                    if let Some(doc) = self.doc.take() {
+                        return Some(Ok(Item::Comment(doc)));
+                    }
+                }
+                Rule::decl_enum => {
+                    let mut decl = match parse_enum(self.ctx, root_group) {
+                        Ok(decl) => decl,
+                        Err(err) => return self.fail(err),
+                    };
+
+                    decl.not_exists |= self.if_not_exists;
+
+                    self.decls += 1;
+
+                    // Enums have no documentation, so the comment is kept on its own
+                    return match self.doc.take() {
+                        Some(doc) => {
+                            self.queued = Some(Item::Enum(decl));
+
+                            Some(Ok(Item::Comment(doc)))
+                        }
+                        None => Some(Ok(Item::Enum(decl))),
+                    };
+                }
+                Rule::decl_mixin => {
+                    let before = self.ctx.diags.len();
+
+                    let (decl, _, _) = match parse_table(self.ctx, root_group) {
+                        Ok(decl) => decl,
+                        Err(err) => return self.fail(err),
+                    };
+
+                    self.decls += 1;
+
+                    // Still kept when invalid, so the tables including it are not reported as well
+                    self.mixins.push(decl);
+
+                    if self.ctx.diags.len() != before {
+                        return Some(Err(Error::Invalid));
+                    }
+
+                    // Mixins are not items themselves, so the comment is kept on its own
+                    if let Some(doc) = self.doc.take() {
+                        return Some(Ok(Item::Comment(doc)));
+                    }
+                }
+                Rule::decl_table => {
+                    // Column modifiers are checked while parsing, so the count has to be taken first
+                    let before = self.ctx.diags.len();
+
+                    let (mut decl, includes, no_timestamps) =
+                        match parse_table(self.ctx, root_group) {
+                            Ok(decl) => decl,
+                            Err(err) => return self.fail(err),
+                        };
+
+                    decl.doc = self.doc.take();
+
+                    if self.if_not_exists {
+                        decl.not_exists = true;
+
+                        for index in &mut decl.indexes {
+                            index.not_exists = true;
+                        }
+                    }
+
+                    self.decls += 1;
+
+                    include_mixins(self.ctx, self.input, &mut decl, &includes, &self.mixins);
+
+                    if let (Some(timestamps), false) = (self.timestamps, no_timestamps) {
+                        add_timestamps(&mut decl, timestamps);
+                    }
+
+                    validate_table(self.ctx, self.input, &decl);
+
+                    if self.ctx.diags.len() != before {
+                        return Some(Err(Error::Invalid));
+                    }
+
+                    return Some(Ok(Item::Table(decl)));
+                }
+                Rule::comment => {
+                    // Only the last comment before a table documents it, any before that are kept on their own
+                    let previous = self.doc.take();
+
+                    self.doc = parse_comment(root_group);
+
+                    if let Some(previous) = previous {
+                        return Some(Ok(Item::Comment(previous)));
+                    }
+                }
+                Rule::EOI => {
+                    self.pairs = None;
+
+                    return self.doc.take().map(|doc| Ok(Item::Comment(doc)));
+                }
+                _ => {
+                    self.ctx.diags.push(
+                        Diagnostic::error()
+                            .with_message("Unexpected token")
+                            .with_labels(vec![Label::primary(self.ctx.file_id, span_range_single(&root_group))
+                                .with_message(format!(
+                                    "expected `@database`, `@if_not_exists`, `@timestamps`, `enum declaration`, `mixin declaration`, `table declaration`, or `comment`, found `{:?}`",
+                                    root_group.as_rule()
+                                ))]),
+                    );
+
+                    return self.fail(Error::UnexpectedPair(root_group.as_span().into()));
+                }
+            }
+        }
+    }
+}
+
+/// Parses the input with pest, returning the schema's declarations or `None` if it is empty.
+#[inline]
+fn schema_pairs<'i>(ctx: &mut Context, input: &'i str) -> Result<Option<Pairs<'i, Rule>>, Error> {
+    check_input(ctx, input)?;
+
+    let mut pairs: Pairs<'i, Rule> = Parser::parse(Rule::schema, input)?;
+
+    match pairs.next() {
+        Some(pair) if pair.as_rule() == Rule::schema => Ok(Some(pair.into_inner())),
+        Some(pair) if pair.as_rule() == Rule::EOI => Ok(None),
         Some(pair) => {
             ctx.diags.push(
                 Diagnostic::error()
                     .with_message("Unexpected token")
                     .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
                         .with_message(format!(
-                            "expected `ident`, found `{:?}`",
+                            "expected `schema`, found `{:?}`",
                             pair.as_rule()
                         ))]),
             );
 
-            return Err(Error::UnexpectedPair(pair.as_span().into()));
+            Err(Error::UnexpectedPair(pair.as_span().into()))
         }
-        None => {
+        None => Err(Error::UnexpectedEOS),
+    }
+}
+
+/// Parses a single enum declaration on its own, without the rest of a schema.
+pub fn parse_enum_decl<'i>(ctx: &mut Context, input: &'i str) -> Result<Enum<'i>, Error> {
+    let pair = parse_single(ctx, input, Rule::single_enum, Rule::decl_enum)?;
+
+    parse_enum(ctx, pair)
+}
+
+/// Parses a single table declaration on its own, without the rest of a schema.
+pub fn parse_table_decl<'i>(ctx: &mut Context, input: &'i str) -> Result<Table<'i>, Error> {
+    let pair = parse_single(ctx, input, Rule::single_table, Rule::decl_table)?;
+
+    let before = ctx.diags.len();
+
+    let (mut decl, includes, _) = parse_table(ctx, pair)?;
+
+    // A table on its own has no mixins to include
+    include_mixins(ctx, input, &mut decl, &includes, &[]);
+
+    validate_table(ctx, input, &decl);
+
+    if ctx.diags.len() != before {
+        return Err(Error::Invalid);
+    }
+
+    Ok(decl)
+}
+
+#[inline]
+fn parse_single<'i>(
+    ctx: &mut Context,
+    input: &'i str,
+    rule: Rule,
+    expected: Rule,
+) -> Result<Pair<'i, Rule>, Error> {
+    check_input(ctx, input)?;
+
+    let mut pairs: Pairs<'i, Rule> = Parser::parse(rule, input)?;
+
+    match pairs.next() {
+        Some(pair) if pair.as_rule() == expected => Ok(pair),
+        Some(pair) => {
             ctx.diags.push(
                 Diagnostic::error()
-                    .with_message("Unexpected end of stream")
-                    .with_labels(vec![Label::primary(
-                        ctx.file_id,
-                        span_range_end(inner_span),
-                    )
-                    .with_message("here")]),
+                    .with_message("Unexpected token")
+                    .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
+                        .with_message(format!(
+                            "expected `{:?}`, found `{:?}`",
+                            expected,
+                            pair.as_rule()
+                        ))]),
             );
 
-            return Err(Error::UnexpectedEOS);
+            Err(Error::UnexpectedPair(pair.as_span().into()))
         }
-    };
+        None => Err(Error::UnexpectedEOS),
+    }
+}
 
-    let typ = match inner.next() {
-        Some(pair) if pair.as_rule() == Rule::column_type => Types::from_str(pair.as_str()),
+#[inline]
+fn parse_enum<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<Enum<'i>, Error> {
+    debug_assert!(
+        pair.as_rule() == Rule::decl_enum,
+        "The root pair must be a `decl_enum` to be able to parse a enum declaration"
+    );
+
+    let inner_span = pair.as_span();
+    let mut inner: Pairs<'i, Rule> = pair.into_inner();
+
+    let name = match inner.next() {
+        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
         Some(pair) => {
             ctx.diags.push(
                 Diagnostic::error()
                     .with_message("Unexpected token")
                     .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
                         .with_message(format!(
-                            "expected `column type`, found `{:?}`",
+                            "expected `ident`, found `{:?}`",
                             pair.as_rule()
                         ))]),
             );
@@ -374,8 +869,8 @@ fn parse_column<'i>(
         }
     };
 
-    let null = match inner.peek() {
-        Some(peeked) if peeked.as_rule() == Rule::null => {
+    let not_exists = match inner.peek() {
+        Some(peeked) if peeked.as_rule() == Rule::exists => {
             let _ = inner.next();
 
             true
@@ -383,98 +878,238 @@ fn parse_column<'i>(
         _ => false,
     };
 
-    let modifiers = match inner.next() {
-        Some(pair) if pair.as_rule() == Rule::modifiers => parse_modifiers(ctx, pair)?,
+    let mut variants = Vec::with_capacity(inner.size_hint().0);
+
+    let mut doc = None;
+
+    for pair in inner {
+        if variants.len() == ctx.limits.max_variants && pair.as_rule() == Rule::variant {
+            let max = ctx.limits.max_variants;
+
+            return Err(limit_exceeded(
+                ctx,
+                span_range_single(&pair),
+                "variants",
+                max,
+            ));
+        }
+
+        match pair.as_rule() {
+            Rule::variant => variants.push(Variant {
+                name: pair.as_str(),
+                doc: doc.take(),
+            }),
+            Rule::comment => doc = parse_comment(pair),
+            _ => {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Unexpected token")
+                        .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
+                            .with_message(format!(
+                                "expected `variant` or `comment`, found `{:?}`",
+                                pair.as_rule()
+                            ))]),
+                );
+
+                return Err(Error::UnexpectedPair(pair.as_span().into()));
+            }
+        }
+    }
+
+    Ok(Enum {
+        name,
+        not_exists,
+        variants,
+    })
+}
+
+#[inline]
+/// Parses a table or mixin declaration, along with the names of the mixins the table includes and if it opted out of
+/// `@timestamps`.
+fn parse_table<'i>(
+    ctx: &mut Context,
+    pair: Pair<'i, Rule>,
+) -> Result<(Table<'i>, Vec<&'i str>, bool), Error> {
+    debug_assert!(
+        pair.as_rule() == Rule::decl_table || pair.as_rule() == Rule::decl_mixin,
+        "The root pair must be a `decl_table` or `decl_mixin` to be able to parse a table declaration"
+    );
+
+    let inner_span = pair.as_span();
+    let mut inner: Pairs<'i, Rule> = pair.into_inner();
+
+    let name = match inner.next() {
+        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
         Some(pair) => {
             ctx.diags.push(
                 Diagnostic::error()
                     .with_message("Unexpected token")
                     .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
                         .with_message(format!(
-                            "expected `modifiers`, found `{:?}`",
+                            "expected `ident`, found `{:?}`",
                             pair.as_rule()
                         ))]),
             );
 
             return Err(Error::UnexpectedPair(pair.as_span().into()));
         }
-        None => vec![],
-    };
+        None => {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Unexpected end of stream")
+                    .with_labels(vec![Label::primary(
+                        ctx.file_id,
+                        span_range_end(inner_span),
+                    )
+                    .with_message("here")]),
+            );
 
-    Ok((ColumnPartial { name, typ, null }, modifiers))
-}
+            return Err(Error::UnexpectedEOS);
+        }
+    };
 
-#[inline]
-fn parse_modifiers<'i>(
-    ctx: &mut Context,
-    pair: Pair<'i, Rule>,
-) -> Result<Vec<Modifier<'i>>, Error> {
-    debug_assert!(
-        pair.as_rule() == Rule::modifiers,
-        "The root pair must be a `modifiers` to be able to parse column modifiers"
-    );
+    let not_exists = match inner.peek() {
+        Some(peeked) if peeked.as_rule() == Rule::exists => {
+            let _ = inner.next();
 
-    let inner_span = pair.as_span();
-    let inner: Pairs<'i, Rule> = pair.into_inner();
+            true
+        }
+        _ => false,
+    };
 
-    let mut modifiers = Vec::new();
+    let mut columns = Vec::new();
+    let mut primary_keys = Vec::new();
+    let mut foreign_keys = Vec::new();
+    let mut unique_keys = Vec::new();
+    let mut unique_names = Vec::new();
+    let mut unique_nulls_not_distinct = false;
+    let mut without_rowid = false;
+    let mut no_timestamps = false;
+    let mut indexes = Vec::new();
+    let mut includes = Vec::new();
+
+    let mut doc = None;
 
     for pair in inner {
-        match pair.as_rule() {
-            Rule::modifier_default => {
-                let mut inner = pair.into_inner();
+        if columns.len() == ctx.limits.max_columns && pair.as_rule() == Rule::column {
+            let max = ctx.limits.max_columns;
 
-                let default = match inner.next() {
-                    Some(pair) if pair.as_rule() == Rule::modifier_default_value => pair.as_str(),
-                    Some(pair) => {
-                        ctx.diags.push(
-                            Diagnostic::error()
-                                .with_message("Unexpected token")
-                                .with_labels(vec![Label::primary(
-                                    ctx.file_id,
-                                    span_range_single(&pair),
-                                )
-                                .with_message(format!(
-                                    "expected `modifier default value`, found `{:?}`",
-                                    pair.as_rule()
-                                ))]),
-                        );
+            return Err(limit_exceeded(
+                ctx,
+                span_range_single(&pair),
+                "columns",
+                max,
+            ));
+        }
 
-                        return Err(Error::UnexpectedPair(pair.as_span().into()));
-                    }
-                    None => {
-                        ctx.diags.push(
-                            Diagnostic::error()
-                                .with_message("Unexpected end of stream")
-                                .with_labels(vec![Label::primary(
-                                    ctx.file_id,
-                                    span_range_end(inner_span),
-                                )
-                                .with_message("here")]),
-                        );
+        match pair.as_rule() {
+            Rule::column => {
+                let (col, modifiers) = parse_column(ctx, pair)?;
+                let mut default = ColumnDefault::default();
+                let mut skip = Vec::new();
+                let mut rust_name = None;
+                let mut collate = None;
+                let mut searchable = false;
+                let mut version = false;
 
-                        return Err(Error::UnexpectedEOS);
+                for modifier in modifiers {
+                    match modifier {
+                        Modifier::Collate { name } => collate = Some(name),
+                        Modifier::Default { value } => default = ColumnDefault::Raw(value),
+                        Modifier::DefaultBool { value } => default = ColumnDefault::Bool(value),
+                        Modifier::DefaultDateTime => default = ColumnDefault::Now,
+                        Modifier::DefaultCurrentDate => default = ColumnDefault::CurrentDate,
+                        Modifier::DefaultCurrentTime => default = ColumnDefault::CurrentTime,
+                        Modifier::DefaultNull => default = ColumnDefault::Null,
+                        Modifier::DefaultUuid => default = ColumnDefault::Uuid,
+                        Modifier::PrimaryKey => primary_keys.push(col.name),
+                        Modifier::Reference {
+                            table,
+                            column,
+                            delete,
+                            update,
+                            deferrable,
+                            name,
+                        } => foreign_keys.push(ForeignKey {
+                            local: vec![col.name],
+                            table,
+                            foreign: vec![column],
+                            delete: delete.clone(),
+                            update: update.clone(),
+                            deferrable,
+                            name,
+                        }),
+                        Modifier::RustName { name } => rust_name = Some(name),
+                        Modifier::Searchable => searchable = true,
+                        Modifier::Skip { targets } => skip.extend(targets),
+                        Modifier::Unique {
+                            name,
+                            nulls_not_distinct,
+                        } => {
+                            unique_keys.push(col.name);
+                            unique_names.extend(name);
+
+                            // There is one unique constraint, so any column asking for it applies to all of them
+                            unique_nulls_not_distinct |= nulls_not_distinct;
+                        }
+                        Modifier::Version => version = true,
                     }
-                };
+                }
 
-                modifiers.push(match default {
-                    "now()" => Modifier::DefaultDateTime,
-                    "null" => Modifier::DefaultNull,
-                    value => Modifier::Default { value },
+                columns.push(Column {
+                    name: col.name,
+                    doc: doc.take(),
+                    typ: col.typ,
+                    null: col.null,
+                    default,
+                    skip,
+                    rust_name,
+                    collate,
+                    searchable,
+                    version,
                 });
             }
-            Rule::modifier_primary => modifiers.push(Modifier::PrimaryKey),
-            Rule::modifier_ref => {
-                let modifier = parse_modifier_ref(ctx, pair)?;
-                modifiers.push(modifier);
+            Rule::comment => {
+                doc = parse_comment(pair);
+
+                continue;
+            }
+            Rule::index => {
+                doc = None;
+
+                let mut index = parse_index(pair);
+
+                // A guarded table's indexes are guarded as well, so running the schema again does not fail on them
+                index.not_exists |= not_exists;
+
+                indexes.push(index);
+            }
+            Rule::foreign_key => {
+                doc = None;
+
+                foreign_keys.push(parse_foreign_key(ctx, pair)?);
+            }
+            Rule::includes => {
+                includes.extend(pair.into_inner().map(|pair| pair.as_str()));
+            }
+            Rule::table_options => {
+                for option in pair.into_inner() {
+                    match option.as_rule() {
+                        Rule::option_without_rowid => without_rowid = true,
+                        Rule::option_no_timestamps => no_timestamps = true,
+                        _ => {}
+                    }
+                }
             }
-            Rule::modifier_unique => modifiers.push(Modifier::Unique),
             _ => {
                 ctx.diags.push(
                     Diagnostic::error()
                         .with_message("Unexpected token")
                         .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
-                        .with_message(format!("expected `modifier default`, `modifier primary`, `modifier reference`, or `modifier unique`, found `{:?}`", pair.as_rule()))]),
+                            .with_message(format!(
+                        "expected `column`, `index`, `foreign key`, `comment`, `includes`, or `table options`, found `{:?}`",
+                        pair.as_rule()
+                    ))]),
                 );
 
                 return Err(Error::UnexpectedPair(pair.as_span().into()));
@@ -482,20 +1117,206 @@ fn parse_modifiers<'i>(
         }
     }
 
-    Ok(modifiers)
+    validate_unique_names(ctx, inner_span, &unique_names);
+
+    Ok((
+        Table {
+            name,
+            doc: None,
+            not_exists,
+            columns,
+            primary_keys,
+            foreign_keys,
+            unique_keys,
+            unique_name: unique_names.first().copied(),
+            unique_nulls_not_distinct,
+            without_rowid,
+            indexes,
+        },
+        includes,
+        no_timestamps,
+    ))
+}
+
+/// Adds the `@timestamps` columns to the end of a table, a column the table already has is kept as it is declared.
+fn add_timestamps<'i>(decl: &mut Table<'i>, timestamps: Timestamps<'i>) {
+    for name in [timestamps.created, timestamps.updated].iter().copied() {
+        if decl.columns.iter().any(|c| c.name == name) {
+            continue;
+        }
+
+        decl.columns.push(Column {
+            name,
+            doc: None,
+            typ: Types::DateTime,
+            null: false,
+            default: ColumnDefault::Now,
+            skip: vec![],
+            rust_name: None,
+            collate: None,
+            searchable: false,
+            version: false,
+        });
+    }
+}
+
+/// Splices the columns of the included mixins onto the end of a table, in the order they are included.
+///
+/// Mixins have to be declared before the tables that include them.
+fn include_mixins<'i>(
+    ctx: &mut Context,
+    input: &str,
+    decl: &mut Table<'i>,
+    includes: &[&'i str],
+    mixins: &[Table<'i>],
+) {
+    for include in includes {
+        let mixin = match mixins.iter().find(|mixin| mixin.name == *include) {
+            Some(mixin) => mixin,
+            None => {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Unknown mixin")
+                        .with_labels(vec![Label::primary(ctx.file_id, str_range(input, include))
+                            .with_message(format!(
+                                "mixin `{}` is not declared before table `{}`",
+                                include, decl.name
+                            ))]),
+                );
+
+                continue;
+            }
+        };
+
+        for column in &mixin.columns {
+            if decl.columns.iter().any(|c| c.name == column.name) {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Duplicate column")
+                        .with_labels(vec![Label::primary(ctx.file_id, str_range(input, include))
+                            .with_message(format!(
+                                "column `{}` from mixin `{}` is already in table `{}`",
+                                column.name, mixin.name, decl.name
+                            ))]),
+                );
+
+                continue;
+            }
+
+            decl.columns.push(column.clone());
+        }
+
+        decl.primary_keys.extend(mixin.primary_keys.iter().copied());
+        decl.foreign_keys.extend(mixin.foreign_keys.iter().cloned());
+        decl.unique_keys.extend(mixin.unique_keys.iter().copied());
+
+        if decl.unique_name.is_none() {
+            decl.unique_name = mixin.unique_name;
+        }
+
+        decl.unique_nulls_not_distinct |= mixin.unique_nulls_not_distinct;
+    }
 }
 
 #[inline]
-fn parse_modifier_ref<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<Modifier<'i>, Error> {
+fn parse_index<'i>(pair: Pair<'i, Rule>) -> Index<'i> {
     debug_assert!(
-        pair.as_rule() == Rule::modifier_ref,
-        "The root pair must be a `modifier_ref` to be able to parse column ref modifier"
+        pair.as_rule() == Rule::index,
+        "The root pair must be a `index` to be able to parse a index declaration"
+    );
+
+    let mut name = "";
+    let mut not_exists = false;
+    let mut columns = Vec::new();
+    let mut predicate = None;
+
+    for (i, pair) in pair.into_inner().enumerate() {
+        match pair.as_rule() {
+            Rule::ident if i == 0 => name = pair.as_str(),
+            Rule::exists => not_exists = true,
+            Rule::ident => columns.push(pair.as_str()),
+            Rule::index_predicate => predicate = Some(pair.as_str().trim_end()),
+            _ => unreachable!(),
+        }
+    }
+
+    Index {
+        name,
+        not_exists,
+        columns,
+        predicate,
+    }
+}
+
+/// Parses a table level `foreign key (a, b) references Table (x, y)`, the declaration for references spanning
+/// more than one column.
+#[inline]
+fn parse_foreign_key<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<ForeignKey<'i>, Error> {
+    debug_assert!(
+        pair.as_rule() == Rule::foreign_key,
+        "The root pair must be a `foreign_key` to be able to parse a foreign key declaration"
+    );
+
+    let mut local = Vec::new();
+    let mut table = "";
+    let mut foreign = Vec::new();
+    let mut name = None;
+    let mut actions = (Action::default(), Action::default(), false);
+
+    for pair in pair.into_inner() {
+        match pair.as_rule() {
+            Rule::key_columns if local.is_empty() => {
+                local.extend(pair.into_inner().map(|pair| pair.as_str()))
+            }
+            Rule::key_columns => foreign.extend(pair.into_inner().map(|pair| pair.as_str())),
+            Rule::ident => table = pair.as_str(),
+            Rule::constraint_name => name = Some(pair.as_str()),
+            Rule::ref_action => actions = parse_modifier_ref_action(ctx, pair)?,
+            _ => unreachable!(),
+        }
+    }
+
+    let (delete, update, deferrable) = actions;
+
+    Ok(ForeignKey {
+        local,
+        table,
+        foreign,
+        delete,
+        update,
+        deferrable,
+        name,
+    })
+}
+
+/// Gets the trimmed text of a comment, comments directly before a declaration or column are kept as its documentation.
+#[inline]
+fn parse_comment<'i>(pair: Pair<'i, Rule>) -> Option<&'i str> {
+    debug_assert!(
+        pair.as_rule() == Rule::comment,
+        "The root pair must be a `comment` to be able to parse a comment"
+    );
+
+    pair.into_inner()
+        .next()
+        .map(|inner| inner.as_str().trim())
+        .filter(|text| !text.is_empty())
+}
+
+#[inline]
+fn parse_column<'i>(
+    ctx: &mut Context,
+    pair: Pair<'i, Rule>,
+) -> Result<(ColumnPartial<'i>, Vec<Modifier<'i>>), Error> {
+    debug_assert!(
+        pair.as_rule() == Rule::column,
+        "The root pair must be a `column` to be able to parse a table column definition"
     );
 
     let inner_span = pair.as_span();
     let mut inner: Pairs<'i, Rule> = pair.into_inner();
 
-    let table = match inner.next() {
+    let name = match inner.next() {
         Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
         Some(pair) => {
             ctx.diags.push(
@@ -525,15 +1346,15 @@ fn parse_modifier_ref<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<Mod
         }
     };
 
-    let column = match inner.next() {
-        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
+    let mut typ = match inner.next() {
+        Some(pair) if pair.as_rule() == Rule::column_type => Types::from_str(pair.as_str()),
         Some(pair) => {
             ctx.diags.push(
                 Diagnostic::error()
                     .with_message("Unexpected token")
                     .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
                         .with_message(format!(
-                            "expected `ident`, found `{:?}`",
+                            "expected `column type`, found `{:?}`",
                             pair.as_rule()
                         ))]),
             );
@@ -555,56 +1376,160 @@ fn parse_modifier_ref<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<Mod
         }
     };
 
-    let (delete, update) = match inner.next() {
-        Some(pair) if pair.as_rule() == Rule::ref_action => parse_modifier_ref_action(ctx, pair)?,
+    while let Some(peeked) = inner.peek() {
+        if peeked.as_rule() != Rule::array {
+            break;
+        }
+
+        let _ = inner.next();
+
+        typ = Types::Array(Box::new(typ));
+    }
+
+    let null = match inner.peek() {
+        Some(peeked) if peeked.as_rule() == Rule::null => {
+            let _ = inner.next();
+
+            true
+        }
+        _ => false,
+    };
+
+    let modifiers = match inner.next() {
+        Some(pair) if pair.as_rule() == Rule::modifiers => {
+            check_modifiers(ctx, name, null, &pair);
+
+            parse_modifiers(ctx, pair)?
+        }
         Some(pair) => {
             ctx.diags.push(
                 Diagnostic::error()
                     .with_message("Unexpected token")
                     .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
                         .with_message(format!(
-                            "expected `modifier reference action(s)`, found `{:?}`",
+                            "expected `modifiers`, found `{:?}`",
                             pair.as_rule()
                         ))]),
             );
 
             return Err(Error::UnexpectedPair(pair.as_span().into()));
         }
-        None => (Action::default(), Action::default()),
+        None => vec![],
     };
 
-    Ok(Modifier::Reference {
-        table,
-        column,
-        delete,
-        update,
-    })
+    Ok((ColumnPartial { name, typ, null }, modifiers))
+}
+
+/// Reports column modifiers that contradict each other or the column, such as a nullable primary key or a second
+/// default, pointing at the offending modifier.
+///
+/// Only diagnostics are pushed, the caller turns them into an [`Error::Invalid`].
+fn check_modifiers(ctx: &mut Context, name: &str, null: bool, pair: &Pair<Rule>) {
+    let mut seen: Vec<(Rule, Range<usize>)> = Vec::new();
+    let mut primary = None;
+    let mut default_null = None;
+
+    for modifier in pair.clone().into_inner() {
+        let rule = modifier.as_rule();
+        let range = span_range_single(&modifier);
+
+        let label = match rule {
+            Rule::modifier_default => {
+                let value = modifier.clone().into_inner().next();
+
+                if value.map(|value| value.as_str().trim_end()) == Some("null") {
+                    default_null = Some(range.clone());
+                }
+
+                "default"
+            }
+            Rule::modifier_primary => {
+                primary = Some(range.clone());
+
+                "primary key"
+            }
+            Rule::modifier_collate => "collate",
+            Rule::modifier_rust_name => "as",
+            Rule::modifier_searchable => "searchable",
+            Rule::modifier_skip => "skip",
+            Rule::modifier_unique => "unique",
+            Rule::modifier_version => "version",
+            // A column can reference more than one table
+            _ => continue,
+        };
+
+        match seen.iter().find(|(seen, _)| *seen == rule) {
+            Some((_, first)) => ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Duplicate modifier")
+                    .with_labels(vec![
+                        Label::primary(ctx.file_id, range).with_message(format!(
+                            "`{}` is given more than once on column `{}`",
+                            label, name
+                        )),
+                        Label::secondary(ctx.file_id, first.clone())
+                            .with_message("first given here"),
+                    ]),
+            ),
+            None => seen.push((rule, range)),
+        }
+    }
+
+    let primary = match primary {
+        Some(primary) => primary,
+        None => return,
+    };
+
+    if null {
+        ctx.diags.push(
+            Diagnostic::error()
+                .with_message("Contradictory modifiers")
+                .with_labels(vec![Label::primary(ctx.file_id, primary.clone())
+                    .with_message(format!(
+                        "column `{}` is nullable, but a primary key can not be null",
+                        name
+                    ))]),
+        );
+    }
+
+    if let Some(default_null) = default_null {
+        ctx.diags.push(
+            Diagnostic::error()
+                .with_message("Contradictory modifiers")
+                .with_labels(vec![
+                    Label::primary(ctx.file_id, default_null).with_message(format!(
+                        "`default: null` on column `{}`, but a primary key can not be null",
+                        name
+                    )),
+                    Label::secondary(ctx.file_id, primary).with_message("primary key given here"),
+                ]),
+        );
+    }
 }
 
 #[inline]
-fn parse_modifier_ref_action<'i>(
+fn parse_modifiers<'i>(
     ctx: &mut Context,
     pair: Pair<'i, Rule>,
-) -> Result<(Action, Action), Error> {
+) -> Result<Vec<Modifier<'i>>, Error> {
     debug_assert!(
-        pair.as_rule() == Rule::ref_action,
-        "The root pair must be a `ref_action` to be able to parse column ref modifier"
+        pair.as_rule() == Rule::modifiers,
+        "The root pair must be a `modifiers` to be able to parse column modifiers"
     );
 
     let inner_span = pair.as_span();
     let inner: Pairs<'i, Rule> = pair.into_inner();
 
-    let mut delete = Action::default();
-    let mut update = Action::default();
+    let mut modifiers = Vec::new();
 
     for pair in inner {
-        let (rule, action) = match pair.as_rule() {
-            Rule::ref_action_delete => {
-                let mut inner: Pairs<'i, Rule> = pair.into_inner();
+        match pair.as_rule() {
+            Rule::modifier_default => {
+                let mut inner = pair.into_inner();
 
-                match inner.next() {
-                    Some(pair) if pair.as_rule() == Rule::action => {
-                        (Rule::ref_action_delete, pair.as_str())
+                let default = match inner.next() {
+                    Some(pair) if pair.as_rule() == Rule::modifier_default_value => {
+                        pair.as_str().trim_end()
                     }
                     Some(pair) => {
                         ctx.diags.push(
@@ -615,7 +1540,7 @@ fn parse_modifier_ref_action<'i>(
                                     span_range_single(&pair),
                                 )
                                 .with_message(format!(
-                                    "expected `action`, found `{:?}`",
+                                    "expected `modifier default value`, found `{:?}`",
                                     pair.as_rule()
                                 ))]),
                         );
@@ -633,135 +1558,2147 @@ fn parse_modifier_ref_action<'i>(
                                 .with_message("here")]),
                         );
 
-                        return Err(Error::UnexpectedEOS);
-                    }
+                        return Err(Error::UnexpectedEOS);
+                    }
+                };
+
+                modifiers.push(match default {
+                    "now()" => Modifier::DefaultDateTime,
+                    "today()" => Modifier::DefaultCurrentDate,
+                    "currentTime()" => Modifier::DefaultCurrentTime,
+                    "null" => Modifier::DefaultNull,
+                    "uuid()" => Modifier::DefaultUuid,
+                    "true" => Modifier::DefaultBool { value: true },
+                    "false" => Modifier::DefaultBool { value: false },
+                    value => Modifier::Default { value },
+                });
+            }
+            Rule::modifier_collate => {
+                if let Some(name) = pair.into_inner().next() {
+                    modifiers.push(Modifier::Collate {
+                        name: name.as_str(),
+                    });
+                }
+            }
+            Rule::modifier_primary => modifiers.push(Modifier::PrimaryKey),
+            Rule::modifier_ref => {
+                let modifier = parse_modifier_ref(ctx, pair)?;
+                modifiers.push(modifier);
+            }
+            Rule::modifier_rust_name => {
+                if let Some(name) = pair.into_inner().next() {
+                    modifiers.push(Modifier::RustName {
+                        name: name.as_str(),
+                    });
+                }
+            }
+            Rule::modifier_searchable => modifiers.push(Modifier::Searchable),
+            Rule::modifier_version => modifiers.push(Modifier::Version),
+            Rule::modifier_skip => modifiers.push(Modifier::Skip {
+                targets: pair.into_inner().map(|pair| pair.as_str()).collect(),
+            }),
+            Rule::modifier_unique => {
+                let mut name = None;
+                let mut nulls_not_distinct = false;
+
+                for pair in pair.into_inner() {
+                    match pair.as_rule() {
+                        Rule::nulls_not_distinct => nulls_not_distinct = true,
+                        _ => name = Some(pair.as_str()),
+                    }
+                }
+
+                modifiers.push(Modifier::Unique {
+                    name,
+                    nulls_not_distinct,
+                });
+            }
+            _ => {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Unexpected token")
+                        .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
+                        .with_message(format!("expected `modifier collate`, `modifier default`, `modifier primary`, `modifier reference`, `modifier rust name`, `modifier searchable`, `modifier skip`, `modifier unique`, or `modifier version`, found `{:?}`", pair.as_rule()))]),
+                );
+
+                return Err(Error::UnexpectedPair(pair.as_span().into()));
+            }
+        }
+    }
+
+    Ok(modifiers)
+}
+
+#[inline]
+fn parse_modifier_ref<'i>(ctx: &mut Context, pair: Pair<'i, Rule>) -> Result<Modifier<'i>, Error> {
+    debug_assert!(
+        pair.as_rule() == Rule::modifier_ref,
+        "The root pair must be a `modifier_ref` to be able to parse column ref modifier"
+    );
+
+    let inner_span = pair.as_span();
+    let mut inner: Pairs<'i, Rule> = pair.into_inner();
+
+    let table = match inner.next() {
+        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
+        Some(pair) => {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Unexpected token")
+                    .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
+                        .with_message(format!(
+                            "expected `ident`, found `{:?}`",
+                            pair.as_rule()
+                        ))]),
+            );
+
+            return Err(Error::UnexpectedPair(pair.as_span().into()));
+        }
+        None => {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Unexpected end of stream")
+                    .with_labels(vec![Label::primary(
+                        ctx.file_id,
+                        span_range_end(inner_span),
+                    )
+                    .with_message("here")]),
+            );
+
+            return Err(Error::UnexpectedEOS);
+        }
+    };
+
+    let column = match inner.next() {
+        Some(pair) if pair.as_rule() == Rule::ident => pair.as_str(),
+        Some(pair) => {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Unexpected token")
+                    .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
+                        .with_message(format!(
+                            "expected `ident`, found `{:?}`",
+                            pair.as_rule()
+                        ))]),
+            );
+
+            return Err(Error::UnexpectedPair(pair.as_span().into()));
+        }
+        None => {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Unexpected end of stream")
+                    .with_labels(vec![Label::primary(
+                        ctx.file_id,
+                        span_range_end(inner_span),
+                    )
+                    .with_message("here")]),
+            );
+
+            return Err(Error::UnexpectedEOS);
+        }
+    };
+
+    let name = match inner.peek() {
+        Some(peeked) if peeked.as_rule() == Rule::constraint_name => {
+            inner.next().map(|pair| pair.as_str())
+        }
+        _ => None,
+    };
+
+    let (delete, update, deferrable) = match inner.next() {
+        Some(pair) if pair.as_rule() == Rule::ref_action => parse_modifier_ref_action(ctx, pair)?,
+        Some(pair) => {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Unexpected token")
+                    .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
+                        .with_message(format!(
+                            "expected `modifier reference action(s)`, found `{:?}`",
+                            pair.as_rule()
+                        ))]),
+            );
+
+            return Err(Error::UnexpectedPair(pair.as_span().into()));
+        }
+        None => (Action::default(), Action::default(), false),
+    };
+
+    Ok(Modifier::Reference {
+        table,
+        column,
+        delete,
+        update,
+        deferrable,
+        name,
+    })
+}
+
+#[inline]
+fn parse_modifier_ref_action<'i>(
+    ctx: &mut Context,
+    pair: Pair<'i, Rule>,
+) -> Result<(Action, Action, bool), Error> {
+    debug_assert!(
+        pair.as_rule() == Rule::ref_action,
+        "The root pair must be a `ref_action` to be able to parse column ref modifier"
+    );
+
+    let inner_span = pair.as_span();
+    let inner: Pairs<'i, Rule> = pair.into_inner();
+
+    let mut delete = Action::default();
+    let mut update = Action::default();
+    let mut deferrable = false;
+
+    for pair in inner {
+        let (rule, action) = match pair.as_rule() {
+            Rule::ref_deferrable => {
+                deferrable = true;
+
+                continue;
+            }
+            Rule::ref_action_delete => {
+                let mut inner: Pairs<'i, Rule> = pair.into_inner();
+
+                match inner.next() {
+                    Some(pair) if pair.as_rule() == Rule::action => {
+                        (Rule::ref_action_delete, pair.as_str())
+                    }
+                    Some(pair) => {
+                        ctx.diags.push(
+                            Diagnostic::error()
+                                .with_message("Unexpected token")
+                                .with_labels(vec![Label::primary(
+                                    ctx.file_id,
+                                    span_range_single(&pair),
+                                )
+                                .with_message(format!(
+                                    "expected `action`, found `{:?}`",
+                                    pair.as_rule()
+                                ))]),
+                        );
+
+                        return Err(Error::UnexpectedPair(pair.as_span().into()));
+                    }
+                    None => {
+                        ctx.diags.push(
+                            Diagnostic::error()
+                                .with_message("Unexpected end of stream")
+                                .with_labels(vec![Label::primary(
+                                    ctx.file_id,
+                                    span_range_end(inner_span),
+                                )
+                                .with_message("here")]),
+                        );
+
+                        return Err(Error::UnexpectedEOS);
+                    }
+                }
+            }
+            Rule::ref_action_update => {
+                let mut inner: Pairs<'i, Rule> = pair.into_inner();
+
+                match inner.next() {
+                    Some(pair) if pair.as_rule() == Rule::action => {
+                        (Rule::ref_action_update, pair.as_str())
+                    }
+                    Some(pair) => {
+                        ctx.diags.push(
+                            Diagnostic::error()
+                                .with_message("Unexpected token")
+                                .with_labels(vec![Label::primary(
+                                    ctx.file_id,
+                                    span_range_single(&pair),
+                                )
+                                .with_message(format!(
+                                    "expected `action`, found `{:?}`",
+                                    pair.as_rule()
+                                ))]),
+                        );
+
+                        return Err(Error::UnexpectedPair(pair.as_span().into()));
+                    }
+                    None => {
+                        ctx.diags.push(
+                            Diagnostic::error()
+                                .with_message("Unexpected end of stream")
+                                .with_labels(vec![Label::primary(
+                                    ctx.file_id,
+                                    span_range_end(inner_span),
+                                )
+                                .with_message("here")]),
+                        );
+
+                        return Err(Error::UnexpectedEOS);
+                    }
+                }
+            }
+            _ => {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Unexpected token")
+                        .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
+                        .with_message(format!("expected `modifier reference action delete`, `modifier reference action update`, or `deferrable`, found `{:?}`", pair.as_rule()))]),
+                );
+
+                return Err(Error::UnexpectedPair(pair.as_span().into()));
+            }
+        };
+
+        match rule {
+            Rule::ref_action_delete => delete = Action::try_from(action)?,
+            Rule::ref_action_update => update = Action::try_from(action)?,
+            _ => unreachable!(),
+        }
+    }
+
+    Ok((delete, update, deferrable))
+}
+
+#[cfg(test)]
+mod tests {
+    pub use {
+        super::*,
+        crate::models::{Column, Index, Item, Table, Variant},
+        codespan_reporting::{
+            files::SimpleFiles,
+            term::{
+                self,
+                termcolor::{Buffer, ColorChoice, StandardStream, WriteColor},
+                Config,
+            },
+        },
+    };
+
+    fn assert_span(name: &str, input: &str, out: Schema) {
+        let mut files = SimpleFiles::new();
+
+        let file_id = files.add(name, input);
+
+        let mut ctx = Context::new(file_id);
+
+        match parse(&mut ctx, input) {
+            Ok(schema) => assert_eq!(out, schema,),
+            Err(err) => {
+                let mut writer = Buffer::no_color();
+                let config = Config::default();
+
+                for diag in ctx.diags {
+                    term::emit(&mut writer, &config, &files, &diag).unwrap();
+                }
+
+                panic!(
+                    "{}{:?}",
+                    String::from_utf8_lossy(writer.as_slice()).into_owned(),
+                    err
+                );
+            }
+        }
+    }
+
+    mod defaults {
+        use super::*;
+
+        fn assert_default(value: &str, expected: ColumnDefault<'_>) {
+            let input = format!(
+                "table Example {{ value text! [default: {}, unique] }}",
+                value
+            );
+
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(&mut ctx, &input).expect("Unable to parse table");
+
+            assert_eq!(expected, decl.columns[0].default);
+            assert_eq!(vec!["value"], decl.unique_keys);
+        }
+
+        #[test]
+        fn now() {
+            assert_default("now()", ColumnDefault::Now);
+        }
+
+        #[test]
+        fn today() {
+            assert_default("today()", ColumnDefault::CurrentDate);
+        }
+
+        #[test]
+        fn current_time() {
+            assert_default("currentTime()", ColumnDefault::CurrentTime);
+        }
+
+        #[test]
+        fn null() {
+            assert_default("null", ColumnDefault::Null);
+        }
+
+        #[test]
+        fn uuid() {
+            assert_default("uuid()", ColumnDefault::Uuid);
+        }
+
+        #[test]
+        fn bool() {
+            assert_default("true", ColumnDefault::Bool(true));
+            assert_default("false", ColumnDefault::Bool(false));
+        }
+
+        #[test]
+        fn function_call() {
+            assert_default("to_timestamp(0)", ColumnDefault::Raw("to_timestamp(0)"));
+        }
+
+        #[test]
+        fn nested_quoted() {
+            assert_default("nextval('seq')", ColumnDefault::Raw("nextval('seq')"));
+            assert_default(
+                "coalesce(lower('a)'), 'b')",
+                ColumnDefault::Raw("coalesce(lower('a)'), 'b')"),
+            );
+        }
+
+        #[test]
+        fn quoted_comma() {
+            assert_default("'a, b'", ColumnDefault::Raw("'a, b'"));
+            assert_default("'it''s, ]'", ColumnDefault::Raw("'it''s, ]'"));
+        }
+
+        #[test]
+        fn expression() {
+            assert_default("1 + 2", ColumnDefault::Raw("1 + 2"));
+        }
+
+        #[test]
+        fn unbalanced() {
+            let mut ctx = Context::new(0);
+
+            assert!(parse_table_decl(
+                &mut ctx,
+                "table Example { value text [default: lower('a']) }"
+            )
+            .is_err());
+        }
+    }
+
+    mod without_rowid {
+        use super::*;
+
+        #[test]
+        fn option() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Example { key text [primary key] } [without rowid]",
+            )
+            .expect("Unable to parse table");
+
+            assert!(decl.without_rowid);
+
+            let decl = parse_table_decl(&mut ctx, "table Example { key text [primary key] }")
+                .expect("Unable to parse table");
+
+            assert!(!decl.without_rowid);
+        }
+    }
+
+    mod skip {
+        use super::*;
+
+        fn parse_skip(modifiers: &str) -> Table<'_> {
+            let mut ctx = Context::new(0);
+
+            parse_table_decl(&mut ctx, modifiers).expect("Unable to parse table")
+        }
+
+        #[test]
+        fn single() {
+            let decl = parse_skip("table Example { hash text [skip: rust] }");
+
+            assert_eq!(vec!["rust"], decl.columns[0].skip);
+            assert!(decl.columns[0].skips("rust"));
+            assert!(!decl.columns[0].skips("graphql"));
+        }
+
+        #[test]
+        fn multiple() {
+            let decl = parse_skip(
+                "table Example { hash text [skip: rust, graphql, unique, primary key] }",
+            );
+
+            assert_eq!(vec!["rust", "graphql"], decl.columns[0].skip);
+            assert_eq!(vec!["hash"], decl.unique_keys);
+            assert_eq!(vec!["hash"], decl.primary_keys);
+        }
+
+        #[test]
+        fn unknown() {
+            let mut ctx = Context::new(0);
+
+            assert!(parse_table_decl(&mut ctx, "table Example { hash text [skip: sql] }").is_err());
+        }
+    }
+
+    mod rust_name {
+        use super::*;
+
+        #[test]
+        fn renamed() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Example {
+                    id text [primary key]
+                    type text [as: kind, unique]
+                    note text!
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!("type", decl.columns[1].name);
+            assert_eq!(Some("kind"), decl.columns[1].rust_name);
+            assert_eq!(vec!["type"], decl.unique_keys);
+            assert_eq!(None, decl.columns[2].rust_name);
+        }
+    }
+
+    mod collate {
+        use super::*;
+
+        #[test]
+        fn collations() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Example {
+                    id text [primary key, collate: nocase]
+                    name text [collate: \"en_US\", unique]
+                    note text!
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(
+                vec![Some("nocase"), Some("\"en_US\""), None],
+                decl.columns
+                    .iter()
+                    .map(|column| column.collate)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(vec!["name"], decl.unique_keys);
+        }
+    }
+
+    mod searchable {
+        use super::*;
+
+        #[test]
+        fn flagged() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Post {
+                    id text [primary key]
+                    title text [searchable]
+                    body text! [collate: nocase, searchable]
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(
+                vec![false, true, true],
+                decl.columns
+                    .iter()
+                    .map(|column| column.searchable)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    mod version {
+        use super::*;
+
+        #[test]
+        fn flagged() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Setting {
+                    key text [primary key]
+                    revision int [version, default: 0]
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(
+                vec![false, true],
+                decl.columns
+                    .iter()
+                    .map(|column| column.version)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    mod context {
+        use super::*;
+
+        #[test]
+        fn rollback() {
+            let mut ctx = Context::new(0);
+
+            parse(&mut ctx, "table Settings { key text [default: null] }")
+                .expect_err("An invalid schema was parsed");
+
+            let snapshot = ctx.snapshot();
+
+            parse(
+                &mut ctx,
+                "table Settings { key text [primary key, primary key] value text! [default: null] }",
+            )
+            .expect_err("An invalid schema was parsed");
+
+            assert!(ctx.diagnostics().len() > 1);
+            assert_eq!(1, ctx.warnings().len());
+
+            ctx.rollback(snapshot);
+
+            assert_eq!(1, ctx.diagnostics().len());
+            assert!(ctx.warnings().is_empty());
+            assert_eq!("Invalid column default", ctx.diagnostics()[0].message);
+
+            parse(&mut ctx, "table Settings { key text [primary key] }")
+                .expect("Unable to parse schema");
+
+            assert_eq!(snapshot, ctx.snapshot());
+        }
+
+        #[test]
+        fn clone_and_clear() {
+            let mut ctx = Context::new(0);
+
+            parse(&mut ctx, "table Settings { key text [default: null] }")
+                .expect_err("An invalid schema was parsed");
+
+            let before = ctx.clone();
+
+            ctx.clear();
+
+            assert!(ctx.diagnostics().is_empty());
+            assert_eq!(1, before.diagnostics().len());
+        }
+    }
+
+    mod limits {
+        use super::*;
+
+        fn assert_limited(limits: Limits, input: &str, limit: &str) {
+            let mut ctx = Context::with_limits(0, limits);
+
+            match parse(&mut ctx, input) {
+                Err(Error::LimitExceeded(exceeded)) => assert_eq!(limit, exceeded),
+                res => panic!("expected the {} limit to be exceeded, got {:?}", limit, res),
+            }
+
+            assert_eq!(1, ctx.diagnostics().len());
+        }
+
+        #[test]
+        fn input() {
+            assert_limited(
+                Limits {
+                    max_input: 8,
+                    ..Limits::default()
+                },
+                "enum Rating { Explicit }",
+                "bytes",
+            );
+        }
+
+        #[test]
+        fn items() {
+            assert_limited(
+                Limits {
+                    max_items: 1,
+                    ..Limits::default()
+                },
+                "enum Rating { Explicit } /* comment */ enum State { Working }",
+                "items",
+            );
+        }
+
+        #[test]
+        fn columns() {
+            assert_limited(
+                Limits {
+                    max_columns: 1,
+                    ..Limits::default()
+                },
+                "table Settings { key text [primary key] value text }",
+                "columns",
+            );
+        }
+
+        #[test]
+        fn variants() {
+            assert_limited(
+                Limits {
+                    max_variants: 2,
+                    ..Limits::default()
+                },
+                "enum Rating { Explicit Mature Teen }",
+                "variants",
+            );
+        }
+
+        #[test]
+        fn nesting() {
+            assert_limited(
+                Limits {
+                    max_nesting: 2,
+                    ..Limits::default()
+                },
+                "table Settings { key text [primary key] value int [default: (((1)))] }",
+                "levels of nesting",
+            );
+        }
+
+        #[test]
+        fn deep_nesting() {
+            let input = format!(
+                "table Settings {{ key text [primary key] value int [default: {}1{}] }}",
+                "(".repeat(100_000),
+                ")".repeat(100_000),
+            );
+
+            assert_limited(Limits::default(), &input, "levels of nesting");
+        }
+
+        #[test]
+        fn deep_nesting_in_predicate() {
+            let input = format!(
+                "table Settings {{ key text [primary key] value int index settings_value on (value) where {}value > 0{} }}",
+                "(".repeat(100_000),
+                ")".repeat(100_000),
+            );
+
+            assert_limited(Limits::default(), &input, "levels of nesting");
+        }
+
+        #[test]
+        fn deep_nesting_in_single_table() {
+            let mut ctx = Context::new(0);
+
+            let input = format!(
+                "table Settings {{ key text [primary key] value int [default: {}1{}] }}",
+                "(".repeat(100_000),
+                ")".repeat(100_000),
+            );
+
+            match parse_table_decl(&mut ctx, &input) {
+                Err(Error::LimitExceeded(exceeded)) => assert_eq!("levels of nesting", exceeded),
+                res => panic!("expected the nesting limit to be exceeded, got {:?}", res),
+            }
+        }
+
+        #[test]
+        fn nesting_in_strings() {
+            let mut ctx = Context::with_limits(
+                0,
+                Limits {
+                    max_nesting: 1,
+                    ..Limits::default()
+                },
+            );
+
+            parse(
+                &mut ctx,
+                "table Settings { key text [primary key] value text [default: '(('] /* (( */ }",
+            )
+            .expect("parentheses in strings and comments were counted");
+        }
+
+        #[test]
+        fn within() {
+            let mut ctx = Context::with_limits(
+                0,
+                Limits {
+                    max_items: 1,
+                    max_columns: 2,
+                    ..Limits::default()
+                },
+            );
+
+            parse(
+                &mut ctx,
+                "table Settings { key text [primary key] value text /* trailing */ } /* trailing */",
+            )
+            .expect("schema within the limits was rejected");
+        }
+    }
+
+    mod single {
+        use super::*;
+
+        #[test]
+        fn table() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Settings? {
+                    key text [primary key]
+                    value text!
+                }",
+            )
+            .expect("Unable to parse table declaration");
+
+            assert_eq!(
+                Table {
+                    name: "Settings",
+                    doc: None,
+                    not_exists: true,
+                    columns: vec![
+                        Column {
+                            name: "key",
+                            doc: None,
+                            typ: Types::Text,
+                            null: false,
+                            default: ColumnDefault::None,
+                            skip: vec![],
+                            rust_name: None,
+                            collate: None,
+                            searchable: false,
+                            version: false,
+                        },
+                        Column {
+                            name: "value",
+                            doc: None,
+                            typ: Types::Text,
+                            null: true,
+                            default: ColumnDefault::None,
+                            skip: vec![],
+                            rust_name: None,
+                            collate: None,
+                            searchable: false,
+                            version: false,
+                        },
+                    ],
+                    primary_keys: vec!["key"],
+                    foreign_keys: vec![],
+                    unique_keys: vec![],
+                    unique_name: None,
+                    unique_nulls_not_distinct: false,
+                    without_rowid: false,
+                    indexes: vec![],
+                },
+                decl
+            );
+        }
+
+        #[test]
+        fn enum_() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_enum_decl(&mut ctx, "enum State { Working Finished }")
+                .expect("Unable to parse enum declaration");
+
+            assert_eq!(
+                Enum {
+                    name: "State",
+                    not_exists: false,
+                    variants: vec![
+                        Variant {
+                            name: "Working",
+                            doc: None
+                        },
+                        Variant {
+                            name: "Finished",
+                            doc: None
+                        }
+                    ],
+                },
+                decl
+            );
+        }
+
+        #[test]
+        fn money() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(&mut ctx, "table Order { price money refund money! }")
+                .expect("Unable to parse table declaration");
+
+            assert_eq!(
+                vec![(Types::Money, false), (Types::Money, true)],
+                decl.columns
+                    .iter()
+                    .map(|column| (column.typ.clone(), column.null))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn varchar_length() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(&mut ctx, "table User { Name varchar(10) Bio varchar }")
+                .expect("Unable to parse table declaration");
+
+            assert_eq!(
+                vec![Types::Varchar(Some(10)), Types::Varchar(None)],
+                decl.columns
+                    .iter()
+                    .map(|column| column.typ.clone())
+                    .collect::<Vec<_>>()
+            );
+
+            assert!(parse_table_decl(&mut ctx, "table User { Name varchar(0) }").is_err());
+        }
+
+        #[test]
+        fn keyword_prefixed_raw_type() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table User { Age intt Bio texts Name varchar_name }",
+            )
+            .expect("Unable to parse table declaration");
+
+            assert_eq!(
+                vec![
+                    Types::Raw("intt"),
+                    Types::Raw("texts"),
+                    Types::Raw("varchar_name")
+                ],
+                decl.columns
+                    .iter()
+                    .map(|column| column.typ.clone())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn interval() {
+            let mut ctx = Context::new(0);
+
+            let decl =
+                parse_table_decl(&mut ctx, "table Task { Timeout interval Runtime duration }")
+                    .expect("Unable to parse table declaration");
+
+            assert_eq!(
+                vec![Types::Interval, Types::Interval],
+                decl.columns
+                    .iter()
+                    .map(|column| column.typ.clone())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn trailing() {
+            let mut ctx = Context::new(0);
+
+            assert!(parse_enum_decl(&mut ctx, "enum State { Working } enum Other { A }").is_err());
+            assert!(parse_table_decl(&mut ctx, "enum State { Working }").is_err());
+        }
+    }
+
+    mod enums {
+        use super::*;
+
+        const ENUM: &str = "enum Rating {
+            Explicit
+            Mature
+            Teen
+            General
+        }";
+
+        #[test]
+        fn simple() {
+            assert_span(
+                "tests::enums::simple",
+                ENUM,
+                Schema {
+                    database: None,
+                    if_not_exists: false,
+                    items: vec![Item::Enum(Enum {
+                        name: "Rating",
+                        not_exists: false,
+                        variants: vec![
+                            Variant {
+                                name: "Explicit",
+                                doc: None,
+                            },
+                            Variant {
+                                name: "Mature",
+                                doc: None,
+                            },
+                            Variant {
+                                name: "Teen",
+                                doc: None,
+                            },
+                            Variant {
+                                name: "General",
+                                doc: None,
+                            },
+                        ],
+                    })],
+                },
+            );
+        }
+    }
+
+    mod enums_doc {
+        use super::*;
+
+        #[test]
+        fn variants() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_enum_decl(
+                &mut ctx,
+                "enum State {
+                    /* Still being written */
+                    Working
+                    Finished
+                }",
+            )
+            .expect("Unable to parse enum declaration");
+
+            assert_eq!(
+                vec![
+                    Variant {
+                        name: "Working",
+                        doc: Some("Still being written"),
+                    },
+                    Variant {
+                        name: "Finished",
+                        doc: None,
+                    },
+                ],
+                decl.variants
+            );
+        }
+    }
+
+    mod constraint_names {
+        use super::*;
+
+        #[test]
+        fn nulls_not_distinct() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Node {
+                    id text [primary key]
+                    email text! [unique nulls not distinct as uq_node_email]
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert!(decl.unique_nulls_not_distinct);
+            assert_eq!(Some("uq_node_email"), decl.unique_name);
+        }
+
+        #[test]
+        fn named() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Node {
+                    id text [primary key]
+                    name text [unique as uq_node_name]
+                    parent text [ref: Node.id as fk_node_parent (delete: cascade)]
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(Some("uq_node_name"), decl.unique_name);
+            assert_eq!("uq_node_name", decl.unique_constraint_name());
+            assert_eq!(Some("fk_node_parent"), decl.foreign_keys[0].name);
+            assert_eq!(Action::Cascade, decl.foreign_keys[0].delete);
+        }
+
+        #[test]
+        fn generated() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Node {
+                    id text [primary key]
+                    name text [unique]
+                    parent text [ref: Node.id]
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(None, decl.unique_name);
+            assert_eq!("Node_name_key", decl.unique_constraint_name());
+            assert_eq!(None, decl.foreign_keys[0].name);
+            assert_eq!(
+                "Node_parent_fkey",
+                decl.foreign_keys[0].constraint_name(decl.name)
+            );
+        }
+    }
+
+    mod deferrable {
+        use super::*;
+
+        #[test]
+        fn reference() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Node {
+                    id text [primary key]
+                    parent text [ref: Node.id (deferrable)]
+                    root text [ref: Node.id (delete: cascade, deferrable)]
+                    other text [ref: Node.id]
+                }",
+            )
+            .expect("Unable to parse table");
+
+            let deferrable = decl
+                .foreign_keys
+                .iter()
+                .map(|foreign_key| foreign_key.deferrable)
+                .collect::<Vec<_>>();
+
+            assert_eq!(vec![true, true, false], deferrable);
+            assert_eq!(Action::Cascade, decl.foreign_keys[1].delete);
+        }
+    }
+
+    mod foreign_keys {
+        use super::*;
+
+        #[test]
+        fn multi_column() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Comment {
+                    id text [primary key]
+                    storyId text
+                    chapterId text
+                    parent text [ref: Comment.id]
+                    foreign key (storyId, chapterId) references StoryChapter (storyId, chapterId) (delete: cascade)
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(
+                vec![
+                    ForeignKey {
+                        local: vec!["parent"],
+                        table: "Comment",
+                        foreign: vec!["id"],
+                        delete: Action::default(),
+                        update: Action::default(),
+                        deferrable: false,
+                        name: None,
+                    },
+                    ForeignKey {
+                        local: vec!["storyId", "chapterId"],
+                        table: "StoryChapter",
+                        foreign: vec!["storyId", "chapterId"],
+                        delete: Action::Cascade,
+                        update: Action::default(),
+                        deferrable: false,
+                        name: None,
+                    },
+                ],
+                decl.foreign_keys
+            );
+            assert_eq!(
+                "Comment_storyId_chapterId_fkey",
+                decl.foreign_keys[1].constraint_name(decl.name)
+            );
+        }
+
+        #[test]
+        fn named() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Comment {
+                    storyId text
+                    chapterId text
+                    foreign key (storyId, chapterId) references StoryChapter (storyId, chapterId) as fk_comment_chapter
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(Some("fk_comment_chapter"), decl.foreign_keys[0].name);
+            assert_eq!(
+                decl.to_string(),
+                parse_table_decl(&mut ctx, &decl.to_string())
+                    .expect("Unable to parse the written table")
+                    .to_string()
+            );
+        }
+    }
+
+    mod indexes {
+        use super::*;
+
+        #[test]
+        fn partial() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Settings {
+                    key text [primary key]
+                    value text!
+                    index ValueIndex on (value) where value is not null
+                    index BothIndex on (key, value)
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(
+                vec![
+                    Index {
+                        name: "ValueIndex",
+                        not_exists: false,
+                        columns: vec!["value"],
+                        predicate: Some("value is not null"),
+                    },
+                    Index {
+                        name: "BothIndex",
+                        not_exists: false,
+                        columns: vec!["key", "value"],
+                        predicate: None,
+                    },
+                ],
+                decl.indexes
+            );
+        }
+
+        #[test]
+        fn predicate_single_line() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Settings { key text index KeyIndex on (key) where key <> '}' and (key <> ')') }",
+            )
+            .expect("Unable to parse table");
+
+            assert_eq!(
+                Some("key <> '}' and (key <> ')')"),
+                decl.indexes[0].predicate
+            );
+        }
+
+        #[test]
+        fn guarded() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Settings {
+                    key text [primary key]
+                    value text!
+                    index ValueIndex? on (value)
+                    index KeyIndex on (key)
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert!(!decl.not_exists);
+            assert!(decl.indexes[0].not_exists);
+            assert!(!decl.indexes[1].not_exists);
+        }
+
+        #[test]
+        fn guarded_table() {
+            let mut ctx = Context::new(0);
+
+            let decl = parse_table_decl(
+                &mut ctx,
+                "table Settings? {
+                    key text [primary key]
+                    index KeyIndex on (key)
+                }",
+            )
+            .expect("Unable to parse table");
+
+            assert!(decl.indexes[0].not_exists);
+        }
+    }
+
+    mod report {
+        use super::*;
+
+        #[test]
+        fn success() {
+            let schema =
+                parse_and_report("schema.dal", "table Settings { key text [primary key] }")
+                    .expect("Unable to parse schema");
+
+            assert_eq!(1, schema.items.len());
+        }
+
+        #[test]
+        fn rendered_error() {
+            let report = parse_and_report(
+                "schema.dal",
+                "table Settings {
+                    key text [primary key]
+                    value text [default: null]
+                }",
+            )
+            .expect_err("An invalid schema was parsed");
+
+            assert!(report.starts_with("Schema failed validation"), "{}", report);
+            assert!(report.contains("schema.dal:3:21"), "{}", report);
+            assert!(
+                report.contains("`default: null` requires column `value` to be nullable"),
+                "{}",
+                report
+            );
+        }
+
+        const INVALID: &str = "table Settings {
+            key text [primary key]
+            one text [default: null]
+            two text [default: null]
+            three text [default: null]
+        }";
+
+        #[test]
+        fn limited() {
+            let report = parse_and_report_with(
+                "schema.dal",
+                INVALID,
+                ReportOptions {
+                    max_diagnostics: Some(1),
+                    source: true,
+                },
+            )
+            .expect_err("An invalid schema was parsed");
+
+            assert!(report.contains("column `one`"), "{}", report);
+            assert!(!report.contains("column `two`"), "{}", report);
+            assert!(
+                report.contains("... and 2 more diagnostic(s)"),
+                "{}",
+                report
+            );
+        }
+
+        #[test]
+        fn without_source() {
+            let report = parse_and_report_with(
+                "schema.dal",
+                INVALID,
+                ReportOptions {
+                    max_diagnostics: None,
+                    source: false,
+                },
+            )
+            .expect_err("An invalid schema was parsed");
+
+            assert!(report.contains("schema.dal:3:13"), "{}", report);
+            assert!(report.contains("column `three`"), "{}", report);
+            assert!(!report.contains("[default: null]"), "{}", report);
+        }
+    }
+
+    mod database {
+        use super::*;
+
+        fn assert_invalid(input: &str, message: &str) {
+            let mut ctx = Context::new(0);
+
+            match parse(&mut ctx, input) {
+                Err(Error::Invalid) => {}
+                res => panic!("expected an invalid annotation, got {:?}", res),
+            }
+
+            assert_eq!(message, ctx.diagnostics()[0].labels[0].message);
+        }
+
+        #[test]
+        fn annotated() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "/* Only ever used with SQLite */
+                @database sqlite
+
+                table Settings { key text [primary key] }",
+            )
+            .expect("Unable to parse schema");
+
+            assert_eq!(Some("sqlite"), schema.database);
+            assert_eq!(2, schema.items.len());
+        }
+
+        #[test]
+        fn missing() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(&mut ctx, "table Settings { key text [primary key] }")
+                .expect("Unable to parse schema");
+
+            assert_eq!(None, schema.database);
+        }
+
+        #[test]
+        fn unknown() {
+            assert_invalid(
+                "@database oracle
+                table Settings { key text [primary key] }",
+                "expected `mysql`, `postgres`, `postgresql`, or `sqlite`, found `oracle`",
+            );
+        }
+
+        #[test]
+        fn after_declaration() {
+            assert_invalid(
+                "table Settings { key text [primary key] }
+                @database sqlite",
+                "`@database` has to come before every declaration",
+            );
+        }
+
+        #[test]
+        fn duplicate() {
+            assert_invalid(
+                "@database sqlite
+                @database postgres
+                table Settings { key text [primary key] }",
+                "`@database` is given more than once",
+            );
+        }
+    }
+
+    mod if_not_exists {
+        use super::*;
+
+        fn assert_invalid(input: &str, message: &str) {
+            let mut ctx = Context::new(0);
+
+            match parse(&mut ctx, input) {
+                Err(Error::Invalid) => {}
+                res => panic!("expected an invalid annotation, got {:?}", res),
+            }
+
+            assert_eq!(message, ctx.diagnostics()[0].labels[0].message);
+        }
+
+        #[test]
+        fn annotated() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "@database postgres
+                @if_not_exists
+
+                enum State { Working Finished }
+
+                table Settings {
+                    key text [primary key]
+                    state State
+                    index StateIndex on (state)
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            assert!(schema.if_not_exists);
+
+            match &schema.items[..] {
+                [Item::Enum(state), Item::Table(settings)] => {
+                    assert!(state.not_exists);
+                    assert!(settings.not_exists);
+                    assert!(settings.indexes[0].not_exists);
+                }
+                items => panic!("expected an enum and a table, got {:?}", items),
+            }
+        }
+
+        #[test]
+        fn missing() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(&mut ctx, "table Settings { key text [primary key] }")
+                .expect("Unable to parse schema");
+
+            assert!(!schema.if_not_exists);
+            assert!(!schema.items.iter().any(|item| match item {
+                Item::Table(decl) => decl.not_exists,
+                _ => false,
+            }));
+        }
+
+        #[test]
+        fn after_declaration() {
+            assert_invalid(
+                "table Settings { key text [primary key] }
+                @if_not_exists",
+                "`@if_not_exists` has to come before every declaration",
+            );
+        }
+
+        #[test]
+        fn duplicate() {
+            assert_invalid(
+                "@if_not_exists
+                @if_not_exists
+                table Settings { key text [primary key] }",
+                "`@if_not_exists` is given more than once",
+            );
+        }
+    }
+
+    mod identifiers {
+        use super::*;
+
+        #[test]
+        fn underscores() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "enum account_state {
+                    not_verified
+                }
+
+                table user_accounts {
+                    user_id serial [primary key]
+                    account_state account_state
+                    parent_id int! [ref: user_accounts.user_id]
+                    index by_parent on (parent_id)
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            match &schema.items[..] {
+                [Item::Enum(decl), Item::Table(table)] => {
+                    assert_eq!("account_state", decl.name);
+                    assert_eq!("not_verified", decl.variants[0].name);
+
+                    assert_eq!("user_accounts", table.name);
+                    assert_eq!(
+                        vec!["user_id", "account_state", "parent_id"],
+                        table.columns.iter().map(|c| c.name).collect::<Vec<_>>()
+                    );
+                    assert_eq!(Types::Raw("account_state"), table.columns[1].typ);
+                    assert_eq!("user_accounts", table.foreign_keys[0].table);
+                    assert_eq!(vec!["user_id"], table.foreign_keys[0].foreign);
+                    assert_eq!(vec!["parent_id"], table.indexes[0].columns);
+                }
+                items => panic!("expected an enum and a table, got {:?}", items),
+            }
+        }
+
+        #[test]
+        fn other_punctuation() {
+            let mut ctx = Context::new(0);
+
+            assert!(parse(&mut ctx, "table user-accounts { id text [primary key] }").is_err());
+        }
+    }
+
+    mod timestamps {
+        use super::*;
+
+        fn assert_invalid(input: &str, message: &str) {
+            let mut ctx = Context::new(0);
+
+            match parse(&mut ctx, input) {
+                Err(Error::Invalid) => {}
+                res => panic!("expected an invalid annotation, got {:?}", res),
+            }
+
+            assert_eq!(message, ctx.diagnostics()[0].labels[0].message);
+        }
+
+        fn columns<'s>(schema: &'s Schema<'s>) -> Vec<Vec<&'s str>> {
+            schema
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    Item::Table(decl) => Some(decl.columns.iter().map(|c| c.name).collect()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        #[test]
+        fn added() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "@timestamps
+
+                table Story {
+                    Id text [primary key]
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            assert_eq!(vec![vec!["Id", "Created", "Updated"]], columns(&schema));
+
+            match &schema.items[..] {
+                [Item::Table(decl)] => {
+                    assert_eq!(Types::DateTime, decl.columns[1].typ);
+                    assert_eq!(ColumnDefault::Now, decl.columns[1].default);
+                    assert!(!decl.columns[1].null);
+                }
+                items => panic!("expected a table, got {:?}", items),
+            }
+        }
+
+        #[test]
+        fn named() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "@timestamps(created = \"created_at\", updated = \"updated_at\")
+
+                table story {
+                    id text [primary key]
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            assert_eq!(
+                vec![vec!["id", "created_at", "updated_at"]],
+                columns(&schema)
+            );
+        }
+
+        #[test]
+        fn opted_out() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "@timestamps(updated = \"Modified\")
+
+                table Story {
+                    Id text [primary key]
+                    Created dateTime!
+                }
+
+                table Tag {
+                    Name text [primary key]
+                } [no timestamps]",
+            )
+            .expect("Unable to parse schema");
+
+            assert_eq!(
+                vec![vec!["Id", "Created", "Modified"], vec!["Name"]],
+                columns(&schema)
+            );
+
+            // A declared column is kept as it is
+            match &schema.items[0] {
+                Item::Table(decl) => assert!(decl.columns[1].null),
+                item => panic!("expected a table, got {:?}", item),
+            }
+        }
+
+        #[test]
+        fn written_out() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "@timestamps(created = \"created_at\")
+
+                table Story {
+                    Id text [primary key]
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            // The columns are written out, so parsing the schema again does not add them twice
+            let written = schema.to_string();
+
+            let mut ctx = Context::new(0);
+
+            assert_eq!(
+                schema,
+                parse(&mut ctx, &written).expect("Unable to parse written schema")
+            );
+        }
+
+        #[test]
+        fn after_declaration() {
+            assert_invalid(
+                "table Settings { key text [primary key] }
+                @timestamps",
+                "`@timestamps` has to come before every declaration",
+            );
+        }
+
+        #[test]
+        fn duplicate() {
+            assert_invalid(
+                "@timestamps
+                @timestamps
+                table Settings { key text [primary key] }",
+                "`@timestamps` is given more than once",
+            );
+
+            assert_invalid(
+                "@timestamps(created = \"A\", created = \"B\")
+                table Settings { key text [primary key] }",
+                "`created` is given more than once",
+            );
+
+            assert_invalid(
+                "@timestamps(created = \"At\", updated = \"At\")
+                table Settings { key text [primary key] }",
+                "`created` and `updated` are both named `At`",
+            );
+        }
+    }
+
+    mod streaming {
+        use super::*;
+
+        const SCHEMA: &str = "/* Written by hand */
+
+            /* The state of a story */
+            enum State { Working }
+
+            /* Unused for now */
+            /* Application settings */
+            table Settings { key text [primary key] }
+
+            table Other { id text [primary key] }
+
+            /* The end */";
+
+        #[test]
+        fn same_as_parse() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(&mut ctx, SCHEMA).expect("Unable to parse schema");
+
+            let mut ctx = Context::new(0);
+
+            let items = parse_items(&mut ctx, SCHEMA)
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Unable to parse schema items");
+
+            assert_eq!(schema.items, items);
+        }
+
+        #[test]
+        fn continues_after_invalid() {
+            let mut ctx = Context::new(0);
+
+            let items = parse_items(
+                &mut ctx,
+                "table Settings { key text [default: null] }
+                table Other { id text [primary key] }",
+            )
+            .map(|item| match item {
+                Ok(Item::Table(decl)) => Ok(decl.name),
+                Ok(_) => panic!("expected a table"),
+                Err(err) => Err(err.to_string()),
+            })
+            .collect::<Vec<_>>();
+
+            assert_eq!(2, items.len());
+            assert!(items[0].is_err());
+            assert_eq!(Ok("Other"), items[1]);
+            assert_eq!(1, ctx.diagnostics().len());
+        }
+
+        #[test]
+        fn syntax_error() {
+            let mut ctx = Context::new(0);
+
+            let mut items = parse_items(&mut ctx, "table {");
+
+            assert!(matches!(items.next(), Some(Err(Error::Parse(_)))));
+            assert!(items.next().is_none());
+        }
+    }
+
+    mod mixins {
+        use super::*;
+
+        fn column_names<'i>(schema: &Schema<'i>) -> Vec<&'i str> {
+            match schema.items.as_slice() {
+                [Item::Table(decl)] => decl.columns.iter().map(|c| c.name).collect(),
+                items => panic!("expected a single table, found {:?}", items),
+            }
+        }
+
+        #[test]
+        fn expanded() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "mixin Timestamps {
+                    created dateTime [default: now()]
+                    updated dateTime [default: now()]
                 }
+
+                table Settings includes Timestamps {
+                    key text [primary key]
+                    value text
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            assert_eq!(
+                vec!["key", "value", "created", "updated"],
+                column_names(&schema)
+            );
+        }
+
+        #[test]
+        fn multiple() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "mixin Id { id uuid [primary key] }
+                mixin Timestamps { created dateTime [default: now()] }
+
+                table Settings? includes Id, Timestamps {
+                    value text
+                }",
+            )
+            .expect("Unable to parse schema");
+
+            assert_eq!(vec!["value", "id", "created"], column_names(&schema));
+
+            match schema.items.as_slice() {
+                [Item::Table(decl)] => assert_eq!(vec!["id"], decl.primary_keys),
+                items => panic!("expected a single table, found {:?}", items),
             }
-            Rule::ref_action_update => {
-                let mut inner: Pairs<'i, Rule> = pair.into_inner();
+        }
 
-                match inner.next() {
-                    Some(pair) if pair.as_rule() == Rule::action => {
-                        (Rule::ref_action_update, pair.as_str())
-                    }
-                    Some(pair) => {
-                        ctx.diags.push(
-                            Diagnostic::error()
-                                .with_message("Unexpected token")
-                                .with_labels(vec![Label::primary(
-                                    ctx.file_id,
-                                    span_range_single(&pair),
-                                )
-                                .with_message(format!(
-                                    "expected `action`, found `{:?}`",
-                                    pair.as_rule()
-                                ))]),
-                        );
+        #[test]
+        fn unknown() {
+            let mut ctx = Context::new(0);
 
-                        return Err(Error::UnexpectedPair(pair.as_span().into()));
-                    }
-                    None => {
-                        ctx.diags.push(
-                            Diagnostic::error()
-                                .with_message("Unexpected end of stream")
-                                .with_labels(vec![Label::primary(
-                                    ctx.file_id,
-                                    span_range_end(inner_span),
-                                )
-                                .with_message("here")]),
-                        );
+            let res = parse(
+                &mut ctx,
+                "table Settings includes Timestamps {
+                    key text [primary key]
+                }
 
-                        return Err(Error::UnexpectedEOS);
-                    }
+                mixin Timestamps { created dateTime }",
+            );
+
+            assert!(matches!(res, Err(Error::Invalid)));
+            assert_eq!(
+                "mixin `Timestamps` is not declared before table `Settings`",
+                ctx.diagnostics()[0].labels[0].message
+            );
+        }
+
+        #[test]
+        fn collision() {
+            let mut ctx = Context::new(0);
+
+            let res = parse(
+                &mut ctx,
+                "mixin Timestamps { created dateTime }
+
+                table Settings includes Timestamps {
+                    key text [primary key]
+                    created dateTime
+                }",
+            );
+
+            assert!(matches!(res, Err(Error::Invalid)));
+            assert_eq!(
+                "column `created` from mixin `Timestamps` is already in table `Settings`",
+                ctx.diagnostics()[0].labels[0].message
+            );
+        }
+    }
+
+    mod enum_order {
+        use super::*;
+
+        #[test]
+        fn moved_before_table() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "table Story {
+                    id text [primary key]
+                    state State
+                    rating Rating
                 }
-            }
-            _ => {
-                ctx.diags.push(
-                    Diagnostic::error()
-                        .with_message("Unexpected token")
-                        .with_labels(vec![Label::primary(ctx.file_id, span_range_single(&pair))
-                        .with_message(format!("expected `modifier reference action delete`, or `modifier reference action update`, found `{:?}`", pair.as_rule()))]),
-                );
 
-                return Err(Error::UnexpectedPair(pair.as_span().into()));
-            }
-        };
+                enum Rating { Safe }
 
-        match rule {
-            Rule::ref_action_delete => delete = Action::try_from(action)?,
-            Rule::ref_action_update => update = Action::try_from(action)?,
-            _ => unreachable!(),
+                table Other { id text [primary key] }
+
+                /* The state of a story */
+                enum State { Working }",
+            )
+            .expect("Unable to parse schema");
+
+            let items = schema
+                .items
+                .iter()
+                .map(|item| match item {
+                    Item::Comment(comment) => format!("comment: {}", comment),
+                    Item::Enum(decl) => format!("enum: {}", decl.name),
+                    Item::Table(decl) => format!("table: {}", decl.name),
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                vec![
+                    "enum: Rating",
+                    "comment: The state of a story",
+                    "enum: State",
+                    "table: Story",
+                    "table: Other",
+                ],
+                items
+            );
         }
     }
 
-    Ok((delete, update))
-}
+    mod comments {
+        use super::*;
 
-#[cfg(test)]
-mod tests {
-    pub use {
-        super::*,
-        crate::models::{Column, Item, Table},
-        codespan_reporting::{
-            files::SimpleFiles,
-            term::{
-                self,
-                termcolor::{Buffer, ColorChoice, StandardStream, WriteColor},
-                Config,
-            },
-        },
-    };
+        #[test]
+        fn kept() {
+            let mut ctx = Context::new(0);
 
-    fn assert_span(name: &str, input: &str, out: Schema) {
-        let mut files = SimpleFiles::new();
+            let schema = parse(
+                &mut ctx,
+                "/* Written by hand */
 
-        let file_id = files.add(name, input);
+                /* The state of a story */
+                enum State { Working }
 
-        let mut ctx = Context::new(file_id);
+                /* Unused for now */
+                /* Application settings */
+                table Settings { key text [primary key] }
 
-        match parse(&mut ctx, input) {
-            Ok(schema) => assert_eq!(out, schema,),
-            Err(err) => {
-                let mut writer = Buffer::no_color();
-                let config = Config::default();
+                /* The end */",
+            )
+            .expect("Unable to parse schema");
 
-                for diag in ctx.diags {
-                    term::emit(&mut writer, &config, &files, &diag).unwrap();
+            let items = schema
+                .items
+                .iter()
+                .map(|item| match item {
+                    Item::Comment(comment) => format!("comment: {}", comment),
+                    Item::Enum(decl) => format!("enum: {}", decl.name),
+                    Item::Table(decl) => format!("table: {} ({:?})", decl.name, decl.doc),
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                vec![
+                    "comment: Written by hand",
+                    "comment: The state of a story",
+                    "enum: State",
+                    "comment: Unused for now",
+                    "table: Settings (Some(\"Application settings\"))",
+                    "comment: The end",
+                ],
+                items
+            );
+        }
+
+        #[test]
+        fn interspersed() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "@database sqlite
+                /* After the annotation */
+
+                enum State {
+                    /* Still going */
+                    Working
+                    Finished
+                    /* Nothing after this */
                 }
+                /* Between declarations */
+                table Story {
+                    Id text [primary key]
+                    /* Nothing after this either */
+                }
+                /* At the end */",
+            )
+            .expect("Unable to parse schema");
 
-                panic!(
-                    "{}{:?}",
-                    String::from_utf8_lossy(writer.as_slice()).into_owned(),
-                    err
-                );
-            }
+            assert_eq!(
+                vec![Some("Still going"), None],
+                schema
+                    .items
+                    .iter()
+                    .find_map(|item| match item {
+                        Item::Enum(decl) =>
+                            Some(decl.variants.iter().map(|v| v.doc).collect::<Vec<_>>()),
+                        _ => None,
+                    })
+                    .expect("State is missing")
+            );
+
+            // A comment right before a declaration is its documentation rather than an item
+            assert_eq!(
+                vec!["After the annotation", "At the end"],
+                schema
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        Item::Comment(comment) => Some(*comment),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            );
+
+            assert_eq!(
+                Some("Between declarations"),
+                schema.items.iter().find_map(|item| match item {
+                    Item::Table(decl) => decl.doc,
+                    _ => None,
+                })
+            );
+
+            // A trailing comment in a table has no column after it to document
+            assert_eq!(
+                vec![None],
+                schema
+                    .items
+                    .iter()
+                    .find_map(|item| match item {
+                        Item::Table(decl) =>
+                            Some(decl.columns.iter().map(|c| c.doc).collect::<Vec<_>>()),
+                        _ => None,
+                    })
+                    .expect("Story is missing")
+            );
         }
     }
 
-    mod enums {
+    mod trailing_commas {
         use super::*;
 
-        const ENUM: &str = "enum Rating {
-            Explicit
-            Mature
-            Teen
-            General
-        }";
-
         #[test]
-        fn simple() {
-            assert_span(
-                "tests::enums::simple",
-                ENUM,
-                Schema {
-                    items: vec![Item::Enum(Enum {
-                        name: "Rating",
-                        not_exists: false,
-                        variants: vec!["Explicit", "Mature", "Teen", "General"],
-                    })],
-                },
+        fn allowed() {
+            let mut ctx = Context::new(0);
+
+            let schema = parse(
+                &mut ctx,
+                "enum State { Working, Finished, }
+
+                mixin Timestamps {
+                    Created dateTime [default: now(),]
+                }
+
+                table Story includes Timestamps, {
+                    Id text [primary key, unique,]
+                    State State
+                }
+
+                table Chapter {
+                    Id text [primary key]
+                    StoryId text [ref: Story.Id (delete: cascade, deferrable,)]
+                    Hidden text [skip: rust, graphql,]
+
+                    foreign key (StoryId,) references Story (Id,) (update: cascade,)
+                    index ChapterStory on (StoryId, Id,)
+                } [without rowid,]",
+            )
+            .expect("Unable to parse schema");
+
+            let story = schema
+                .items
+                .iter()
+                .find_map(|item| match item {
+                    Item::Table(decl) if decl.name == "Story" => Some(decl),
+                    _ => None,
+                })
+                .expect("Story is missing");
+
+            assert_eq!(vec!["Id"], story.primary_keys);
+            assert_eq!(vec!["Id"], story.unique_keys);
+            assert_eq!(
+                vec!["Id", "State", "Created"],
+                story.columns.iter().map(|c| c.name).collect::<Vec<_>>()
+            );
+
+            let chapter = schema
+                .items
+                .iter()
+                .find_map(|item| match item {
+                    Item::Table(decl) if decl.name == "Chapter" => Some(decl),
+                    _ => None,
+                })
+                .expect("Chapter is missing");
+
+            assert!(chapter.without_rowid);
+            assert_eq!(vec!["StoryId", "Id"], chapter.indexes[0].columns);
+            assert_eq!(vec!["rust", "graphql"], chapter.columns[2].skip);
+            assert_eq!(
+                vec![(Action::Cascade, true), (Action::NoAction, false)],
+                chapter
+                    .foreign_keys
+                    .iter()
+                    .map(|key| (key.delete.clone(), key.deferrable))
+                    .collect::<Vec<_>>()
+            );
+
+            let state = schema
+                .items
+                .iter()
+                .find_map(|item| match item {
+                    Item::Enum(decl) => Some(decl),
+                    _ => None,
+                })
+                .expect("State is missing");
+
+            assert_eq!(
+                vec!["Working", "Finished"],
+                state.variants.iter().map(|v| v.name).collect::<Vec<_>>()
             );
         }
+
+        #[test]
+        fn only_one() {
+            let mut ctx = Context::new(0);
+
+            assert!(parse_table_decl(&mut ctx, "table Story { Id text [primary key,,] }").is_err());
+            assert!(parse_table_decl(&mut ctx, "table Story { Id text [,] }").is_err());
+            assert!(parse_enum_decl(&mut ctx, "enum State { , }").is_err());
+        }
     }
 
     mod tables {
@@ -781,6 +3718,41 @@ mod tests {
             updated dateTime [default: now()]
         }";
 
+        const TABLE_UUID: &str = "table Settings {
+            key text [primary key]
+            value uuid
+            created dateTime [default: now()]
+            updated dateTime [default: now()]
+        }";
+
+        const TABLE_SMALL_SERIAL: &str = "table Settings {
+            key text [primary key]
+            value smallSerial
+            created dateTime [default: now()]
+            updated dateTime [default: now()]
+        }";
+
+        const TABLE_BIG_SERIAL: &str = "table Settings {
+            key text [primary key]
+            value bigSerial
+            created dateTime [default: now()]
+            updated dateTime [default: now()]
+        }";
+
+        const TABLE_ARRAY: &str = "table Settings {
+            key text [primary key]
+            value text[]
+            created dateTime [default: now()]
+            updated dateTime [default: now()]
+        }";
+
+        const TABLE_ARRAY_NESTED: &str = "table Settings {
+            key text [primary key]
+            value int[][]! [unique]
+            created dateTime [default: now()]
+            updated dateTime [default: now()]
+        }";
+
         const TABLE_REFERENCE: &str = "table Settings {
             key text [primary key]
             otherOne text [ref: Other.id (delete: cascade, update: cascade)]
@@ -790,36 +3762,70 @@ mod tests {
             updated dateTime [default: now()]
         }";
 
+        const TABLE_DOC: &str = "/* Application settings */
+        table Settings {
+            key text [primary key]
+            /* The setting's value, it's always text */
+            value text
+            created dateTime [default: now()]
+            updated dateTime [default: now()]
+        }";
+
         #[inline]
         fn def_table(column: Column) -> Schema {
             Schema {
+                database: None,
+                if_not_exists: false,
                 items: vec![Item::Table(Table {
                     name: "Settings",
+                    doc: None,
                     not_exists: false,
                     columns: vec![
                         Column {
                             name: "key",
+                            doc: None,
                             typ: Types::Text,
                             null: false,
                             default: ColumnDefault::default(),
+                            skip: vec![],
+                            rust_name: None,
+                            collate: None,
+                            searchable: false,
+                            version: false,
                         },
                         column,
                         Column {
                             name: "created",
+                            doc: None,
                             typ: Types::DateTime,
                             null: false,
                             default: ColumnDefault::Now,
+                            skip: vec![],
+                            rust_name: None,
+                            collate: None,
+                            searchable: false,
+                            version: false,
                         },
                         Column {
                             name: "updated",
+                            doc: None,
                             typ: Types::DateTime,
                             null: false,
                             default: ColumnDefault::Now,
+                            skip: vec![],
+                            rust_name: None,
+                            collate: None,
+                            searchable: false,
+                            version: false,
                         },
                     ],
                     primary_keys: vec!["key"],
                     foreign_keys: vec![],
                     unique_keys: vec![],
+                    unique_name: None,
+                    unique_nulls_not_distinct: false,
+                    without_rowid: false,
+                    indexes: vec![],
                 })],
             }
         }
@@ -831,9 +3837,15 @@ mod tests {
                 TABLE,
                 def_table(Column {
                     name: "value",
+                    doc: None,
                     typ: Types::Text,
                     null: false,
                     default: ColumnDefault::default(),
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 }),
             );
         }
@@ -845,85 +3857,264 @@ mod tests {
                 TABLE_NULL,
                 def_table(Column {
                     name: "value",
+                    doc: None,
                     typ: Types::Text,
                     null: true,
                     default: ColumnDefault::default(),
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                }),
+            );
+        }
+
+        #[test]
+        fn doc() {
+            let mut expected = def_table(Column {
+                name: "value",
+                doc: Some("The setting's value, it's always text"),
+                typ: Types::Text,
+                null: false,
+                default: ColumnDefault::default(),
+                skip: vec![],
+                rust_name: None,
+                collate: None,
+                searchable: false,
+                version: false,
+            });
+
+            if let Item::Table(table) = &mut expected.items[0] {
+                table.doc = Some("Application settings");
+            }
+
+            assert_span("tests::tables::doc", TABLE_DOC, expected);
+        }
+
+        #[test]
+        fn uuid() {
+            assert_span(
+                "tests::tables::uuid",
+                TABLE_UUID,
+                def_table(Column {
+                    name: "value",
+                    doc: None,
+                    typ: Types::Uuid,
+                    null: false,
+                    default: ColumnDefault::default(),
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                }),
+            );
+        }
+
+        #[test]
+        fn small_serial() {
+            assert_span(
+                "tests::tables::small_serial",
+                TABLE_SMALL_SERIAL,
+                def_table(Column {
+                    name: "value",
+                    doc: None,
+                    typ: Types::SmallSerial,
+                    null: false,
+                    default: ColumnDefault::default(),
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                }),
+            );
+        }
+
+        #[test]
+        fn big_serial() {
+            assert_span(
+                "tests::tables::big_serial",
+                TABLE_BIG_SERIAL,
+                def_table(Column {
+                    name: "value",
+                    doc: None,
+                    typ: Types::BigSerial,
+                    null: false,
+                    default: ColumnDefault::default(),
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
                 }),
             );
         }
 
+        #[test]
+        fn array() {
+            assert_span(
+                "tests::tables::array",
+                TABLE_ARRAY,
+                def_table(Column {
+                    name: "value",
+                    doc: None,
+                    typ: Types::Array(Box::new(Types::Text)),
+                    null: false,
+                    default: ColumnDefault::default(),
+                    skip: vec![],
+                    rust_name: None,
+                    collate: None,
+                    searchable: false,
+                    version: false,
+                }),
+            );
+        }
+
+        #[test]
+        fn array_nested() {
+            let mut expected = def_table(Column {
+                name: "value",
+                doc: None,
+                typ: Types::Array(Box::new(Types::Array(Box::new(Types::Int)))),
+                null: true,
+                default: ColumnDefault::default(),
+                skip: vec![],
+                rust_name: None,
+                collate: None,
+                searchable: false,
+                version: false,
+            });
+
+            if let Item::Table(table) = &mut expected.items[0] {
+                table.unique_keys = vec!["value"];
+            }
+
+            assert_span("tests::tables::array_nested", TABLE_ARRAY_NESTED, expected);
+        }
+
         #[test]
         fn reference() {
             assert_span(
                 "tests::tables::reference",
                 TABLE_REFERENCE,
                 Schema {
+                    database: None,
+                    if_not_exists: false,
                     items: vec![Item::Table(Table {
                         name: "Settings",
+                        doc: None,
                         not_exists: false,
                         columns: vec![
                             Column {
                                 name: "key",
+                                doc: None,
                                 typ: Types::Text,
                                 null: false,
                                 default: ColumnDefault::default(),
+                                skip: vec![],
+                                rust_name: None,
+                                collate: None,
+                                searchable: false,
+                                version: false,
                             },
                             Column {
                                 name: "otherOne",
+                                doc: None,
                                 typ: Types::Text,
                                 null: false,
                                 default: ColumnDefault::default(),
+                                skip: vec![],
+                                rust_name: None,
+                                collate: None,
+                                searchable: false,
+                                version: false,
                             },
                             Column {
                                 name: "otherTwo",
+                                doc: None,
                                 typ: Types::Text,
                                 null: false,
                                 default: ColumnDefault::default(),
+                                skip: vec![],
+                                rust_name: None,
+                                collate: None,
+                                searchable: false,
+                                version: false,
                             },
                             Column {
                                 name: "otherThree",
+                                doc: None,
                                 typ: Types::Text,
                                 null: false,
                                 default: ColumnDefault::default(),
+                                skip: vec![],
+                                rust_name: None,
+                                collate: None,
+                                searchable: false,
+                                version: false,
                             },
                             Column {
                                 name: "created",
+                                doc: None,
                                 typ: Types::DateTime,
                                 null: false,
                                 default: ColumnDefault::Now,
+                                skip: vec![],
+                                rust_name: None,
+                                collate: None,
+                                searchable: false,
+                                version: false,
                             },
                             Column {
                                 name: "updated",
+                                doc: None,
                                 typ: Types::DateTime,
                                 null: false,
                                 default: ColumnDefault::Now,
+                                skip: vec![],
+                                rust_name: None,
+                                collate: None,
+                                searchable: false,
+                                version: false,
                             },
                         ],
                         primary_keys: vec!["key"],
                         foreign_keys: vec![
                             ForeignKey {
-                                local: "otherOne",
+                                local: vec!["otherOne"],
                                 table: "Other",
-                                foreign: "id",
+                                foreign: vec!["id"],
                                 delete: Action::Cascade,
                                 update: Action::Cascade,
+                                deferrable: false,
+                                name: None,
                             },
                             ForeignKey {
-                                local: "otherTwo",
+                                local: vec!["otherTwo"],
                                 table: "Other",
-                                foreign: "id",
+                                foreign: vec!["id"],
                                 delete: Action::Cascade,
                                 update: Action::default(),
+                                deferrable: false,
+                                name: None,
                             },
                             ForeignKey {
-                                local: "otherThree",
+                                local: vec!["otherThree"],
                                 table: "Other",
-                                foreign: "id",
+                                foreign: vec!["id"],
                                 delete: Action::default(),
                                 update: Action::Cascade,
+                                deferrable: false,
+                                name: None,
                             },
                         ],
                         unique_keys: vec![],
+                        unique_name: None,
+                        unique_nulls_not_distinct: false,
+                        without_rowid: false,
+                        indexes: vec![],
                     })],
                 },
             );
@@ -0,0 +1,374 @@
+//! [`proptest`] strategies that generate random valid schemas, for fuzzing code that works with them.
+//!
+//! Names, documentation, and raw values are picked from small fixed lists, so every generated schema parses back
+//! to itself and passes validation.
+
+use {
+    crate::{
+        models::{
+            Action, Column, ColumnDefault, Enum, ForeignKey, Index, Item, Schema, Table, Types,
+            Variant,
+        },
+        validate::is_version_type,
+    },
+    proptest::{
+        prelude::*,
+        sample::{select, subsequence},
+    },
+};
+
+const ENUM_NAMES: &[&str] = &["State", "Rating", "Kind"];
+const VARIANT_NAMES: &[&str] = &["Working", "Finished", "Abandoned", "Hidden"];
+const TABLE_NAMES: &[&str] = &["Story", "Chapter", "Author", "Tag", "Review"];
+const COLUMN_NAMES: &[&str] = &[
+    "Id", "Name", "Title", "Body", "Count", "Score", "Created", "Updated",
+];
+const INDEX_NAMES: &[&str] = &["ByName", "ByCreated"];
+const CONSTRAINT_NAMES: &[&str] = &["uq_name", "fk_parent", "custom_1"];
+const RUST_NAMES: &[&str] = &["kind", "value", "r_type"];
+const COLLATIONS: &[&str] = &["nocase", "\"en_US\"", "C"];
+const SKIP_TARGETS: &[&str] = &["graphql", "rust"];
+const DOCS: &[&str] = &["The primary key", "Shown to users", "Kept for old clients"];
+const RAW_DEFAULTS: &[&str] = &["'hello'", "42", "-1", "(1 + 2)", "'a, b'"];
+const PREDICATES: &[&str] = &["Name is not null", "Count > 0"];
+const DATABASES: &[&str] = &["mysql", "postgresql", "sqlite"];
+
+const BUILT_IN_TYPES: &[Types<'static>] = &[
+    Types::Boolean,
+    Types::Char,
+    Types::Varchar(None),
+    Types::Varchar(Some(32)),
+    Types::Text,
+    Types::Number,
+    Types::SmallInt,
+    Types::MediumInt,
+    Types::BigInt,
+    Types::Int,
+    Types::Serial,
+    Types::SmallSerial,
+    Types::BigSerial,
+    Types::Float,
+    Types::Real,
+    Types::Double,
+    Types::Numeric,
+    Types::Decimal,
+    Types::Money,
+    Types::DateTime,
+    Types::Date,
+    Types::Time,
+    Types::Interval,
+    Types::Uuid,
+];
+
+/// A schema of enums followed by the tables that use them.
+pub fn schema() -> impl Strategy<Value = Schema<'static>> {
+    subsequence(ENUM_NAMES, 0..=ENUM_NAMES.len())
+        .prop_flat_map(|enum_names| {
+            let enums = enum_names
+                .iter()
+                .map(|name| enum_decl(name))
+                .collect::<Vec<_>>();
+
+            let tables = subsequence(TABLE_NAMES, 1..=3).prop_flat_map(move |names| {
+                names
+                    .into_iter()
+                    .map(|name| table(name, enum_names.clone()))
+                    .collect::<Vec<_>>()
+            });
+
+            (
+                prop::option::of(select(DATABASES)),
+                any::<bool>(),
+                enums,
+                tables,
+            )
+        })
+        .prop_map(|(database, if_not_exists, mut enums, mut tables)| {
+            // The parser sets every declaration's flag when the schema has `@if_not_exists`
+            if if_not_exists {
+                for decl in &mut enums {
+                    decl.not_exists = true;
+                }
+
+                for decl in &mut tables {
+                    decl.not_exists = true;
+
+                    for index in &mut decl.indexes {
+                        index.not_exists = true;
+                    }
+                }
+            }
+
+            Schema {
+                database,
+                if_not_exists,
+                items: enums
+                    .into_iter()
+                    .map(Item::Enum)
+                    .chain(tables.into_iter().map(Item::Table))
+                    .collect(),
+            }
+        })
+}
+
+/// An enum with at least one variant.
+pub fn enum_decl(name: &'static str) -> impl Strategy<Value = Enum<'static>> {
+    (
+        any::<bool>(),
+        subsequence(VARIANT_NAMES, 1..=VARIANT_NAMES.len()).prop_flat_map(|names| {
+            names
+                .into_iter()
+                .map(|name| {
+                    prop::option::of(select(DOCS)).prop_map(move |doc| Variant { name, doc })
+                })
+                .collect::<Vec<_>>()
+        }),
+    )
+        .prop_map(move |(not_exists, variants)| Enum {
+            name,
+            not_exists,
+            variants,
+        })
+}
+
+/// A table with at least one column, its columns may use any of the given enums.
+pub fn table(
+    name: &'static str,
+    enums: Vec<&'static str>,
+) -> impl Strategy<Value = Table<'static>> {
+    subsequence(COLUMN_NAMES, 1..=6)
+        .prop_flat_map(move |names| {
+            let columns = names
+                .iter()
+                .map(|name| column(name, enums.clone()))
+                .collect::<Vec<_>>();
+
+            (Just(names), columns)
+        })
+        .prop_flat_map(|(names, columns)| {
+            let len = names.len();
+
+            let foreign_keys = columns
+                .iter()
+                .map(|column| prop::option::weighted(0.3, foreign_key(column.clone())))
+                .collect::<Vec<_>>();
+
+            (
+                Just(columns),
+                prop::option::of(select(DOCS)),
+                any::<bool>(),
+                subsequence(names.clone(), 0..=len),
+                subsequence(names.clone(), 0..=len),
+                prop::option::of(select(CONSTRAINT_NAMES)),
+                foreign_keys,
+                any::<bool>(),
+                indexes(names),
+                any::<bool>(),
+            )
+        })
+        .prop_map(
+            move |(
+                mut columns,
+                doc,
+                not_exists,
+                primary_keys,
+                unique_keys,
+                unique_name,
+                foreign_keys,
+                without_rowid,
+                mut indexes,
+                unique_nulls_not_distinct,
+            )| {
+                // A guarded table's indexes are always guarded
+                for index in &mut indexes {
+                    index.not_exists |= not_exists;
+                }
+
+                // Only the first version column is kept, a table can have one
+                let mut versioned = false;
+
+                for column in &mut columns {
+                    column.version &= !versioned;
+                    versioned |= column.version;
+                }
+
+                // Primary keys can not be nullable
+                let primary_keys = primary_keys
+                    .into_iter()
+                    .filter(|name| columns.iter().any(|c| c.name == *name && !c.null))
+                    .collect::<Vec<_>>();
+
+                Table {
+                    name,
+                    doc,
+                    not_exists,
+                    columns,
+                    unique_name: if unique_keys.is_empty() {
+                        None
+                    } else {
+                        unique_name
+                    },
+                    unique_nulls_not_distinct: unique_nulls_not_distinct && !unique_keys.is_empty(),
+                    without_rowid: without_rowid && !primary_keys.is_empty(),
+                    primary_keys,
+                    foreign_keys: foreign_keys.into_iter().flatten().collect(),
+                    unique_keys,
+                    indexes,
+                }
+            },
+        )
+}
+
+/// A column of a built-in type, an array, or one of the given enums.
+pub fn column(
+    name: &'static str,
+    enums: Vec<&'static str>,
+) -> impl Strategy<Value = Column<'static>> {
+    (
+        types(enums),
+        any::<bool>(),
+        prop::option::of(select(DOCS)),
+        column_default(),
+        subsequence(SKIP_TARGETS, 0..=SKIP_TARGETS.len()),
+        prop::option::of(select(RUST_NAMES)),
+        prop::option::of(select(COLLATIONS)),
+        any::<bool>(),
+        prop::bool::weighted(0.1),
+    )
+        .prop_map(
+            move |(typ, null, doc, default, skip, rust_name, collate, searchable, version)| {
+                Column {
+                    name,
+                    doc,
+                    // Only text can be searched
+                    searchable: searchable
+                        && matches!(typ, Types::Char | Types::Varchar(_) | Types::Text),
+                    version: version && !null && is_version_type(&typ),
+                    typ,
+                    null,
+                    // `default: null` is only allowed on nullable columns
+                    default: match default {
+                        ColumnDefault::Null if !null => ColumnDefault::None,
+                        default => default,
+                    },
+                    skip,
+                    rust_name,
+                    collate,
+                }
+            },
+        )
+}
+
+pub fn types(enums: Vec<&'static str>) -> impl Strategy<Value = Types<'static>> {
+    let leaf = if enums.is_empty() {
+        select(BUILT_IN_TYPES).boxed()
+    } else {
+        prop_oneof![
+            4 => select(BUILT_IN_TYPES),
+            1 => select(enums).prop_map(Types::Raw),
+        ]
+        .boxed()
+    };
+
+    leaf.prop_recursive(2, 4, 1, |inner| {
+        inner.prop_map(|typ| Types::Array(Box::new(typ)))
+    })
+}
+
+pub fn column_default() -> impl Strategy<Value = ColumnDefault<'static>> {
+    prop_oneof![
+        3 => Just(ColumnDefault::None),
+        1 => Just(ColumnDefault::Now),
+        1 => Just(ColumnDefault::CurrentDate),
+        1 => Just(ColumnDefault::CurrentTime),
+        1 => Just(ColumnDefault::Null),
+        1 => Just(ColumnDefault::Uuid),
+        1 => any::<bool>().prop_map(ColumnDefault::Bool),
+        1 => select(RAW_DEFAULTS).prop_map(ColumnDefault::Raw),
+    ]
+}
+
+pub fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        Just(Action::NoAction),
+        Just(Action::Restrict),
+        Just(Action::SetNull),
+        Just(Action::SetDefault),
+        Just(Action::Cascade),
+    ]
+}
+
+/// A reference from the column, with the actions the column does not allow replaced with `no action`.
+pub fn foreign_key(column: Column<'static>) -> impl Strategy<Value = ForeignKey<'static>> {
+    (
+        select(TABLE_NAMES),
+        select(COLUMN_NAMES),
+        action(),
+        action(),
+        any::<bool>(),
+        prop::option::of(select(CONSTRAINT_NAMES)),
+    )
+        .prop_map(
+            move |(table, foreign, delete, update, deferrable, name)| ForeignKey {
+                local: vec![column.name],
+                table,
+                foreign: vec![foreign],
+                delete: allowed_action(&column, delete),
+                update: allowed_action(&column, update),
+                deferrable,
+                name,
+            },
+        )
+}
+
+fn allowed_action(column: &Column, action: Action) -> Action {
+    match action {
+        Action::SetNull if !column.null => Action::NoAction,
+        Action::SetDefault if column.default == ColumnDefault::None => Action::NoAction,
+        action => action,
+    }
+}
+
+/// Up to two indexes over the given columns.
+pub fn indexes(columns: Vec<&'static str>) -> impl Strategy<Value = Vec<Index<'static>>> {
+    subsequence(INDEX_NAMES, 0..=INDEX_NAMES.len()).prop_flat_map(move |names| {
+        names
+            .into_iter()
+            .map(|name| {
+                (
+                    any::<bool>(),
+                    subsequence(columns.clone(), 1..=columns.len()),
+                    prop::option::of(select(PREDICATES)),
+                )
+                    .prop_map(move |(not_exists, columns, predicate)| Index {
+                        name,
+                        not_exists,
+                        columns,
+                        predicate,
+                    })
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{parser::parse, testing::schema, Context},
+        proptest::prelude::*,
+    };
+
+    proptest! {
+        #[test]
+        fn round_trip(schema in schema()) {
+            let written = schema.to_string();
+
+            let mut ctx = Context::new(0);
+
+            let parsed = parse(&mut ctx, &written);
+
+            prop_assert!(parsed.is_ok(), "{:?}\n{}", parsed, written);
+            prop_assert_eq!(schema, parsed.unwrap(), "{}", written);
+        }
+    }
+}
@@ -0,0 +1,852 @@
+use {
+    crate::{
+        models::{Action, ColumnDefault, Item, Table, Types},
+        parser::Context,
+    },
+    codespan_reporting::diagnostic::{Diagnostic, Label},
+    pest::Span,
+    std::ops::Range,
+};
+
+/// Finds the byte range of a `&str` borrowed from the input.
+///
+/// Falls back to an empty range at the start of the input if the string does not point into it.
+#[inline]
+pub fn str_range(input: &str, s: &str) -> Range<usize> {
+    let input_start = input.as_ptr() as usize;
+    let start = s.as_ptr() as usize;
+
+    if start < input_start || start + s.len() > input_start + input.len() {
+        return 0..0;
+    }
+
+    (start - input_start)..(start - input_start + s.len())
+}
+
+/// Checks if a column of the type can be a `[version]`, integers are incremented and dates set to the current time.
+pub(crate) fn is_version_type(typ: &Types) -> bool {
+    matches!(
+        typ,
+        Types::Number
+            | Types::SmallInt
+            | Types::MediumInt
+            | Types::Int
+            | Types::BigInt
+            | Types::DateTime
+    )
+}
+
+/// Rejects giving the unique constraint a second name, a table has a single one made of every `unique` column.
+///
+/// `names` are the names of each `unique as <name>` in the order they are written, in the table at `span`.
+pub(crate) fn validate_unique_names(ctx: &mut Context, span: Span<'_>, names: &[&str]) {
+    let first = match names.first() {
+        Some(first) => *first,
+        None => return,
+    };
+
+    for name in names.iter().filter(|name| **name != first) {
+        let range = str_range(span.as_str(), name);
+
+        ctx.diags.push(
+            Diagnostic::error()
+                .with_message("Duplicate constraint name")
+                .with_labels(vec![Label::primary(
+                    ctx.file_id,
+                    (span.start() + range.start)..(span.start() + range.end),
+                )
+                .with_message(format!(
+                    "the unique constraint is already named `{}`",
+                    first
+                ))]),
+        );
+    }
+}
+
+/// Runs the semantic checks that the grammar is too permissive to catch on a single table.
+///
+/// Every problem found is pushed onto the context as a diagnostic, the caller decides when to
+/// turn them into an [`Error::Invalid`](crate::Error::Invalid).
+pub(crate) fn validate_table(ctx: &mut Context, input: &str, decl: &Table) {
+    if decl.without_rowid && decl.primary_keys.is_empty() {
+        ctx.diags.push(
+            Diagnostic::error()
+                .with_message("Invalid table option")
+                .with_labels(vec![Label::primary(
+                    ctx.file_id,
+                    str_range(input, decl.name),
+                )
+                .with_message(format!(
+                    "`without rowid` requires table `{}` to have a primary key",
+                    decl.name
+                ))]),
+        );
+    }
+
+    let mut version = None;
+
+    for column in &decl.columns {
+        if column.null && column.default == ColumnDefault::Null {
+            ctx.warning(
+                "Redundant column default",
+                str_range(input, column.name),
+                format!(
+                    "column `{}` is nullable, so it already defaults to null",
+                    column.name
+                ),
+            );
+        }
+
+        if !column.null && column.default == ColumnDefault::Null {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Invalid column default")
+                    .with_labels(vec![Label::primary(
+                        ctx.file_id,
+                        str_range(input, column.name),
+                    )
+                    .with_message(format!(
+                        "`default: null` requires column `{}` to be nullable",
+                        column.name
+                    ))]),
+            );
+        }
+
+        if column.searchable && !matches!(column.typ, Types::Char | Types::Varchar(_) | Types::Text)
+        {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Invalid column modifier")
+                    .with_labels(vec![Label::primary(
+                        ctx.file_id,
+                        str_range(input, column.name),
+                    )
+                    .with_message(format!(
+                        "`searchable` requires column `{}` to be text",
+                        column.name
+                    ))]),
+            );
+        }
+
+        if column.version {
+            let problem = if let Some(first) = version {
+                Some(format!(
+                    "`version` is already given to column `{}` of table `{}`",
+                    first, decl.name
+                ))
+            } else if !is_version_type(&column.typ) {
+                Some(format!(
+                    "`version` requires column `{}` to be an integer or a date and time",
+                    column.name
+                ))
+            } else if column.null {
+                Some(format!(
+                    "`version` requires column `{}` to not be nullable",
+                    column.name
+                ))
+            } else {
+                None
+            };
+
+            if let Some(problem) = problem {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Invalid column modifier")
+                        .with_labels(vec![Label::primary(
+                            ctx.file_id,
+                            str_range(input, column.name),
+                        )
+                        .with_message(problem)]),
+                );
+            }
+
+            version = version.or(Some(column.name));
+        }
+
+        if let ColumnDefault::Raw(value) = column.default {
+            if let Err(problem) = check_raw_expression(value) {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Invalid raw expression")
+                        .with_labels(vec![Label::primary(ctx.file_id, str_range(input, value))
+                            .with_message(format!(
+                                "the default of column `{}` {}",
+                                column.name, problem
+                            ))]),
+                );
+            }
+        }
+    }
+
+    for index in &decl.indexes {
+        if let Some(predicate) = index.predicate {
+            if let Err(problem) = check_raw_expression(predicate) {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Invalid raw expression")
+                        .with_labels(vec![Label::primary(
+                            ctx.file_id,
+                            str_range(input, predicate),
+                        )
+                        .with_message(format!(
+                            "the predicate of index `{}` {}",
+                            index.name, problem
+                        ))]),
+                );
+            }
+        }
+    }
+
+    for index in &decl.indexes {
+        for column in &index.columns {
+            if decl.columns.iter().all(|c| c.name != *column) {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Unknown column")
+                        .with_labels(vec![Label::primary(ctx.file_id, str_range(input, column))
+                            .with_message(format!(
+                                "index `{}` uses column `{}`, which is not in table `{}`",
+                                index.name, column, decl.name
+                            ))]),
+                );
+            }
+        }
+    }
+
+    for foreign_key in &decl.foreign_keys {
+        if foreign_key.local.len() != foreign_key.foreign.len() {
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Mismatched foreign key")
+                    .with_labels(vec![Label::primary(
+                        ctx.file_id,
+                        str_range(input, foreign_key.table),
+                    )
+                    .with_message(format!(
+                        "{} column(s) of table `{}` reference {} column(s) of `{}`",
+                        foreign_key.local.len(),
+                        decl.name,
+                        foreign_key.foreign.len(),
+                        foreign_key.table
+                    ))]),
+            );
+        }
+
+        for local in &foreign_key.local {
+            if decl.columns.iter().all(|c| c.name != *local) {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Unknown column")
+                        .with_labels(vec![Label::primary(ctx.file_id, str_range(input, local))
+                            .with_message(format!(
+                                "foreign key uses column `{}`, which is not in table `{}`",
+                                local, decl.name
+                            ))]),
+                );
+
+                continue;
+            }
+
+            validate_foreign_key_action(ctx, input, decl, local, "delete", &foreign_key.delete);
+            validate_foreign_key_action(ctx, input, decl, local, "update", &foreign_key.update);
+        }
+    }
+}
+
+/// Database types that are never enums, whatever case they are written in.
+const RAW_TYPES: &[&str] = &[
+    "BLOB", "BYTEA", "CIDR", "CITEXT", "HSTORE", "INET", "JSON", "JSONB", "LTREE", "MACADDR",
+    "TSQUERY", "TSVECTOR", "XML",
+];
+
+/// Checks if a raw type looks like an enum, a PascalCase name like `State`.
+///
+/// Names in lowercase or all uppercase, and the known database types in any case, are raw types such as `jsonb` or
+/// `JSONB`.
+fn is_enum_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.contains(|c: char| c.is_ascii_lowercase())
+        && !RAW_TYPES.iter().any(|raw| raw.eq_ignore_ascii_case(name))
+}
+
+/// Checks that every column typed with what looks like an enum has the enum declared somewhere in the schema.
+///
+/// Other names are left alone, they are passed through to the database as raw types, unless strict types are on.
+pub(crate) fn validate_enum_references(ctx: &mut Context, input: &str, items: &[Item]) {
+    let enums = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Enum(decl) => Some(decl.name),
+            Item::Comment(_) | Item::Table(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    let tables = items.iter().filter_map(|item| match item {
+        Item::Table(decl) => Some(decl),
+        Item::Comment(_) | Item::Enum(_) => None,
+    });
+
+    for decl in tables {
+        for column in &decl.columns {
+            let name = match column.typ.raw_name() {
+                Some(name) if is_enum_name(name) => name,
+                Some(name) if ctx.options.strict_types => name,
+                _ => continue,
+            };
+
+            if enums.contains(&name) {
+                continue;
+            }
+
+            // Raw database types, which strict types does not allow
+            if !is_enum_name(name) {
+                ctx.diags.push(
+                    Diagnostic::error()
+                        .with_message("Unknown type")
+                        .with_labels(vec![Label::primary(ctx.file_id, str_range(input, name))
+                            .with_message(format!(
+                        "column `{}` uses `{}`, which is not a built-in type or a declared enum",
+                        column.name, name
+                    ))]),
+                );
+
+                continue;
+            }
+
+            ctx.diags.push(
+                Diagnostic::error()
+                    .with_message("Unknown enum")
+                    .with_labels(vec![Label::primary(ctx.file_id, str_range(input, name))
+                        .with_message(format!(
+                            "column `{}` uses `{}`, which is not a declared enum",
+                            column.name, name
+                        ))]),
+            );
+        }
+    }
+}
+
+/// Warns about enums that no column uses, and enums named after a built-in type, which columns can not use.
+pub(crate) fn warn_unused_enums(ctx: &mut Context, input: &str, items: &[Item]) {
+    let used = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Table(decl) => Some(decl.columns.iter().filter_map(|c| c.typ.raw_name())),
+            Item::Comment(_) | Item::Enum(_) => None,
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let enums = items.iter().filter_map(|item| match item {
+        Item::Enum(decl) => Some(decl),
+        Item::Comment(_) | Item::Table(_) => None,
+    });
+
+    for decl in enums {
+        let range = str_range(input, decl.name);
+
+        if Types::from_str(decl.name).raw_name().is_none() {
+            ctx.warning(
+                "Shadowed type",
+                range,
+                format!(
+                    "enum `{0}` has the name of a built-in type, columns of type `{0}` use the built-in type",
+                    decl.name
+                ),
+            );
+        } else if !used.contains(&decl.name) {
+            ctx.warning(
+                "Unused enum",
+                range,
+                format!("enum `{}` is not used by any column", decl.name),
+            );
+        }
+    }
+}
+
+fn validate_foreign_key_action(
+    ctx: &mut Context,
+    input: &str,
+    decl: &Table,
+    local: &str,
+    on: &str,
+    action: &Action,
+) {
+    let column = match decl.columns.iter().find(|c| c.name == local) {
+        Some(column) => column,
+        None => return,
+    };
+
+    let message = match action {
+        Action::SetNull if !column.null => format!(
+            "`{}: set null` requires column `{}` to be nullable",
+            on, column.name
+        ),
+        Action::SetDefault if column.default == ColumnDefault::None => format!(
+            "`{}: set default` requires column `{}` to have a default",
+            on, column.name
+        ),
+        _ => return,
+    };
+
+    ctx.diags.push(
+        Diagnostic::error()
+            .with_message("Invalid reference action")
+            .with_labels(vec![
+                Label::primary(ctx.file_id, str_range(input, local)).with_message(message)
+            ]),
+    );
+}
+
+/// Checks an expression that is written into the SQL as it is, such as a raw default or an index predicate.
+///
+/// The expression has to be something that can not break out of the statement it is written into, so quotes and
+/// parentheses have to be balanced, and statement terminators and comments are only allowed inside of strings.
+pub(crate) fn check_raw_expression(expr: &str) -> Result<(), &'static str> {
+    let mut quote = None;
+    let mut depth = 0usize;
+    let mut chars = expr.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if let Some(open) = quote {
+            // A doubled quote is an escaped quote, which leaves and re-enters the string
+            if c == open {
+                quote = None;
+            }
+
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '(' => depth += 1,
+            ')' if depth == 0 => return Err("closes a parenthesis that was never opened"),
+            ')' => depth -= 1,
+            ';' => return Err("contains `;`, which would end the statement"),
+            '-' if chars.peek() == Some(&'-') => {
+                return Err("contains a `--` comment, which would hide the rest of the statement")
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                return Err("contains a `/*` comment, which would hide the rest of the statement")
+            }
+            _ => {}
+        }
+    }
+
+    if quote.is_some() {
+        return Err("has a string that is never closed");
+    }
+
+    if depth != 0 {
+        return Err("has a parenthesis that is never closed");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::check_raw_expression,
+        crate::{parser::parse, Context, Error, Limits, ParseOptions},
+        codespan_reporting::diagnostic::Diagnostic,
+    };
+
+    fn assert_invalid(input: &str, expected: &[&str]) {
+        assert_invalid_with(Context::new(0), input, expected)
+    }
+
+    fn assert_invalid_with(mut ctx: Context, input: &str, expected: &[&str]) {
+        match parse(&mut ctx, input) {
+            Err(Error::Invalid) => {}
+            res => panic!("expected validation failure, got {:?}", res),
+        }
+
+        let messages = ctx
+            .diagnostics()
+            .iter()
+            .flat_map(|diag: &Diagnostic<usize>| diag.labels.iter())
+            .map(|label| label.message.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(expected, messages.as_slice());
+    }
+
+    #[test]
+    fn duplicate_unique_name() {
+        assert_invalid(
+            "table Node {
+                id text [primary key]
+                email text [unique as uq_node_email]
+                name text [unique as uq_node_name]
+            }",
+            &["the unique constraint is already named `uq_node_email`"],
+        );
+    }
+
+    #[test]
+    fn repeated_unique_name() {
+        let mut ctx = Context::new(0);
+
+        let schema = parse(
+            &mut ctx,
+            "table Node {
+                id text [primary key]
+                email text [unique as uq_node]
+                name text [unique as uq_node]
+            }",
+        )
+        .expect("Unable to parse schema");
+
+        assert_eq!(1, schema.items.len());
+    }
+
+    #[test]
+    fn set_null_on_not_null() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                other text [ref: Other.id (delete: set null)]
+            }",
+            &["`delete: set null` requires column `other` to be nullable"],
+        );
+    }
+
+    #[test]
+    fn set_null_on_not_null_update() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                other text [ref: Other.id (update: set null)]
+            }",
+            &["`update: set null` requires column `other` to be nullable"],
+        );
+    }
+
+    #[test]
+    fn set_default_without_default() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                other text! [ref: Other.id (delete: set default)]
+            }",
+            &["`delete: set default` requires column `other` to have a default"],
+        );
+    }
+
+    #[test]
+    fn default_null_on_not_null() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                value text [default: null]
+            }",
+            &["`default: null` requires column `value` to be nullable"],
+        );
+    }
+
+    #[test]
+    fn default_null_on_nullable() {
+        let mut ctx = Context::new(0);
+
+        parse(
+            &mut ctx,
+            "table Settings {
+                key text [primary key]
+                value text! [default: null]
+            }",
+        )
+        .expect("`default: null` on a nullable column was rejected");
+
+        assert!(ctx.diagnostics().is_empty());
+        assert_eq!(
+            vec!["column `value` is nullable, so it already defaults to null"],
+            warning_labels(&ctx)
+        );
+    }
+
+    fn warning_labels(ctx: &Context) -> Vec<&str> {
+        ctx.warnings()
+            .iter()
+            .flat_map(|diag| diag.labels.iter().map(|label| label.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn unused_enum() {
+        let mut ctx = Context::new(0);
+
+        parse(
+            &mut ctx,
+            "enum Kind { Text Number }
+            enum State { On Off }
+            table Settings {
+                key text [primary key]
+                state State
+            }",
+        )
+        .expect("An unused enum was rejected");
+
+        assert!(ctx.diagnostics().is_empty());
+        assert_eq!(
+            vec!["enum `Kind` is not used by any column"],
+            warning_labels(&ctx)
+        );
+    }
+
+    #[test]
+    fn shadowed_enum() {
+        let mut ctx = Context::new(0);
+
+        parse(
+            &mut ctx,
+            "enum text { Short Long }
+            table Settings {
+                key text [primary key]
+            }",
+        )
+        .expect("An enum named after a built-in type was rejected");
+
+        assert_eq!(
+            vec!["enum `text` has the name of a built-in type, columns of type `text` use the built-in type"],
+            warning_labels(&ctx)
+        );
+    }
+
+    #[test]
+    fn raw_default_injection() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                value text [default: 'a'; DROP TABLE Settings]
+            }",
+            &["the default of column `value` contains `;`, which would end the statement"],
+        );
+    }
+
+    #[test]
+    fn raw_predicate_comment() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                value text!
+
+                index ByValue on (value) where value is not null -- and more
+            }",
+            &["the predicate of index `ByValue` contains a `--` comment, which would hide the rest of the statement"],
+        );
+    }
+
+    #[test]
+    fn raw_expressions() {
+        assert_eq!(
+            Ok(()),
+            check_raw_expression("coalesce(lower('a;)'), 'it''s -- fine')")
+        );
+        assert_eq!(Ok(()), check_raw_expression("\"odd;name\" > 0"));
+        assert_eq!(Ok(()), check_raw_expression("1 - -1"));
+
+        assert_eq!(
+            Err("has a parenthesis that is never closed"),
+            check_raw_expression("lower('a'")
+        );
+        assert_eq!(
+            Err("closes a parenthesis that was never opened"),
+            check_raw_expression("1) + (2")
+        );
+        assert_eq!(
+            Err("has a string that is never closed"),
+            check_raw_expression("'it''s")
+        );
+        assert_eq!(
+            Err("contains `;`, which would end the statement"),
+            check_raw_expression("0; DROP TABLE Users")
+        );
+    }
+
+    #[test]
+    fn searchable_not_text() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                count number [searchable]
+            }",
+            &["`searchable` requires column `count` to be text"],
+        );
+    }
+
+    #[test]
+    fn version() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                name text [version]
+                count int! [version]
+            }",
+            &[
+                "`version` requires column `name` to be an integer or a date and time",
+                "`version` is already given to column `name` of table `Settings`",
+            ],
+        );
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                updated dateTime! [version]
+            }",
+            &["`version` requires column `updated` to not be nullable"],
+        );
+    }
+
+    #[test]
+    fn index_unknown_column() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                index ValueIndex on (key, value)
+            }",
+            &["index `ValueIndex` uses column `value`, which is not in table `Settings`"],
+        );
+    }
+
+    #[test]
+    fn foreign_key_unknown_column() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                foreign key (key, scope) references Other (key, scope)
+            }",
+            &["foreign key uses column `scope`, which is not in table `Settings`"],
+        );
+    }
+
+    #[test]
+    fn foreign_key_mismatched_columns() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                scope text
+                foreign key (key, scope) references Other (key)
+            }",
+            &["2 column(s) of table `Settings` reference 1 column(s) of `Other`"],
+        );
+    }
+
+    #[test]
+    fn nullable_primary_key() {
+        assert_invalid(
+            "table Settings {
+                key text! [primary key]
+            }",
+            &["column `key` is nullable, but a primary key can not be null"],
+        );
+    }
+
+    #[test]
+    fn default_null_primary_key() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key, default: null]
+            }",
+            &[
+                "`default: null` on column `key`, but a primary key can not be null",
+                "primary key given here",
+                "`default: null` requires column `key` to be nullable",
+            ],
+        );
+    }
+
+    #[test]
+    fn duplicate_modifiers() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key, default: 'a', unique, default: 'b', unique]
+            }",
+            &[
+                "`default` is given more than once on column `key`",
+                "first given here",
+                "`unique` is given more than once on column `key`",
+                "first given here",
+            ],
+        );
+    }
+
+    #[test]
+    fn duplicate_modifiers_in_mixin() {
+        assert_invalid(
+            "mixin Timestamps {
+                created dateTime [default: now(), default: now()]
+            }
+
+            table Settings includes Timestamps {
+                key text [primary key]
+            }",
+            &[
+                "`default` is given more than once on column `created`",
+                "first given here",
+            ],
+        );
+    }
+
+    #[test]
+    fn unknown_enum() {
+        assert_invalid(
+            "table Settings {
+                key text [primary key]
+                rating Rating
+                tags Tag[]
+                data jsonb
+                document JSONB
+                search TsVector
+                email CITEXT
+            }",
+            &[
+                "column `rating` uses `Rating`, which is not a declared enum",
+                "column `tags` uses `Tag`, which is not a declared enum",
+            ],
+        );
+    }
+
+    #[test]
+    fn strict_types() {
+        let input = "enum state { on off }
+            table Settings {
+                key text [primary key]
+                count intt
+                state state
+            }";
+
+        parse(&mut Context::new(0), input).expect("A raw type was rejected without strict types");
+
+        assert_invalid_with(
+            Context::with_options(0, Limits::default(), ParseOptions { strict_types: true }),
+            input,
+            &["column `count` uses `intt`, which is not a built-in type or a declared enum"],
+        );
+    }
+
+    #[test]
+    fn without_rowid_without_primary_key() {
+        assert_invalid(
+            "table Settings {
+                key text
+            } [without rowid]",
+            &["`without rowid` requires table `Settings` to have a primary key"],
+        );
+    }
+
+    #[test]
+    fn valid_actions() {
+        let mut ctx = Context::new(0);
+
+        parse(
+            &mut ctx,
+            "table Settings {
+                key text [primary key]
+                other text! [ref: Other.id (delete: set null, update: cascade)]
+            }",
+        )
+        .expect("valid reference actions were rejected");
+
+        assert!(ctx.diagnostics().is_empty());
+    }
+}
@@ -2,16 +2,203 @@
 pub mod postgres;
 #[cfg(all(feature = "sqlite"))]
 pub mod sqlite;
+#[cfg(feature = "sqlite-async")]
+pub mod sqlite_async;
 
-pub use rewryte_macro::{models, schema};
+pub use rewryte_macro::{models, models_and_schema, schema};
+
+/// The extension traits and parameter macros of every enabled database, along with the model macros.
+///
+/// The traits are imported anonymously, only their methods are brought into scope. Both databases name their row
+/// conversion `FromRow`, so implementing it is left to `rewryte::sqlite::prelude` and `rewryte::postgres::prelude`.
+pub mod prelude {
+    pub use crate::{models, models_and_schema, schema};
+
+    #[cfg(feature = "postgres")]
+    pub use crate::{postgres::ClientExt as _, postgres_params};
+
+    #[cfg(feature = "sqlite")]
+    pub use crate::{
+        sqlite::{ConnectionExt as _, StatementExt as _},
+        sqlite_named_params, sqlite_params,
+    };
+}
+
+/// The error of a generated enum's `FromStr`, the string is not one of the enum's variant names.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ParseEnumError {
+    /// The name of the enum in the schema.
+    pub name: &'static str,
+    /// The string that failed to parse.
+    pub value: String,
+}
+
+impl std::fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid `{}` variant `{}`", self.name, self.value)
+    }
+}
+
+impl std::error::Error for ParseEnumError {}
+
+/// The Rust type of `interval` columns, a `Duration` that the databases can read and write, and which can be negative.
+///
+/// PostgreSQL stores it as an `INTERVAL` with microsecond precision, its months are counted as 30 days. SQLite has no
+/// interval type, so it is stored as a `BIGINT` of nanoseconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Interval {
+    /// The length of the interval.
+    pub duration: std::time::Duration,
+    /// If the interval goes back in time, such as PostgreSQL's `-1 day`.
+    pub negative: bool,
+}
+
+impl Interval {
+    pub fn new(duration: std::time::Duration) -> Self {
+        Interval { duration, negative: false }
+    }
+
+    pub fn new_negative(duration: std::time::Duration) -> Self {
+        Interval { duration, negative: true }
+    }
+
+    /// If the interval is below zero, a negative interval of zero length is not.
+    pub fn is_negative(&self) -> bool {
+        self.negative && self.duration != std::time::Duration::default()
+    }
+
+    /// The signed length in nanoseconds, used to compare intervals.
+    fn nanos(&self) -> i128 {
+        let nanos = self.duration.as_nanos() as i128;
+
+        if self.negative {
+            -nanos
+        } else {
+            nanos
+        }
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.nanos() == other.nanos()
+    }
+}
+
+impl Eq for Interval {}
+
+impl std::hash::Hash for Interval {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.nanos().hash(state)
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.nanos().cmp(&other.nanos())
+    }
+}
+
+impl From<std::time::Duration> for Interval {
+    fn from(duration: std::time::Duration) -> Self {
+        Interval::new(duration)
+    }
+}
+
+impl std::convert::TryFrom<Interval> for std::time::Duration {
+    type Error = NegativeIntervalError;
+
+    fn try_from(interval: Interval) -> Result<Self, Self::Error> {
+        if interval.is_negative() {
+            return Err(NegativeIntervalError);
+        }
+
+        Ok(interval.duration)
+    }
+}
+
+/// The error of turning an [`Interval`] into a `Duration`, which can not be negative.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct NegativeIntervalError;
+
+impl std::fmt::Display for NegativeIntervalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a negative interval can not be a `Duration`")
+    }
+}
+
+impl std::error::Error for NegativeIntervalError {}
+
+/// The Rust type of `money` columns, a `rust_decimal::Decimal` with the `with-decimal` feature and an `f64` without.
+///
+/// PostgreSQL stores it as a `NUMERIC`, which neither type can be read from by the driver. SQLite stores the decimal
+/// as text, which its `DECIMAL` affinity keeps as text unless it fits in a `REAL` without losing digits.
+#[cfg(feature = "with-decimal")]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Money(pub rust_decimal::Decimal);
+
+/// The Rust type of `money` columns, a `rust_decimal::Decimal` with the `with-decimal` feature and an `f64` without.
+///
+/// PostgreSQL stores it as a `NUMERIC`, which neither type can be read from by the driver. SQLite stores the `f64` as
+/// a `REAL`.
+#[cfg(not(feature = "with-decimal"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Money(pub f64);
+
+#[cfg(feature = "with-decimal")]
+impl From<rust_decimal::Decimal> for Money {
+    fn from(amount: rust_decimal::Decimal) -> Self {
+        Money(amount)
+    }
+}
+
+#[cfg(feature = "with-decimal")]
+impl From<Money> for rust_decimal::Decimal {
+    fn from(money: Money) -> Self {
+        money.0
+    }
+}
+
+#[cfg(not(feature = "with-decimal"))]
+impl From<f64> for Money {
+    fn from(amount: f64) -> Self {
+        Money(amount)
+    }
+}
+
+#[cfg(not(feature = "with-decimal"))]
+impl From<Money> for f64 {
+    fn from(money: Money) -> Self {
+        money.0
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for Money {
+    #[cfg(feature = "with-decimal")]
+    type Err = rust_decimal::Error;
+    #[cfg(not(feature = "with-decimal"))]
+    type Err = std::num::ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Money)
+    }
+}
 
 #[cfg(feature = "build-script")]
 use {
-    codespan_reporting::{
-        files::SimpleFiles,
-        term::{self, termcolor::NoColor, Config},
-    },
-    rewryte_parser::parser::{parse, Context},
+    rewryte_parser::{parse_and_report, Context},
     std::{
         fs,
         io::{ErrorKind, Write},
@@ -37,57 +224,33 @@ where
         }
     };
 
-    let contents_str = contents.as_str();
-
-    let mut files = SimpleFiles::new();
-
-    let file_id = files.add(path.display().to_string(), contents_str);
-
-    let mut ctx = Context::new(file_id);
-
-    match parse(&mut ctx, contents_str) {
+    match parse_and_report(&path.display().to_string(), &contents) {
         Ok(schema) => {
             let mut options = rewryte_generator::rust::Options::default();
 
             if let Some(extra) = extra {
-                let mut mapped = extra.iter();
-
-                if mapped.by_ref().any(|value| *value == "juniper") {
-                    options.juniper = true;
-                }
-
-                if mapped.by_ref().any(|value| *value == "serde") {
-                    options.serde = true;
-                }
-
-                if mapped.by_ref().any(|value| *value == "sqlx") {
-                    options.sqlx = true;
+                for value in extra {
+                    if !options.enable(value) {
+                        panic!("{}: Unknown option `{}`", path.display(), value);
+                    }
                 }
             }
 
             if let Err(err) = rewryte_generator::rust::write_schema(&schema, writer, options) {
                 panic!("{}: {:?}", path.display(), err);
             }
-        }
-        Err(err) => {
-            let config = Config::default();
 
-            let mut writer = NoColor::new(Vec::new());
+            // Passed on to cargo, which shows them after the build
+            let mut ctx = Context::new(0);
 
-            for diag in ctx.diagnostics() {
-                if let Err(err) = term::emit(&mut writer, &config, &files, diag) {
-                    panic!("{}: {:?}", path.display(), err);
-                }
-            }
+            rewryte_generator::rust::check_options(&mut ctx, &contents, &schema, options);
 
-            let emit_string = match String::from_utf8(writer.into_inner()) {
-                Ok(string) => string,
-                Err(err) => {
-                    panic!("{}: {:?}", path.display(), err);
+            for diag in ctx.warnings() {
+                for label in &diag.labels {
+                    println!("cargo:warning={}: {}: {}", path.display(), diag.message, label.message);
                 }
-            };
-
-            panic!("{}\n\n{}", err, emit_string)
+            }
         }
+        Err(report) => panic!("{}", report),
     }
-}
\ No newline at end of file
+}
@@ -5,13 +5,18 @@ use {
     futures::{Stream, TryStreamExt},
     std::{
         collections::HashMap,
+        convert::TryFrom,
+        error::Error as _,
+        future::Future,
+        hash::Hash,
+        io,
         marker::{PhantomData, PhantomPinned},
         net::IpAddr,
         pin::Pin,
         task::{Context, Poll},
-        time::SystemTime,
+        time::{Duration, SystemTime},
     },
-    tokio_postgres::types::ToSql,
+    tokio_postgres::{error::SqlState, types::ToSql},
 };
 
 #[macro_export]
@@ -30,12 +35,272 @@ fn slice_iter<'a>(
     s.iter().map(|s| *s as _)
 }
 
+/// Checks if an error is transient, a closed connection, an io failure, or a transaction conflict that can be retried.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    let err = match err.chain().find_map(|err| err.downcast_ref::<Error>()) {
+        Some(err) => err,
+        None => return err.chain().any(|err| err.is::<io::Error>()),
+    };
+
+    match err.code() {
+        Some(code) => {
+            code.code().starts_with("08")
+                || code == &SqlState::T_R_SERIALIZATION_FAILURE
+                || code == &SqlState::T_R_DEADLOCK_DETECTED
+                || code == &SqlState::CANNOT_CONNECT_NOW
+        }
+        None => {
+            err.is_closed() || err.source().map(|source| source.is::<io::Error>()).unwrap_or(false)
+        }
+    }
+}
+
+/// The longest delay between two attempts of [`retry`].
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Doubles the delay between attempts, up to [`MAX_BACKOFF`].
+fn next_backoff(delay: Duration) -> Duration {
+    delay.checked_mul(2).unwrap_or(MAX_BACKOFF).min(MAX_BACKOFF)
+}
+
+/// Runs `f` until it succeeds, retrying up to `retries` times while its error is transient.
+async fn retry<T, F, Fut>(retries: usize, backoff: Duration, mut f: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut attempt = 0;
+    let mut delay = backoff;
+
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < retries && is_retryable(&err) => {
+                attempt += 1;
+
+                tokio::time::delay_for(delay).await;
+
+                delay = next_backoff(delay);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// The extension traits, row conversions, and parameter macro, for a single glob import.
+///
+/// ```no_run
+/// use rewryte::postgres::prelude::*;
+///
+/// async fn stories(client: &rewryte::postgres::Client) -> anyhow::Result<i64> {
+///     client.type_query_one("SELECT COUNT(*) FROM Story", postgres_params![]).await
+/// }
+/// ```
+pub mod prelude {
+    pub use {
+        super::{
+            ClientExt, Find, FromNotification, FromRow, FromRowPrefixed, Search, ToParams, TryFromRow,
+            Versioned,
+        },
+        crate::postgres_params,
+    };
+}
+
+/// Converts a row into a type.
+///
+/// To return an error type of your own implement [`TryFromRow`] instead, which implements this trait.
 pub trait FromRow {
     fn from_row(row: Row) -> anyhow::Result<Self>
     where
         Self: Sized;
 }
 
+/// Converts a row into a type, with an error that converts into the [`anyhow::Error`] the query helpers return.
+pub trait TryFromRow {
+    type Error: Into<anyhow::Error>;
+
+    fn try_from_row(row: Row) -> Result<Self, Self::Error>
+    where
+        Self: Sized;
+}
+
+impl<T> FromRow for T
+where
+    T: TryFromRow,
+{
+    fn from_row(row: Row) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        T::try_from_row(row).map_err(Into::into)
+    }
+}
+
+/// Converts the columns named `{prefix}_{column}` of a row into a type.
+///
+/// Lets a join be read into one struct per table, by aliasing each table's columns with its own prefix.
+pub trait FromRowPrefixed {
+    fn from_row_prefixed(row: &Row, prefix: &str) -> anyhow::Result<Self>
+    where
+        Self: Sized;
+}
+
+/// Binds a whole struct as query parameters, in the order of the table's columns.
+pub trait ToParams {
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+/// Finds the rows whose `[searchable]` columns match a query, generated by the `search` option.
+///
+/// The query is read with `plainto_tsquery`, so every word in it has to be in the columns' `to_tsvector`.
+#[async_trait::async_trait]
+pub trait Search: FromRow + Send + Sync + Sized {
+    /// Selects the table's columns where the searchable columns' text search vector matches the query in `$1`.
+    const SEARCH: &'static str;
+
+    async fn search(client: &Client, query: &str) -> anyhow::Result<Vec<Self>> {
+        client.type_query(Self::SEARCH, &[&query]).await
+    }
+}
+
+/// Finds a row by its primary key, generated for every table that has one.
+#[async_trait::async_trait]
+pub trait Find: FromRow + Send + Sync + Sized {
+    /// The primary key, a tuple of the key columns' types if there is more than one.
+    type Key: Send + Sync;
+
+    /// Selects the table's columns where the primary key columns equal `$1`, `$2`, and so on.
+    const FIND: &'static str;
+
+    /// Binds the primary key in the order of the parameters in [`FIND`](Find::FIND).
+    fn key_params(key: &Self::Key) -> Vec<&(dyn ToSql + Sync)>;
+
+    async fn find(client: &Client, key: Self::Key) -> anyhow::Result<Option<Self>> {
+        client.type_query_one_opt(Self::FIND, &Self::key_params(&key)).await
+    }
+}
+
+/// Updates a row only if its `[version]` column still holds the version it was read with, for optimistic concurrency.
+///
+/// Generated for tables with a primary key and a version column. The update bumps the version, integers are
+/// incremented and dates set to the current time, so the struct is stale afterwards and has to be fetched again.
+#[async_trait::async_trait]
+pub trait Versioned: ToParams + Sync {
+    /// Sets the table's columns from [`to_params`](ToParams::to_params) and bumps the version, where the primary key
+    /// and version columns still equal the struct's.
+    const UPDATE_IF_UNCHANGED: &'static str;
+
+    /// Returns `false` without updating anything if the row was changed or deleted since it was read.
+    async fn update_if_unchanged(&self, client: &Client) -> anyhow::Result<bool> {
+        let updated = client.execute(Self::UPDATE_IF_UNCHANGED, &self.to_params()).await?;
+
+        Ok(updated != 0)
+    }
+}
+
+/// Converts the payload of a `NOTIFY` into a type.
+///
+/// Implemented for `String`, which keeps the payload as it is, and with `with-serde-json` for [`types::Json`], which
+/// deserializes a JSON payload into any `serde` type, such as the generated models.
+pub trait FromNotification {
+    type Error: Into<anyhow::Error>;
+
+    fn from_notification(payload: &str) -> Result<Self, Self::Error>
+    where
+        Self: Sized;
+}
+
+impl FromNotification for String {
+    type Error = std::convert::Infallible;
+
+    fn from_notification(payload: &str) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        Ok(payload.to_string())
+    }
+}
+
+#[cfg(feature = "with-serde-json")]
+impl<T> FromNotification for types::Json<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Error = anyhow::Error;
+
+    fn from_notification(payload: &str) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        serde_json::from_str(payload)
+            .map(types::Json)
+            .context("Failed to deserialize the notification payload")
+    }
+}
+
+/// A notification received on a channel the connection listens to, with its payload converted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedNotification<N> {
+    /// The process ID of the backend that sent the notification.
+    pub process_id: i32,
+    pub channel: String,
+    pub payload: N,
+}
+
+impl<N> TypedNotification<N>
+where
+    N: FromNotification,
+{
+    pub fn from_notification(notification: &Notification) -> anyhow::Result<Self> {
+        Ok(Self {
+            process_id: notification.process_id(),
+            channel: notification.channel().to_string(),
+            payload: N::from_notification(notification.payload()).map_err(Into::into)?,
+        })
+    }
+}
+
+/// Turns a connection's messages into a stream of its typed notifications, notices are skipped.
+///
+/// Notifications are only received by polling the connection, which also has to be driven for the client's queries
+/// to complete. Reading the messages straight from the connection in the same task as the queries deadlocks, so a
+/// spawned task drives the connection and forwards its messages over a channel, which the stream is built from:
+///
+/// ```no_run
+/// use {
+///     futures::{StreamExt, TryStreamExt},
+///     rewryte::postgres::{self, prelude::*},
+/// };
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let (client, mut connection) = postgres::connect("host=localhost user=postgres", postgres::NoTls).await?;
+///
+/// let (sender, receiver) = futures::channel::mpsc::unbounded();
+/// let messages = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+///
+/// tokio::spawn(messages.map(Ok).forward(sender));
+///
+/// let mut events = postgres::notifications::<_, String>(receiver);
+///
+/// client.listen("events").await?;
+///
+/// while let Some(event) = events.try_next().await? {
+///     println!("{}: {}", event.channel, event.payload);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn notifications<M, N>(messages: M) -> NotificationStream<M, N>
+where
+    M: Stream<Item = Result<AsyncMessage, Error>>,
+    N: FromNotification,
+{
+    NotificationStream {
+        messages,
+        _n: PhantomData,
+    }
+}
+
 macro_rules! impl_from_row {
     ($( $from:ty, )*) => {
         $(
@@ -60,8 +325,218 @@ impl_from_row![
     HashMap<String, Option<String>>,
     SystemTime,
     IpAddr,
+    crate::Interval,
+    crate::Money,
 ];
 
+const MICROS_PER_DAY: i128 = 86_400_000_000;
+
+impl<'a> types::FromSql<'a> for crate::Interval {
+    fn from_sql(
+        _type: &types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        if raw.len() != 16 {
+            return Err("invalid interval length".into());
+        }
+
+        let micros = i64::from_be_bytes(<[u8; 8]>::try_from(&raw[0..8])?);
+        let days = i32::from_be_bytes(<[u8; 4]>::try_from(&raw[8..12])?);
+        let months = i32::from_be_bytes(<[u8; 4]>::try_from(&raw[12..16])?);
+
+        // The same 30 day months as `justify_days`
+        let total = i128::from(micros) + (i128::from(days) + i128::from(months) * 30) * MICROS_PER_DAY;
+
+        let micros = u64::try_from(total.abs()).map_err(|_| "interval out of range")?;
+
+        Ok(crate::Interval {
+            duration: Duration::from_micros(micros),
+            negative: total < 0,
+        })
+    }
+
+    fn accepts(type_: &types::Type) -> bool {
+        *type_ == types::Type::INTERVAL
+    }
+}
+
+impl types::ToSql for crate::Interval {
+    fn to_sql(
+        &self,
+        _type: &types::Type,
+        out: &mut types::private::BytesMut,
+    ) -> Result<types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        // Anything below a microsecond is dropped, the precision of an `INTERVAL`
+        let micros = i64::try_from(self.duration.as_micros())?;
+        let micros = if self.negative { -micros } else { micros };
+
+        out.extend_from_slice(&micros.to_be_bytes());
+        out.extend_from_slice(&0i32.to_be_bytes());
+        out.extend_from_slice(&0i32.to_be_bytes());
+
+        Ok(types::IsNull::No)
+    }
+
+    fn accepts(type_: &types::Type) -> bool {
+        *type_ == types::Type::INTERVAL
+    }
+
+    types::to_sql_checked!();
+}
+
+const NUMERIC_NEGATIVE: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+
+/// Turns a binary `NUMERIC` into its decimal text, it is made of base 10000 digits, the first of them is
+/// `10000^weight`.
+fn numeric_to_string(raw: &[u8]) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
+    let word = |i: usize| -> Result<u16, Box<dyn std::error::Error + Sync + Send>> {
+        let bytes = raw.get(i * 2..i * 2 + 2).ok_or("invalid numeric length")?;
+
+        Ok(u16::from_be_bytes(<[u8; 2]>::try_from(bytes)?))
+    };
+
+    let ndigits = usize::from(word(0)?);
+    let weight = i64::from(word(1)? as i16);
+    let sign = word(2)?;
+    let dscale = usize::from(word(3)?);
+
+    if sign == NUMERIC_NAN {
+        return Err("NaN is not an amount of money".into());
+    }
+
+    let digits = (0..ndigits).map(|i| word(4 + i)).collect::<Result<Vec<_>, _>>()?;
+    let digit = |i: i64| {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| digits.get(i).copied())
+            .unwrap_or(0)
+    };
+
+    let mut text = String::new();
+
+    if sign == NUMERIC_NEGATIVE {
+        text.push('-');
+    }
+
+    if weight < 0 {
+        // The first digit is already a fraction
+        text.push('0');
+    } else {
+        text.push_str(&digit(0).to_string());
+
+        for i in 1..=weight {
+            text.push_str(&format!("{:04}", digit(i)));
+        }
+    }
+
+    if dscale != 0 {
+        let mut fraction = String::new();
+
+        for i in 1..=(dscale as i64 + 3) / 4 {
+            fraction.push_str(&format!("{:04}", digit(weight + i)));
+        }
+
+        fraction.truncate(dscale);
+
+        text.push('.');
+        text.push_str(&fraction);
+    }
+
+    Ok(text)
+}
+
+/// Turns decimal text, without an exponent, into a binary `NUMERIC`.
+fn numeric_from_str(text: &str, out: &mut types::private::BytesMut) {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let (integer, fraction) = match text.find('.') {
+        Some(i) => (&text[..i], &text[i + 1..]),
+        None => (text, ""),
+    };
+
+    // Both parts are padded with zeros to whole base 10000 digits, aligned on the decimal point
+    let padding = (4 - integer.len() % 4) % 4;
+    let decimal = format!(
+        "{}{}{}{}",
+        "0".repeat(padding),
+        integer,
+        fraction,
+        "0".repeat((4 - fraction.len() % 4) % 4)
+    );
+
+    let mut digits = decimal
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| chunk.iter().fold(0u16, |digit, b| digit * 10 + u16::from(b - b'0')))
+        .collect::<Vec<_>>();
+    let mut weight = ((padding + integer.len()) / 4) as i16 - 1;
+
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    let leading = digits.iter().take_while(|digit| **digit == 0).count();
+
+    digits.drain(..leading);
+    weight -= leading as i16;
+
+    if digits.is_empty() {
+        weight = 0;
+    }
+
+    let sign = if negative && !digits.is_empty() { NUMERIC_NEGATIVE } else { 0 };
+
+    out.extend_from_slice(&(digits.len() as u16).to_be_bytes());
+    out.extend_from_slice(&weight.to_be_bytes());
+    out.extend_from_slice(&sign.to_be_bytes());
+    out.extend_from_slice(&(fraction.len() as u16).to_be_bytes());
+
+    for digit in digits {
+        out.extend_from_slice(&digit.to_be_bytes());
+    }
+}
+
+impl<'a> types::FromSql<'a> for crate::Money {
+    fn from_sql(
+        _type: &types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(numeric_to_string(raw)?.parse()?)
+    }
+
+    fn accepts(type_: &types::Type) -> bool {
+        *type_ == types::Type::NUMERIC
+    }
+}
+
+impl types::ToSql for crate::Money {
+    fn to_sql(
+        &self,
+        _type: &types::Type,
+        out: &mut types::private::BytesMut,
+    ) -> Result<types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        // `f64` never formats with an exponent, so both types give plain decimal text
+        let text = self.to_string();
+
+        if !text.bytes().all(|b| b.is_ascii_digit() || b == b'-' || b == b'.') {
+            return Err(format!("`{}` is not an amount of money", text).into());
+        }
+
+        numeric_from_str(&text, out);
+
+        Ok(types::IsNull::No)
+    }
+
+    fn accepts(type_: &types::Type) -> bool {
+        *type_ == types::Type::NUMERIC
+    }
+
+    types::to_sql_checked!();
+}
+
 #[cfg(feature = "with-chrono")]
 impl_from_row![
     chrono::NaiveDate,
@@ -139,6 +614,31 @@ pub trait ClientExt {
         S: ?Sized + ToStatement + Send + Sync,
         T: FromRow + Send + Sync;
 
+    /// Gets the single value of a single row query, such as a `COUNT(*)`.
+    ///
+    /// The same as [`type_query_one`](ClientExt::type_query_one), named for scalars so call sites read better.
+    ///
+    /// Newer versions of `tokio-postgres` give `Client` an inherent `query_scalar` that returns a `Vec` of every row,
+    /// which is picked over this one, call it as `ClientExt::query_scalar(&client, ...)` on a `Client`.
+    async fn query_scalar<T, S>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> anyhow::Result<T>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync;
+
+    /// Gets the single value of a query that returns at most one row, `None` if there is no row.
+    async fn query_scalar_opt<T, S>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> anyhow::Result<Option<T>>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync;
+
     async fn type_query_raw<T, S>(
         &self,
         statement: &S,
@@ -147,6 +647,71 @@ pub trait ClientExt {
     where
         S: ?Sized + ToStatement + Send + Sync,
         T: FromRow;
+
+    /// Runs [`type_query`](ClientExt::type_query), retrying up to `retries` times on transient errors.
+    ///
+    /// The delay between attempts starts at `backoff` and doubles after each attempt, up to a minute.
+    /// Query and mapping errors are returned straight away, a closed connection is retried.
+    ///
+    /// Only use this for idempotent reads, a retried statement may have already been applied.
+    async fn type_query_retry<T, S>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+        retries: usize,
+        backoff: Duration,
+    ) -> anyhow::Result<Vec<T>>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync;
+
+    /// Collects the mapped rows into a map, using `key` to get each row's key.
+    ///
+    /// If two rows have the same key the later row replaces the earlier one.
+    async fn type_query_map<K, T, S, F>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+        key: F,
+    ) -> anyhow::Result<HashMap<K, T>>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync,
+        K: Eq + Hash + Send,
+        F: FnMut(&T) -> K + Send;
+
+    /// Gets a page of mapped rows, `LIMIT` and `OFFSET` are appended to `statement` and bound after `params`.
+    ///
+    /// The statement should have an `ORDER BY`, otherwise the database is free to return rows in any order.
+    async fn type_query_page<T>(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<T>>
+    where
+        T: FromRow + Send + Sync;
+
+    /// Calls `f` for every mapped row as it is received, without buffering the result set.
+    ///
+    /// Stops at the first error, whether it comes from the query, the row mapping, or `f`.
+    async fn type_for_each<T, S, F, Fut>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+        f: F,
+    ) -> anyhow::Result<()>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync,
+        F: FnMut(T) -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<()>> + Send;
+
+    /// Subscribes the connection to a `NOTIFY` channel, the notifications are read with [`notifications`].
+    ///
+    /// The channel is quoted, so its name is case sensitive, unlike an unquoted `NOTIFY events`, which is lowercased.
+    async fn listen(&self, channel: &str) -> anyhow::Result<()>;
 }
 
 #[async_trait::async_trait]
@@ -239,6 +804,30 @@ impl ClientExt for Client {
         Ok(Some(row))
     }
 
+    async fn query_scalar<T, S>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> anyhow::Result<T>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync,
+    {
+        self.type_query_one(statement, params).await
+    }
+
+    async fn query_scalar_opt<T, S>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> anyhow::Result<Option<T>>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync,
+    {
+        self.type_query_one_opt(statement, params).await
+    }
+
     async fn type_query_raw<T, S>(
         &self,
         statement: &S,
@@ -256,6 +845,99 @@ impl ClientExt for Client {
             _t: PhantomData,
         })
     }
+
+    async fn type_query_retry<T, S>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+        retries: usize,
+        backoff: Duration,
+    ) -> anyhow::Result<Vec<T>>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync,
+    {
+        retry(retries, backoff, || self.type_query::<T, S>(statement, params)).await
+    }
+
+    async fn type_query_map<K, T, S, F>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+        mut key: F,
+    ) -> anyhow::Result<HashMap<K, T>>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync,
+        K: Eq + Hash + Send,
+        F: FnMut(&T) -> K + Send,
+    {
+        let stream = self.type_query_raw::<T, S>(statement, params).await?;
+
+        futures::pin_mut!(stream);
+
+        let mut map = HashMap::with_capacity(stream.size_hint().0);
+
+        while let Some(item) = stream.try_next().await? {
+            map.insert(key(&item), item);
+        }
+
+        Ok(map)
+    }
+
+    async fn type_query_page<T>(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<T>>
+    where
+        T: FromRow + Send + Sync,
+    {
+        let statement = format!(
+            "{} LIMIT ${} OFFSET ${}",
+            statement.trim_end().trim_end_matches(';'),
+            params.len() + 1,
+            params.len() + 2,
+        );
+
+        let mut bound = params.to_vec();
+
+        bound.push(&limit);
+        bound.push(&offset);
+
+        self.type_query::<T, str>(statement.as_str(), &bound).await
+    }
+
+    async fn type_for_each<T, S, F, Fut>(
+        &self,
+        statement: &S,
+        params: &[&(dyn ToSql + Sync)],
+        mut f: F,
+    ) -> anyhow::Result<()>
+    where
+        S: ?Sized + ToStatement + Send + Sync,
+        T: FromRow + Send + Sync,
+        F: FnMut(T) -> Fut + Send,
+        Fut: Future<Output = anyhow::Result<()>> + Send,
+    {
+        let stream = self.type_query_raw::<T, S>(statement, params).await?;
+
+        futures::pin_mut!(stream);
+
+        while let Some(item) = stream.try_next().await? {
+            f(item).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn listen(&self, channel: &str) -> anyhow::Result<()> {
+        self.batch_execute(format!("LISTEN \"{}\"", channel.replace('"', "\"\"")).as_str())
+            .await
+            .with_context(|| format!("Failed to listen to channel `{}`", channel))
+    }
 }
 
 pin_project_lite::pin_project! {
@@ -293,3 +975,215 @@ where
         self.stream.size_hint()
     }
 }
+
+pin_project_lite::pin_project! {
+    /// A stream of the typed notifications received on a connection, returned by [`notifications`].
+    pub struct NotificationStream<M, N> {
+        #[pin]
+        messages: M,
+        _n: PhantomData<N>,
+    }
+}
+
+impl<M, N> Stream for NotificationStream<M, N>
+where
+    M: Stream<Item = Result<AsyncMessage, Error>>,
+    N: FromNotification,
+{
+    type Item = anyhow::Result<TypedNotification<N>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            match futures::ready!(this.messages.as_mut().poll_next(cx)) {
+                Some(Ok(AsyncMessage::Notification(notification))) => {
+                    return Poll::Ready(Some(TypedNotification::from_notification(&notification)))
+                }
+                // Notices are only informational
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_round_trip() {
+        let cases = [
+            ("0", &[0, 0, 0, 0][..]),
+            ("12.3400", &[2, 0, 0, 4, 12, 3400]),
+            ("-0.0005", &[1, 0xFFFF, NUMERIC_NEGATIVE, 4, 5]),
+            ("10000", &[1, 1, 0, 0, 1]),
+            ("123456789.5", &[4, 2, 0, 1, 1, 2345, 6789, 5000]),
+        ];
+
+        for (text, words) in cases.iter() {
+            let mut out = types::private::BytesMut::new();
+
+            numeric_from_str(text, &mut out);
+
+            let encoded = out
+                .chunks(2)
+                .map(|word| u16::from_be_bytes([word[0], word[1]]))
+                .collect::<Vec<_>>();
+
+            assert_eq!(*words, &encoded[..], "{}", text);
+            assert_eq!(*text, numeric_to_string(&out).unwrap());
+        }
+    }
+
+    #[test]
+    fn interval_round_trip() {
+        use types::{FromSql as _, ToSql as _};
+
+        let cases = [
+            crate::Interval::new(Duration::from_micros(90_061_000_001)),
+            crate::Interval::new_negative(Duration::from_secs(86_400)),
+            crate::Interval::default(),
+        ];
+
+        for interval in cases.iter() {
+            let mut out = types::private::BytesMut::new();
+
+            interval.to_sql(&types::Type::INTERVAL, &mut out).unwrap();
+
+            assert_eq!(*interval, crate::Interval::from_sql(&types::Type::INTERVAL, &out).unwrap());
+        }
+
+        // `-1 day 01:00:00` as PostgreSQL sends it, the days and the time have their own signs
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&3_600_000_000i64.to_be_bytes());
+        raw.extend_from_slice(&(-1i32).to_be_bytes());
+        raw.extend_from_slice(&0i32.to_be_bytes());
+
+        assert_eq!(
+            crate::Interval::new_negative(Duration::from_secs(23 * 3600)),
+            crate::Interval::from_sql(&types::Type::INTERVAL, &raw).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_until_success() {
+        let mut attempts = 0;
+
+        let value = retry(3, Duration::from_millis(1), || {
+            attempts += 1;
+
+            let attempt = attempts;
+
+            async move {
+                if attempt <= 2 {
+                    Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset").into())
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await
+        .expect("Unable to retry");
+
+        assert_eq!(3, value);
+    }
+
+    #[tokio::test]
+    async fn retry_gives_up() {
+        let mut attempts = 0;
+
+        let res: anyhow::Result<()> = retry(2, Duration::from_millis(1), || {
+            attempts += 1;
+
+            async { Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset").into()) }
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert_eq!(3, attempts);
+    }
+
+    #[tokio::test]
+    async fn retry_skips_permanent_errors() {
+        let mut attempts = 0;
+
+        let res: anyhow::Result<()> = retry(2, Duration::from_millis(1), || {
+            attempts += 1;
+
+            async { Err(anyhow::anyhow!("invalid column type")) }
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert_eq!(1, attempts);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(Duration::from_secs(2), next_backoff(Duration::from_secs(1)));
+        assert_eq!(MAX_BACKOFF, next_backoff(Duration::from_secs(50)));
+        assert_eq!(MAX_BACKOFF, next_backoff(Duration::new(u64::MAX, 0)));
+    }
+
+    #[test]
+    fn string_payload() {
+        assert_eq!("created 1", String::from_notification("created 1").unwrap());
+    }
+
+    #[cfg(feature = "with-serde-json")]
+    #[test]
+    fn json_payload() {
+        let payload: types::Json<HashMap<String, i64>> =
+            FromNotification::from_notification(r#"{"id": 1}"#).expect("Unable to parse payload");
+
+        assert_eq!(Some(&1), payload.0.get("id"));
+    }
+
+    #[cfg(feature = "with-serde-json")]
+    #[test]
+    fn invalid_json_payload() {
+        let err = <types::Json<HashMap<String, i64>>>::from_notification("created 1")
+            .expect_err("An invalid payload was parsed");
+
+        assert_eq!("Failed to deserialize the notification payload", err.to_string());
+    }
+
+    /// Needs a database, run with `DATABASE_URL` set and `cargo test -- --ignored`.
+    #[cfg(feature = "with-serde-json")]
+    #[ignore]
+    #[tokio::test]
+    async fn listen_notify() {
+        use futures::StreamExt;
+
+        let url = std::env::var("DATABASE_URL").expect("`DATABASE_URL` is not set");
+
+        let (client, mut connection) = connect(&url, NoTls).await.expect("Unable to connect");
+
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let messages = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+
+        tokio::spawn(messages.map(Ok).forward(sender));
+
+        let events = notifications::<_, types::Json<HashMap<String, i64>>>(receiver);
+
+        futures::pin_mut!(events);
+
+        client.listen("rewryte_events").await.expect("Unable to listen");
+        client
+            .batch_execute(r#"NOTIFY rewryte_events, '{"id": 1}'"#)
+            .await
+            .expect("Unable to notify");
+
+        let event = events
+            .try_next()
+            .await
+            .expect("Unable to receive notification")
+            .expect("The connection closed");
+
+        assert_eq!("rewryte_events", event.channel);
+        assert_eq!(Some(&1), event.payload.0.get("id"));
+    }
+}
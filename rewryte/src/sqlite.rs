@@ -1,6 +1,9 @@
 pub use rusqlite::*;
 
-use {std::marker::PhantomData, anyhow::Context as _};
+use {
+    std::{collections::HashMap, convert::TryFrom, hash::Hash, marker::PhantomData, path::Path},
+    anyhow::Context as _,
+};
 
 #[macro_export]
 macro_rules! sqlite_named_params {
@@ -22,12 +25,253 @@ macro_rules! sqlite_params {
         };
     }
 
+/// The extension traits, row conversions, and parameter macros, for a single glob import.
+///
+/// ```
+/// use rewryte::sqlite::prelude::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let conn = rewryte::sqlite::Connection::open_in_memory()?;
+///
+/// let answer: i64 = conn.query_scalar("SELECT ?1 + 1", sqlite_params![41])?;
+///
+/// assert_eq!(42, answer);
+/// # Ok(())
+/// # }
+/// ```
+pub mod prelude {
+    pub use {
+        super::{
+            ConnectionExt, Find, FromRow, FromRowPrefixed, Search, StatementExt, ToParams, TryFromRow,
+            Versioned,
+        },
+        crate::{sqlite_named_params, sqlite_params},
+    };
+}
+
+/// Converts a row into a type.
+///
+/// To return an error type of your own implement [`TryFromRow`] instead, which implements this trait.
 pub trait FromRow {
     fn from_row(row: &Row<'_>) -> anyhow::Result<Self>
     where
         Self: Sized;
 }
 
+/// Converts a row into a type, with an error that converts into the [`anyhow::Error`] the query helpers return.
+pub trait TryFromRow {
+    type Error: Into<anyhow::Error>;
+
+    fn try_from_row(row: &Row<'_>) -> Result<Self, Self::Error>
+    where
+        Self: Sized;
+}
+
+impl<T> FromRow for T
+where
+    T: TryFromRow,
+{
+    fn from_row(row: &Row<'_>) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        T::try_from_row(row).map_err(Into::into)
+    }
+}
+
+/// Converts the columns named `{prefix}_{column}` of a row into a type.
+///
+/// Lets a join be read into one struct per table, by aliasing each table's columns with its own prefix.
+pub trait FromRowPrefixed {
+    fn from_row_prefixed(row: &Row<'_>, prefix: &str) -> anyhow::Result<Self>
+    where
+        Self: Sized;
+}
+
+/// Binds a whole struct as statement parameters, in the order of the table's columns.
+///
+/// The parameters are boxed as SQLite has no 32-bit floats, so those fields are widened into new values.
+pub trait ToParams {
+    fn to_params(&self) -> Vec<Box<dyn ToSql + '_>>;
+}
+
+/// Finds the rows whose `[searchable]` columns contain a query, generated by the `search` option.
+///
+/// The query is matched with `LIKE`, so it is case insensitive for ASCII, `%` and `_` in it are matched literally.
+///
+/// ```
+/// use rewryte::sqlite::prelude::*;
+///
+/// struct Post {
+///     title: String,
+/// }
+///
+/// impl FromRow for Post {
+///     fn from_row(row: &rewryte::sqlite::Row<'_>) -> anyhow::Result<Self> {
+///         Ok(Self { title: row.get(0)? })
+///     }
+/// }
+///
+/// impl Search for Post {
+///     const SEARCH: &'static str = "SELECT Title FROM Post WHERE Title LIKE '%' || ?1 || '%' ESCAPE '\\'";
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let conn = rewryte::sqlite::Connection::open_in_memory()?;
+///
+/// conn.execute_batch("
+///     CREATE TABLE Post (Title TEXT NOT NULL);
+///     INSERT INTO Post (Title) VALUES ('Hello World'), ('Goodbye'), ('100% done');
+/// ")?;
+///
+/// let found = Post::search(&conn, "world")?;
+/// assert_eq!(vec!["Hello World"], found.iter().map(|post| post.title.as_str()).collect::<Vec<_>>());
+///
+/// let found = Post::search(&conn, "0%")?;
+/// assert_eq!(vec!["100% done"], found.iter().map(|post| post.title.as_str()).collect::<Vec<_>>());
+///
+/// assert!(Post::search(&conn, "_")?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub trait Search: FromRow {
+    /// Selects the table's columns where any searchable column is `LIKE` the pattern in `?1`, escaped with `\`.
+    const SEARCH: &'static str;
+
+    fn search(conn: &Connection, query: &str) -> anyhow::Result<Vec<Self>>
+    where
+        Self: Sized,
+    {
+        let query = query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+
+        let mut stmt = conn.prepare(Self::SEARCH)?;
+
+        let rows = stmt.type_query(&[query])?.collect_vec()?;
+
+        Ok(rows)
+    }
+}
+
+/// Finds a row by its primary key, generated for every table that has one.
+///
+/// ```
+/// use rewryte::sqlite::{prelude::*, types::ToSql};
+///
+/// struct Chapter {
+///     story: String,
+///     place: i64,
+///     name: String,
+/// }
+///
+/// impl FromRow for Chapter {
+///     fn from_row(row: &rewryte::sqlite::Row<'_>) -> anyhow::Result<Self> {
+///         Ok(Self { story: row.get(0)?, place: row.get(1)?, name: row.get(2)? })
+///     }
+/// }
+///
+/// impl Find for Chapter {
+///     type Key = (String, i64);
+///
+///     const FIND: &'static str = "SELECT Story, Place, Name FROM Chapter WHERE Story = ?1 AND Place = ?2";
+///
+///     fn key_params(key: &Self::Key) -> Vec<&dyn ToSql> {
+///         vec![&key.0, &key.1]
+///     }
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let conn = rewryte::sqlite::Connection::open_in_memory()?;
+///
+/// conn.execute_batch("
+///     CREATE TABLE Chapter (Story TEXT NOT NULL, Place INTEGER NOT NULL, Name TEXT NOT NULL);
+///     INSERT INTO Chapter (Story, Place, Name) VALUES ('a', 1, 'First'), ('a', 2, 'Second');
+/// ")?;
+///
+/// let found = Chapter::find(&conn, ("a".to_string(), 2))?.expect("the chapter exists");
+/// assert_eq!(("a", 2, "Second"), (found.story.as_str(), found.place, found.name.as_str()));
+///
+/// assert!(Chapter::find(&conn, ("b".to_string(), 1))?.is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub trait Find: FromRow {
+    /// The primary key, a tuple of the key columns' types if there is more than one.
+    type Key;
+
+    /// Selects the table's columns where the primary key columns equal `?1`, `?2`, and so on.
+    const FIND: &'static str;
+
+    /// Binds the primary key in the order of the parameters in [`FIND`](Find::FIND).
+    fn key_params(key: &Self::Key) -> Vec<&dyn ToSql>;
+
+    fn find(conn: &Connection, key: Self::Key) -> anyhow::Result<Option<Self>>
+    where
+        Self: Sized,
+    {
+        conn.type_query_one_opt(Self::FIND, Self::key_params(&key))
+    }
+}
+
+/// Updates a row only if its `[version]` column still holds the version it was read with, for optimistic concurrency.
+///
+/// Generated for tables with a primary key and a version column. The update bumps the version, integers are
+/// incremented and dates set to the current time, so the struct is stale afterwards and has to be fetched again.
+///
+/// ```
+/// use rewryte::sqlite::{prelude::*, types::ToSql};
+///
+/// struct Setting {
+///     key: String,
+///     value: String,
+///     version: i64,
+/// }
+///
+/// impl ToParams for Setting {
+///     fn to_params(&self) -> Vec<Box<dyn ToSql + '_>> {
+///         vec![Box::new(&self.key), Box::new(&self.value), Box::new(&self.version)]
+///     }
+/// }
+///
+/// impl Versioned for Setting {
+///     const UPDATE_IF_UNCHANGED: &'static str =
+///         "UPDATE Setting SET Value = ?2, Version = Version + 1 WHERE Key = ?1 AND Version = ?3";
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let conn = rewryte::sqlite::Connection::open_in_memory()?;
+///
+/// conn.execute_batch("
+///     CREATE TABLE Setting (Key TEXT PRIMARY KEY, Value TEXT NOT NULL, Version INTEGER NOT NULL);
+///     INSERT INTO Setting (Key, Value, Version) VALUES ('theme', 'light', 1);
+/// ")?;
+///
+/// let first = Setting { key: "theme".into(), value: "dark".into(), version: 1 };
+/// let second = Setting { key: "theme".into(), value: "blue".into(), version: 1 };
+///
+/// assert!(first.update_if_unchanged(&conn)?);
+/// assert!(!second.update_if_unchanged(&conn)?);
+///
+/// let value: String = conn.query_scalar("SELECT Value FROM Setting", rewryte::sqlite::NO_PARAMS)?;
+/// assert_eq!("dark", value);
+/// # Ok(())
+/// # }
+/// ```
+pub trait Versioned: ToParams {
+    /// Sets the table's columns from [`to_params`](ToParams::to_params) and bumps the version, where the primary key
+    /// and version columns still equal the struct's.
+    const UPDATE_IF_UNCHANGED: &'static str;
+
+    /// Returns `false` without updating anything if the row was changed or deleted since it was read.
+    fn update_if_unchanged(&self, conn: &Connection) -> anyhow::Result<bool> {
+        let updated = conn.execute(Self::UPDATE_IF_UNCHANGED, self.to_params())?;
+
+        Ok(updated != 0)
+    }
+}
+
 macro_rules! impl_from_row {
     ($( $from:ty, )*) => {
         $(
@@ -49,8 +293,58 @@ impl_from_row![
     u8, u16, u32, i8, i16, i32, i64, isize,
     f64,
     String, Vec<u8>,
+    crate::Interval,
+    crate::Money,
 ];
 
+impl types::ToSql for crate::Interval {
+    fn to_sql(&self) -> Result<types::ToSqlOutput<'_>> {
+        let nanos = i64::try_from(self.duration.as_nanos())
+            .map_err(|err| Error::ToSqlConversionFailure(Box::new(err)))?;
+
+        Ok(types::ToSqlOutput::from(if self.negative { -nanos } else { nanos }))
+    }
+}
+
+impl types::FromSql for crate::Interval {
+    fn column_result(value: types::ValueRef<'_>) -> types::FromSqlResult<Self> {
+        let nanos = value.as_i64()?;
+
+        // `wrapping_abs` leaves `i64::MIN` as is, which is still its length once it is unsigned
+        Ok(crate::Interval {
+            duration: std::time::Duration::from_nanos(nanos.wrapping_abs() as u64),
+            negative: nanos < 0,
+        })
+    }
+}
+
+impl types::ToSql for crate::Money {
+    #[cfg(feature = "with-decimal")]
+    fn to_sql(&self) -> Result<types::ToSqlOutput<'_>> {
+        Ok(types::ToSqlOutput::from(self.0.to_string()))
+    }
+
+    #[cfg(not(feature = "with-decimal"))]
+    fn to_sql(&self) -> Result<types::ToSqlOutput<'_>> {
+        Ok(types::ToSqlOutput::from(self.0))
+    }
+}
+
+impl types::FromSql for crate::Money {
+    fn column_result(value: types::ValueRef<'_>) -> types::FromSqlResult<Self> {
+        // `DECIMAL` columns turn text into numbers when they fit, so any of the three can be read
+        let text = match value {
+            types::ValueRef::Integer(amount) => amount.to_string(),
+            types::ValueRef::Real(amount) => amount.to_string(),
+            types::ValueRef::Text(_) => value.as_str()?.to_string(),
+            _ => return Err(types::FromSqlError::InvalidType),
+        };
+
+        text.parse()
+            .map_err(|err| types::FromSqlError::Other(Box::new(err)))
+    }
+}
+
 #[cfg(feature = "with-chrono")]
 impl_from_row![
     chrono::NaiveDate,
@@ -106,6 +400,11 @@ where
     pub(crate) fn new(rows: Rows<'stmt>, f: F) -> Self {
         Self { rows, map: f }
     }
+
+    /// Collects every mapped row, stopping at the first error.
+    pub fn collect_vec(self) -> anyhow::Result<Vec<T>> {
+        self.collect()
+    }
 }
 
 impl<T, F> Iterator for MappedRowsExt<'_, F>
@@ -143,6 +442,11 @@ where
             typ: PhantomData::default(),
         }
     }
+
+    /// Collects every mapped row, stopping at the first error.
+    pub fn collect_vec(self) -> anyhow::Result<Vec<T>> {
+        self.collect()
+    }
 }
 
 impl<T> Iterator for TypeMappedRowsExt<'_, T>
@@ -156,15 +460,68 @@ where
             .next()
             .map_err(anyhow::Error::from)
             .transpose()
-            .map(|row_result| {
-                row_result
-                    .and_then(|row| T::from_row(&row))
-                    .map_err(anyhow::Error::from)
-            })
+            .map(|row_result| row_result.and_then(|row| T::from_row(&row)))
+    }
+}
+
+/// Opens or creates a database, applying a generated schema the first time it is opened.
+///
+/// Foreign keys are enforced and the database is switched to WAL. Whether the schema has been applied is kept in
+/// `PRAGMA user_version`, which is set to `1` in the same transaction as the schema. Changes to the schema after that
+/// are not applied, they need their own migrations.
+///
+/// The schema can not start its own transaction, as SQLite does not nest them, so it has to be generated without
+/// `wrap_in_transaction`. A schema that does is an error, before the database is opened.
+pub fn open_with_schema(path: impl AsRef<Path>, ddl: &str) -> anyhow::Result<Connection> {
+    let path = path.as_ref();
+
+    if begins_transaction(ddl) {
+        anyhow::bail!("The schema starts its own transaction, generate it without `wrap_in_transaction`");
+    }
+
+    let mut conn = Connection::open(path)
+        .with_context(|| format!("Failed to open database: {}", path.display()))?;
+
+    // Setting the journal mode returns the new mode as a row, so it can not go through `execute`
+    conn.query_row("PRAGMA journal_mode = WAL", NO_PARAMS, |row| row.get::<_, String>(0))
+        .context("Failed to enable WAL")?;
+
+    conn.execute_batch("PRAGMA foreign_keys = ON;")
+        .context("Failed to enable foreign keys")?;
+
+    let tx = conn.transaction()?;
+
+    let version: i64 = tx.query_row("PRAGMA user_version", NO_PARAMS, |row| row.get(0))
+        .context("Failed to get the schema version")?;
+
+    if version == 0 {
+        tx.execute_batch(ddl)
+            .context("Failed to apply schema")?;
+
+        tx.execute_batch("PRAGMA user_version = 1;")
+            .context("Failed to set the schema version")?;
     }
+
+    tx.commit()?;
+
+    Ok(conn)
+}
+
+/// Checks if a schema starts with a `BEGIN`, skipping the comments before its first statement.
+fn begins_transaction(ddl: &str) -> bool {
+    let mut rest = ddl.trim_start();
+
+    while rest.starts_with("--") {
+        rest = rest.find('\n').map_or("", |end| &rest[end..]).trim_start();
+    }
+
+    matches!(rest.get(..5), Some(begin) if begin.eq_ignore_ascii_case("BEGIN"))
 }
 
 pub trait ConnectionExt {
+    /// Applies a generated schema, turning on foreign key enforcement first so the schema's references are checked.
+    fn apply_schema(&self, ddl: &str) -> anyhow::Result<()>;
+
     fn query_one<T, P, F>(&self, sql: &str, params: P, f: F) -> anyhow::Result<T>
     where
         P: IntoIterator,
@@ -188,9 +545,55 @@ pub trait ConnectionExt {
         P: IntoIterator,
         P::Item: ToSql,
         T: FromRow;
+
+    /// Gets the single value of a single row query, such as a `COUNT(*)`.
+    ///
+    /// The same as [`type_query_one`](ConnectionExt::type_query_one), named for scalars so call sites read better.
+    fn query_scalar<T, P>(&self, sql: &str, params: P) -> anyhow::Result<T>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow;
+
+    /// Gets the single value of a query that returns at most one row, `None` if there is no row.
+    fn query_scalar_opt<T, P>(&self, sql: &str, params: P) -> anyhow::Result<Option<T>>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow;
+
+    /// Collects the mapped rows into a map, using `key` to get each row's key.
+    ///
+    /// If two rows have the same key the later row replaces the earlier one.
+    fn type_query_map<K, T, P, F>(&self, sql: &str, params: P, key: F) -> anyhow::Result<HashMap<K, T>>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow,
+        K: Eq + Hash,
+        F: FnMut(&T) -> K;
+
+    /// Gets a page of mapped rows, `LIMIT` and `OFFSET` are appended to `sql` and bound after `params`.
+    ///
+    /// The query should have an `ORDER BY`, otherwise the database is free to return rows in any order.
+    fn type_query_page<T, P>(&self, sql: &str, params: P, limit: i64, offset: i64) -> anyhow::Result<Vec<T>>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow;
 }
 
 impl ConnectionExt for rusqlite::Connection {
+    fn apply_schema(&self, ddl: &str) -> anyhow::Result<()> {
+        self.execute_batch("PRAGMA foreign_keys = ON;")
+            .context("Failed to enable foreign keys")?;
+
+        self.execute_batch(ddl)
+            .context("Failed to apply schema")?;
+
+        Ok(())
+    }
+
     fn query_one<T, P, F>(&self, sql: &str, params: P, f: F) -> anyhow::Result<T>
     where
         P: IntoIterator,
@@ -246,6 +649,67 @@ impl ConnectionExt for rusqlite::Connection {
             },
         }
     }
+
+    fn query_scalar<T, P>(&self, sql: &str, params: P) -> anyhow::Result<T>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow,
+    {
+        self.type_query_one(sql, params)
+    }
+
+    fn query_scalar_opt<T, P>(&self, sql: &str, params: P) -> anyhow::Result<Option<T>>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow,
+    {
+        self.type_query_one_opt(sql, params)
+    }
+
+    fn type_query_map<K, T, P, F>(&self, sql: &str, params: P, mut key: F) -> anyhow::Result<HashMap<K, T>>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow,
+        K: Eq + Hash,
+        F: FnMut(&T) -> K,
+    {
+        let mut stmt = self.prepare(sql)?;
+
+        let mut map = HashMap::new();
+
+        for row in stmt.type_query::<T, P>(params)? {
+            let row = row?;
+
+            map.insert(key(&row), row);
+        }
+
+        Ok(map)
+    }
+
+    fn type_query_page<T, P>(&self, sql: &str, params: P, limit: i64, offset: i64) -> anyhow::Result<Vec<T>>
+    where
+        P: IntoIterator,
+        P::Item: ToSql,
+        T: FromRow,
+    {
+        let sql = format!("{} LIMIT ? OFFSET ?", sql.trim_end().trim_end_matches(';'));
+
+        let params = params.into_iter().collect::<Vec<_>>();
+
+        let mut bound = params.iter().map(|param| param as &dyn ToSql).collect::<Vec<_>>();
+
+        bound.push(&limit);
+        bound.push(&offset);
+
+        let mut stmt = self.prepare(&sql)?;
+
+        let rows = stmt.type_query::<T, _>(bound)?.collect_vec()?;
+
+        Ok(rows)
+    }
 }
 
 pub trait StatementExt {
@@ -441,3 +905,219 @@ impl StatementExt for rusqlite::Statement<'_> {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::path::PathBuf};
+
+    const SCHEMA: &str = "CREATE TABLE Setting (Key TEXT PRIMARY KEY, Value TEXT NOT NULL);";
+
+    /// A database path in the temporary directory, removed along with its WAL files when dropped.
+    struct TempDatabase(PathBuf);
+
+    impl TempDatabase {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("rewryte-{}-{}.db", std::process::id(), name));
+
+            let db = TempDatabase(path);
+            db.remove();
+            db
+        }
+
+        fn remove(&self) {
+            for suffix in &["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", self.0.display(), suffix));
+            }
+        }
+    }
+
+    impl Drop for TempDatabase {
+        fn drop(&mut self) {
+            self.remove();
+        }
+    }
+
+    #[test]
+    fn interval_round_trip() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        let cases = [
+            (crate::Interval::new(std::time::Duration::new(90_061, 1)), 90_061_000_000_001),
+            (crate::Interval::new_negative(std::time::Duration::from_secs(86_400)), -86_400_000_000_000),
+            (crate::Interval::default(), 0),
+        ];
+
+        for (interval, nanos) in cases.iter() {
+            let stored: i64 = conn.query_scalar("SELECT ?", &[interval]).unwrap();
+            let read: crate::Interval = conn.query_scalar("SELECT ?", &[interval]).unwrap();
+
+            assert_eq!(*nanos, stored);
+            assert_eq!(*interval, read);
+        }
+    }
+
+    #[test]
+    fn open_with_schema_creates() {
+        let db = TempDatabase::new("creates");
+
+        let conn = open_with_schema(&db.0, SCHEMA).expect("Unable to open database");
+
+        let count: i64 = conn.query_scalar("SELECT COUNT(*) FROM Setting", NO_PARAMS).unwrap();
+        let version: i64 = conn.query_scalar("PRAGMA user_version", NO_PARAMS).unwrap();
+        let journal: String = conn.query_scalar("PRAGMA journal_mode", NO_PARAMS).unwrap();
+        let foreign_keys: i64 = conn.query_scalar("PRAGMA foreign_keys", NO_PARAMS).unwrap();
+
+        assert_eq!(0, count);
+        assert_eq!(1, version);
+        assert_eq!("wal", journal);
+        assert_eq!(1, foreign_keys);
+    }
+
+    #[test]
+    fn open_with_schema_wrapped() {
+        let wrapped = [
+            format!("BEGIN TRANSACTION;\n\n{}\nCOMMIT;\n", SCHEMA),
+            format!("-- === sqlite ===\n\nbegin transaction;\n\n{}\ncommit;\n", SCHEMA),
+        ];
+
+        for ddl in wrapped.iter() {
+            let db = TempDatabase::new("wrapped");
+
+            let err = open_with_schema(&db.0, ddl).expect_err("A wrapped schema was applied");
+
+            assert!(err.to_string().contains("wrap_in_transaction"), "{}", err);
+            assert!(!db.0.exists());
+        }
+
+        assert!(!begins_transaction("-- BEGIN\nCREATE TABLE Beginning (Id TEXT);"));
+    }
+
+    #[test]
+    fn open_with_schema_reopens() {
+        let db = TempDatabase::new("reopens");
+
+        {
+            let conn = open_with_schema(&db.0, SCHEMA).expect("Unable to open database");
+
+            conn.execute("INSERT INTO Setting (Key, Value) VALUES ('theme', 'dark')", NO_PARAMS).unwrap();
+        }
+
+        // Applying the schema again would fail, as the table already exists
+        let conn = open_with_schema(&db.0, SCHEMA).expect("Unable to reopen database");
+
+        let value: String = conn.query_scalar("SELECT Value FROM Setting WHERE Key = 'theme'", NO_PARAMS).unwrap();
+
+        assert_eq!("dark", value);
+    }
+
+    struct Setting {
+        key: String,
+        value: String,
+    }
+
+    impl FromRow for Setting {
+        fn from_row(row: &Row<'_>) -> anyhow::Result<Self> {
+            Ok(Setting { key: row.get(0)?, value: row.get(1)? })
+        }
+    }
+
+    fn settings(rows: &[(&str, &str)]) -> Connection {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA).unwrap();
+
+        for (key, value) in rows {
+            conn.execute("INSERT INTO Setting (Key, Value) VALUES (?, ?)", &[key, value]).unwrap();
+        }
+
+        conn
+    }
+
+    #[test]
+    fn type_query_map_later_row_wins() {
+        let conn = settings(&[("theme", "dark"), ("font", "serif")]);
+
+        let map = conn
+            .type_query_map("SELECT Key, Value FROM Setting", NO_PARAMS, |setting: &Setting| setting.key.clone())
+            .unwrap();
+
+        assert_eq!(2, map.len());
+        assert_eq!("dark", map["theme"].value);
+        assert_eq!("serif", map["font"].value);
+
+        let map = conn
+            .type_query_map(
+                "SELECT 'color', Value FROM Setting ORDER BY Key",
+                NO_PARAMS,
+                |setting: &Setting| setting.key.clone(),
+            )
+            .unwrap();
+
+        assert_eq!(1, map.len());
+        assert_eq!("dark", map["color"].value);
+    }
+
+    #[test]
+    fn collect_vec_stops_at_error() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        let sql = "SELECT column1 FROM (VALUES ('a'), (NULL), ('c'))";
+
+        let mut stmt = conn.prepare(sql).unwrap();
+        let mut calls = 0;
+
+        let mapped = StatementExt::query(&mut stmt, NO_PARAMS, |row| {
+            calls += 1;
+
+            Ok(row.get::<_, String>(0)?)
+        })
+        .unwrap()
+        .collect_vec();
+
+        assert!(mapped.is_err());
+        assert_eq!(2, calls);
+
+        let mut stmt = conn.prepare(sql).unwrap();
+
+        assert!(stmt.type_query::<String, _>(NO_PARAMS).unwrap().collect_vec().is_err());
+
+        let mut stmt = conn.prepare("SELECT column1 FROM (VALUES ('a'), ('b'))").unwrap();
+
+        assert_eq!(vec!["a", "b"], stmt.type_query::<String, _>(NO_PARAMS).unwrap().collect_vec().unwrap());
+    }
+
+    #[test]
+    fn type_query_page_pages() {
+        let conn = settings(&[("a", "1"), ("b", "2"), ("c", "3"), ("d", "4"), ("e", "5")]);
+
+        let page = |offset| {
+            conn.type_query_page::<Setting, _>("SELECT Key, Value FROM Setting WHERE Key != ? ORDER BY Key;", &["c"], 2, offset)
+                .unwrap()
+                .into_iter()
+                .map(|setting| setting.key)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["a", "b"], page(0));
+        assert_eq!(vec!["d", "e"], page(2));
+        assert!(page(4).is_empty());
+    }
+
+    #[test]
+    fn query_scalar() {
+        let conn = settings(&[("theme", "dark"), ("font", "serif")]);
+
+        let count: i64 = conn.query_scalar("SELECT COUNT(*) FROM Setting", NO_PARAMS).unwrap();
+
+        assert_eq!(2, count);
+
+        let value: Option<String> = conn.query_scalar_opt("SELECT Value FROM Setting WHERE Key = ?", &["theme"]).unwrap();
+
+        assert_eq!(Some("dark"), value.as_deref());
+
+        let missing: Option<String> = conn.query_scalar_opt("SELECT Value FROM Setting WHERE Key = ?", &["color"]).unwrap();
+
+        assert_eq!(None, missing);
+        assert!(conn.query_scalar::<String, _>("SELECT Value FROM Setting WHERE Key = ?", &["color"]).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Async versions of the SQLite helpers, each call runs on tokio's blocking thread pool so it does not stall the executor.
+
+use {
+    crate::sqlite::{Connection, ConnectionExt as _, FromRow, StatementExt as _, ToSql},
+    std::sync::{Arc, Mutex},
+};
+
+/// A SQLite connection that can be shared between tasks.
+///
+/// Queries are run one at a time, parameters have to be owned (`rusqlite::types::Value` or `String` for example)
+/// as they are moved to the blocking thread.
+#[derive(Clone)]
+pub struct AsyncConnection {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl AsyncConnection {
+    pub fn new(conn: Connection) -> Self {
+        Self {
+            conn: Arc::new(Mutex::new(conn)),
+        }
+    }
+
+    /// Runs `f` with the connection on the blocking thread pool.
+    pub async fn call<R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        F: FnOnce(&mut Connection) -> anyhow::Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("SQLite connection lock was poisoned"))?;
+
+            f(&mut conn)
+        })
+        .await?
+    }
+
+    pub async fn execute<P>(&self, sql: impl Into<String>, params: P) -> anyhow::Result<usize>
+    where
+        P: IntoIterator + Send + 'static,
+        P::Item: ToSql,
+    {
+        let sql = sql.into();
+
+        self.call(move |conn| Ok(conn.execute(&sql, params)?)).await
+    }
+
+    pub async fn type_query_all<T, P>(&self, sql: impl Into<String>, params: P) -> anyhow::Result<Vec<T>>
+    where
+        P: IntoIterator + Send + 'static,
+        P::Item: ToSql,
+        T: FromRow + Send + 'static,
+    {
+        let sql = sql.into();
+
+        self.call(move |conn| {
+            let mut stmt = conn.prepare(&sql)?;
+
+            let rows = stmt.type_query::<T, P>(params)?.collect_vec()?;
+
+            Ok(rows)
+        })
+        .await
+    }
+
+    pub async fn type_query_one<T, P>(&self, sql: impl Into<String>, params: P) -> anyhow::Result<T>
+    where
+        P: IntoIterator + Send + 'static,
+        P::Item: ToSql,
+        T: FromRow + Send + 'static,
+    {
+        let sql = sql.into();
+
+        self.call(move |conn| conn.type_query_one(&sql, params)).await
+    }
+
+    pub async fn type_query_one_opt<T, P>(&self, sql: impl Into<String>, params: P) -> anyhow::Result<Option<T>>
+    where
+        P: IntoIterator + Send + 'static,
+        P::Item: ToSql,
+        T: FromRow + Send + 'static,
+    {
+        let sql = sql.into();
+
+        self.call(move |conn| conn.type_query_one_opt(&sql, params)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_params() -> Vec<String> {
+        Vec::new()
+    }
+
+    async fn settings() -> AsyncConnection {
+        let conn = AsyncConnection::new(Connection::open_in_memory().expect("Unable to open database"));
+
+        conn.call(|conn| Ok(conn.execute_batch("CREATE TABLE Setting (Key TEXT PRIMARY KEY, Value TEXT NOT NULL);")?))
+            .await
+            .unwrap();
+
+        conn
+    }
+
+    #[tokio::test]
+    async fn queries() {
+        let conn = settings().await;
+
+        let inserted = conn
+            .execute("INSERT INTO Setting (Key, Value) VALUES (?, ?), (?, ?)", vec!["font".to_string(), "serif".into(), "theme".into(), "dark".into()])
+            .await
+            .unwrap();
+
+        assert_eq!(2, inserted);
+
+        let keys: Vec<String> = conn.type_query_all("SELECT Key FROM Setting ORDER BY Key", no_params()).await.unwrap();
+        let theme: String = conn.type_query_one("SELECT Value FROM Setting WHERE Key = ?", vec!["theme".to_string()]).await.unwrap();
+        let missing: Option<String> = conn.type_query_one_opt("SELECT Value FROM Setting WHERE Key = ?", vec!["size".to_string()]).await.unwrap();
+
+        assert_eq!(vec!["font", "theme"], keys);
+        assert_eq!("dark", theme);
+        assert_eq!(None, missing);
+
+        assert!(conn.type_query_one::<String, _>("SELECT Value FROM Settings", no_params()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn shared_between_tasks() {
+        let conn = settings().await;
+
+        let tasks = (0..4)
+            .map(|i| {
+                let conn = conn.clone();
+
+                tokio::spawn(async move {
+                    conn.execute("INSERT INTO Setting (Key, Value) VALUES (?, 'on')", vec![format!("flag-{}", i)]).await
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let count: i64 = conn.type_query_one("SELECT COUNT(*) FROM Setting", no_params()).await.unwrap();
+
+        assert_eq!(4, count);
+    }
+}
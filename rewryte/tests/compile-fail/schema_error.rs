@@ -0,0 +1,5 @@
+//! The schema's first diagnostic has to be in the compile error.
+
+rewryte::models!("./schema_error.dal");
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! Builds and runs each file in `tests/compile` as its own crate, and checks the errors of those in
+//! `tests/compile-fail` against their `.stderr` files.
+
+#![cfg(feature = "sqlite")]
+
+#[test]
+fn compile() {
+    let cases = trybuild::TestCases::new();
+
+    cases.pass("tests/compile/*.rs");
+    cases.compile_fail("tests/compile-fail/*.rs");
+}
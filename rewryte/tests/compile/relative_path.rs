@@ -0,0 +1,25 @@
+//! The schema is found beside this file, trybuild builds it with its own `CARGO_MANIFEST_DIR`.
+
+use rewryte::sqlite::{prelude::*, Connection, NO_PARAMS};
+
+rewryte::models!("./relative_path.dal");
+
+const SCHEMA: &str = rewryte::schema!("sqlite", "./relative_path.dal");
+
+fn main() {
+    let conn = Connection::open_in_memory().expect("Unable to open database");
+
+    conn.execute_batch(SCHEMA)
+        .expect("Unable to create the tables");
+    conn.execute(
+        "INSERT INTO Note (Id, Body) VALUES ('first', 'Hello')",
+        NO_PARAMS,
+    )
+    .expect("Unable to insert note");
+
+    let note: Note = conn
+        .type_query_one("SELECT Id, Body FROM Note", NO_PARAMS)
+        .expect("Unable to read note");
+
+    assert_eq!("Hello", note.body);
+}
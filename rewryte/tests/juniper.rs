@@ -0,0 +1,38 @@
+// The gated derives need a `rewryte-juniper` feature on the crate using them, which this one does not have
+#![cfg(all(feature = "sqlite", not(feature = "feature-gate-juniper")))]
+
+use juniper::{DefaultScalarValue, FromInputValue, GraphQLType, InputValue, ToInputValue};
+
+rewryte::models!("./schemas/juniper.dal", ["juniper", "id_newtypes"]);
+
+#[test]
+fn id_newtype_scalar() {
+    assert_eq!(
+        Some("UserId"),
+        <UserId as GraphQLType<DefaultScalarValue>>::name(&())
+    );
+
+    let input: InputValue = UserId(7).to_input_value();
+
+    assert_eq!(Some(UserId(7)), UserId::from_input_value(&input));
+}
+
+#[test]
+fn objects_hold_newtypes() {
+    assert_eq!(
+        Some("Post"),
+        <Post as GraphQLType<DefaultScalarValue>>::name(&())
+    );
+    assert_eq!(
+        Some("PostId"),
+        <PostId as GraphQLType<DefaultScalarValue>>::name(&())
+    );
+
+    let post = Post {
+        id: PostId("first".into()),
+        author: UserId(7),
+        title: "First".into(),
+    };
+
+    assert_eq!(UserId(7), post.author);
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "sqlite")]
+
+use rewryte::sqlite::{prelude::*, Connection, NO_PARAMS};
+
+mod models_and_schema {
+    rewryte::models_and_schema!("./schemas/story.dal", "sqlite");
+}
+
+#[test]
+fn models_and_schema() {
+    use models_and_schema::{Story, SCHEMA_SQL};
+
+    let conn = Connection::open_in_memory().expect("Unable to open database");
+
+    conn.execute_batch(SCHEMA_SQL)
+        .expect("Unable to create the tables");
+    conn.execute(
+        "INSERT INTO Story (Id, Name) VALUES ('first', 'First')",
+        NO_PARAMS,
+    )
+    .expect("Unable to insert story");
+
+    let story: Story = conn
+        .type_query_one("SELECT Id, Name FROM Story", NO_PARAMS)
+        .expect("Unable to read story");
+
+    assert_eq!("first", story.id);
+    assert_eq!("First", story.name);
+}
+
+mod manifest_relative {
+    // Not beside this file, so it is found from `CARGO_MANIFEST_DIR`
+    pub const SCHEMA: &str = rewryte::schema!("sqlite", "tests/schemas/story.dal");
+}
+
+#[test]
+fn manifest_relative() {
+    assert_eq!(models_and_schema::SCHEMA_SQL, manifest_relative::SCHEMA);
+}
+
+mod mysql {
+    rewryte::models_and_schema!("./schemas/story.dal", "mysql");
+}
+
+#[test]
+fn models_and_schema_mysql() {
+    assert!(mysql::SCHEMA_SQL.starts_with("CREATE TABLE Story ("));
+
+    let _story = mysql::Story {
+        id: String::from("first"),
+        name: String::from("First"),
+    };
+}
@@ -0,0 +1,370 @@
+//! Needs a database, run with `DATABASE_URL` set and `cargo test -- --ignored`.
+
+#![cfg(feature = "postgres")]
+
+use {
+    rewryte::postgres::{prelude::*, Client, NoTls},
+    std::time::Duration,
+};
+
+rewryte::models!("./schemas/postgres.dal");
+
+const SCHEMA: &str = rewryte::schema!("postgresql", "./schemas/postgres.dal");
+
+/// Connects and creates the schema in its own PostgreSQL schema, so tests running at the same time do not share tables.
+async fn database(name: &str) -> Client {
+    let url = std::env::var("DATABASE_URL").expect("`DATABASE_URL` is not set");
+
+    let (client, connection) = rewryte::postgres::connect(&url, NoTls)
+        .await
+        .expect("Unable to connect");
+
+    tokio::spawn(connection);
+
+    client
+        .batch_execute(&format!(
+            "DROP SCHEMA IF EXISTS {name} CASCADE; CREATE SCHEMA {name}; SET search_path TO {name};",
+            name = name
+        ))
+        .await
+        .expect("Unable to create the test schema");
+
+    client
+        .batch_execute(SCHEMA)
+        .await
+        .expect("Unable to create the tables");
+
+    client
+}
+
+#[ignore]
+#[tokio::test]
+async fn enum_round_trip() {
+    let client = database("enum_round_trip").await;
+
+    client
+        .execute("INSERT INTO Person (Mood) VALUES ($1)", &[&Mood::NotSure])
+        .await
+        .expect("Unable to insert an enum");
+
+    let stored: String = client
+        .query_one("SELECT Mood::TEXT FROM Person", &[])
+        .await
+        .expect("Unable to read the enum as text")
+        .get(0);
+
+    assert_eq!("NotSure", stored);
+    assert_eq!(Mood::NotSure.to_string(), stored);
+    assert_eq!(Ok(Mood::NotSure), stored.parse());
+
+    let person: Person = client
+        .type_query_one("SELECT Id, Mood, Balance FROM Person", postgres_params![])
+        .await
+        .expect("Unable to read the enum");
+
+    assert_eq!(Mood::NotSure, person.mood);
+}
+
+#[ignore]
+#[tokio::test]
+async fn money_round_trip() {
+    let client = database("money_round_trip").await;
+
+    let balance: rewryte::Money = "-12345.6789".parse().expect("Unable to parse the amount");
+
+    client
+        .execute(
+            "INSERT INTO Person (Mood, Balance) VALUES ($1, $2)",
+            &[&Mood::Happy, &balance],
+        )
+        .await
+        .expect("Unable to insert an amount");
+
+    let stored: String = client
+        .query_one("SELECT Balance::TEXT FROM Person", &[])
+        .await
+        .expect("Unable to read the amount as text")
+        .get(0);
+
+    assert_eq!("-12345.6789", stored);
+
+    let person: Person = client
+        .type_query_one("SELECT Id, Mood, Balance FROM Person", postgres_params![])
+        .await
+        .expect("Unable to read the amount");
+
+    assert_eq!(Some(balance), person.balance);
+}
+
+#[ignore]
+#[tokio::test]
+async fn interval_round_trip() {
+    let client = database("interval_round_trip").await;
+
+    let interval = rewryte::Interval::new_negative(Duration::from_millis(1_500));
+
+    let row = client
+        .query_one(
+            "SELECT '-1 day 01:00:00'::INTERVAL, $1::INTERVAL, $1::INTERVAL::TEXT",
+            &[&interval],
+        )
+        .await
+        .expect("Unable to read the intervals");
+
+    let day: rewryte::Interval = row.get(0);
+    let read: rewryte::Interval = row.get(1);
+    let text: String = row.get(2);
+
+    assert_eq!(
+        rewryte::Interval::new_negative(Duration::from_secs(23 * 3600)),
+        day
+    );
+    assert_eq!(interval, read);
+    assert_eq!("-00:00:01.5", text);
+}
+
+#[ignore]
+#[tokio::test]
+async fn prefixed_join() {
+    let client = database("prefixed_join").await;
+
+    client
+        .execute(
+            "INSERT INTO Person (Mood) VALUES ($1), ($2)",
+            &[&Mood::Happy, &Mood::NotSure],
+        )
+        .await
+        .expect("Unable to insert the people");
+
+    // The unquoted aliases are lowercased by PostgreSQL
+    let row = client
+        .query_one(
+            "SELECT A.Id AS First_Id, A.Mood AS First_Mood, A.Balance AS First_Balance,
+                B.Id AS SECOND_ID, B.Mood AS SECOND_MOOD, B.Balance AS SECOND_BALANCE
+            FROM Person A JOIN Person B ON B.Id = A.Id + 1",
+            &[],
+        )
+        .await
+        .expect("Unable to run the join");
+
+    let first = Person::from_row_prefixed(&row, "first").expect("Unable to read the first person");
+    let second =
+        Person::from_row_prefixed(&row, "second").expect("Unable to read the second person");
+
+    assert_eq!(Mood::Happy, first.mood);
+    assert_eq!(Mood::NotSure, second.mood);
+}
+
+#[ignore]
+#[tokio::test]
+async fn type_query_map() {
+    let client = database("type_query_map").await;
+
+    client
+        .execute(
+            "INSERT INTO Person (Mood) VALUES ($1), ($2), ($1)",
+            &[&Mood::Happy, &Mood::NotSure],
+        )
+        .await
+        .expect("Unable to insert the people");
+
+    let people = client
+        .type_query_map(
+            "SELECT Id, Mood, Balance FROM Person ORDER BY Id",
+            postgres_params![],
+            |person: &Person| person.mood,
+        )
+        .await
+        .expect("Unable to read the people");
+
+    assert_eq!(2, people.len());
+    assert_eq!(3, people[&Mood::Happy].id);
+    assert_eq!(2, people[&Mood::NotSure].id);
+}
+
+#[ignore]
+#[tokio::test]
+async fn type_query_page() {
+    let client = database("type_query_page").await;
+
+    client
+        .execute(
+            "INSERT INTO Person (Mood) VALUES ($1), ($1), ($2), ($1), ($1)",
+            &[&Mood::Happy, &Mood::NotSure],
+        )
+        .await
+        .expect("Unable to insert the people");
+
+    for (offset, ids) in [(0, vec![1, 2]), (2, vec![4, 5]), (4, vec![])].iter() {
+        let page: Vec<Person> = client
+            .type_query_page(
+                "SELECT Id, Mood, Balance FROM Person WHERE Mood = $1 ORDER BY Id;",
+                postgres_params![Mood::Happy],
+                2,
+                *offset,
+            )
+            .await
+            .expect("Unable to read the page");
+
+        assert_eq!(
+            *ids,
+            page.iter().map(|person| person.id).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[ignore]
+#[tokio::test]
+async fn type_for_each() {
+    let client = database("type_for_each").await;
+
+    client
+        .execute(
+            "INSERT INTO Person (Mood) VALUES ($1), ($1), ($2)",
+            &[&Mood::Happy, &Mood::NotSure],
+        )
+        .await
+        .expect("Unable to insert the people");
+
+    let mut ids = Vec::new();
+
+    client
+        .type_for_each(
+            "SELECT Id, Mood, Balance FROM Person ORDER BY Id",
+            postgres_params![],
+            |person: Person| {
+                ids.push(person.id);
+
+                async { Ok(()) }
+            },
+        )
+        .await
+        .expect("Unable to read the people");
+
+    assert_eq!(vec![1, 2, 3], ids);
+
+    let mut seen = 0;
+
+    let err = client
+        .type_for_each(
+            "SELECT Id, Mood, Balance FROM Person ORDER BY Id",
+            postgres_params![],
+            |person: Person| {
+                seen += 1;
+
+                async move {
+                    if person.mood == Mood::Happy {
+                        Ok(())
+                    } else {
+                        Err(anyhow::anyhow!("person {} is not happy", person.id))
+                    }
+                }
+            },
+        )
+        .await
+        .expect_err("The error from the closure was ignored");
+
+    assert_eq!("person 3 is not happy", err.to_string());
+    assert_eq!(3, seen);
+
+    let mut seen = 0;
+
+    client
+        .type_for_each(
+            "SELECT Id, Mood, Balance FROM Person ORDER BY Id",
+            postgres_params![],
+            |_: Person| {
+                seen += 1;
+
+                async { Err(anyhow::anyhow!("stop")) }
+            },
+        )
+        .await
+        .expect_err("The error from the closure was ignored");
+
+    assert_eq!(1, seen);
+}
+
+#[ignore]
+#[tokio::test]
+async fn query_scalar() {
+    let client = database("query_scalar").await;
+
+    client
+        .execute(
+            "INSERT INTO Person (Mood) VALUES ($1), ($2)",
+            &[&Mood::Happy, &Mood::NotSure],
+        )
+        .await
+        .expect("Unable to insert the people");
+
+    // `Client` has an inherent `query_scalar` that collects every row, which shadows the trait's
+    let count: i64 =
+        ClientExt::query_scalar(&client, "SELECT COUNT(*) FROM Person", postgres_params![])
+            .await
+            .expect("Unable to count the people");
+
+    assert_eq!(2, count);
+
+    let missing: Option<i64> = client
+        .query_scalar_opt(
+            "SELECT Id FROM Person WHERE Id = $1",
+            postgres_params![3i32],
+        )
+        .await
+        .expect("Unable to look up the person");
+
+    assert_eq!(None, missing);
+}
+
+#[ignore]
+#[tokio::test]
+async fn find() {
+    let client = database("find").await;
+
+    client
+        .execute(
+            "INSERT INTO Person (Mood) VALUES ($1), ($2)",
+            &[&Mood::Happy, &Mood::NotSure],
+        )
+        .await
+        .expect("Unable to insert the people");
+
+    let person = Person::find(&client, 2)
+        .await
+        .expect("Unable to find the person")
+        .expect("The person was not found");
+
+    assert_eq!(Mood::NotSure, person.mood);
+    assert!(Person::find(&client, 3)
+        .await
+        .expect("Unable to find the person")
+        .is_none());
+}
+
+mod try_from_row {
+    use std::convert::TryInto;
+
+    rewryte::models!("./schemas/postgres.dal", ["try_from_row"]);
+
+    #[ignore]
+    #[tokio::test]
+    async fn try_into() {
+        let client = super::database("try_from_row").await;
+
+        client
+            .execute("INSERT INTO Person (Mood) VALUES ($1)", &[&Mood::Happy])
+            .await
+            .expect("Unable to insert a person");
+
+        let person: Person = client
+            .query_one("SELECT Id, Mood, Balance FROM Person", &[])
+            .await
+            .expect("Unable to run the query")
+            .try_into()
+            .expect("Unable to read the person");
+
+        assert_eq!(Mood::Happy, person.mood);
+        assert_eq!(None, person.balance);
+    }
+}
@@ -0,0 +1,413 @@
+#![cfg(feature = "sqlite")]
+
+#[cfg(feature = "with-chrono")]
+mod versioned {
+    use rewryte::sqlite::{prelude::*, Connection, NO_PARAMS};
+
+    rewryte::models!("./schemas/versioned.dal");
+
+    const SCHEMA: &str = rewryte::schema!("sqlite", "./schemas/versioned.dal");
+
+    #[test]
+    fn date_time_version() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA)
+            .expect("Unable to create the tables");
+
+        // An old version, so the update can not set it to the same second
+        conn.execute(
+            "INSERT INTO Setting (Key, Value, Updated) VALUES ('theme', 'light', '2020-01-01 00:00:00')",
+            NO_PARAMS,
+        )
+        .expect("Unable to insert setting");
+
+        let read: Setting = conn
+            .type_query_one("SELECT Key, Value, Updated FROM Setting", NO_PARAMS)
+            .expect("Unable to read setting");
+
+        let first = Setting {
+            value: "dark".into(),
+            ..read.clone()
+        };
+        let second = Setting {
+            value: "blue".into(),
+            ..read
+        };
+
+        assert!(first
+            .update_if_unchanged(&conn)
+            .expect("Unable to update setting"));
+        assert!(!second
+            .update_if_unchanged(&conn)
+            .expect("Unable to update setting"));
+
+        let value: String = conn
+            .query_scalar("SELECT Value FROM Setting", NO_PARAMS)
+            .expect("Unable to read value");
+
+        assert_eq!("dark", value);
+    }
+}
+
+mod money {
+    use rewryte::sqlite::{prelude::*, Connection, NO_PARAMS};
+
+    rewryte::models!("./schemas/money.dal");
+
+    const SCHEMA: &str = rewryte::schema!("sqlite", "./schemas/money.dal");
+
+    #[test]
+    fn money_round_trip() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA)
+            .expect("Unable to create the tables");
+
+        let accounts = ["0.1", "-12345.6789", "42"]
+            .iter()
+            .map(|balance| Account {
+                name: balance.to_string(),
+                balance: balance.parse().expect("Unable to parse the amount"),
+            })
+            .collect::<Vec<_>>();
+
+        for account in &accounts {
+            conn.execute(
+                "INSERT INTO Account (Name, Balance) VALUES (?1, ?2)",
+                account.to_params(),
+            )
+            .expect("Unable to insert account");
+        }
+
+        let mut stmt = conn
+            .prepare("SELECT Name, Balance FROM Account ORDER BY rowid")
+            .expect("Unable to prepare query");
+
+        let read: Vec<Account> = stmt
+            .type_query(NO_PARAMS)
+            .and_then(|rows| rows.collect_vec())
+            .expect("Unable to read accounts");
+
+        assert_eq!(accounts, read);
+    }
+}
+
+mod join {
+    use rewryte::sqlite::{prelude::*, Connection, NO_PARAMS};
+
+    rewryte::models!("./schemas/join.dal");
+
+    const SCHEMA: &str = rewryte::schema!("sqlite", "./schemas/join.dal");
+
+    #[test]
+    fn prefixed_tables() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA)
+            .expect("Unable to create the tables");
+        conn.execute_batch(
+            "INSERT INTO Author (Id, DisplayName) VALUES ('tolkien', 'J. R. R. Tolkien');
+            INSERT INTO Book (Id, AuthorId, Title) VALUES ('hobbit', 'tolkien', 'The Hobbit');",
+        )
+        .expect("Unable to insert the book");
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT Book.Id AS book_id, Book.AuthorId AS book_author_id, Book.Title AS book_title,
+                    Author.Id AS AUTHOR_ID, Author.DisplayName AS Author_Display_Name
+                FROM Book JOIN Author ON Author.Id = Book.AuthorId",
+            )
+            .expect("Unable to prepare the join");
+        let mut rows = stmt.query(NO_PARAMS).expect("Unable to run the join");
+        let row = rows
+            .next()
+            .expect("Unable to read the join")
+            .expect("The join is empty");
+
+        let book = Book::from_row_prefixed(row, "book").expect("Unable to read the book");
+        let author = Author::from_row_prefixed(row, "author").expect("Unable to read the author");
+
+        assert_eq!("The Hobbit", book.title);
+        assert_eq!(book.author_id, author.id);
+        assert_eq!("J. R. R. Tolkien", author.display_name);
+    }
+
+    #[test]
+    fn apply_schema() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.apply_schema(SCHEMA)
+            .expect("Unable to apply the schema");
+        conn.execute(
+            "INSERT INTO Author (Id, DisplayName) VALUES ('tolkien', 'J. R. R. Tolkien')",
+            NO_PARAMS,
+        )
+        .expect("Unable to insert the author");
+
+        let name: String = conn
+            .query_scalar("SELECT DisplayName FROM Author", NO_PARAMS)
+            .expect("Unable to read the author");
+
+        assert_eq!("J. R. R. Tolkien", name);
+
+        // The author does not exist, so the foreign key has to be enforced for this to fail
+        assert!(conn
+            .execute(
+                "INSERT INTO Book (Id, AuthorId, Title) VALUES ('dune', 'herbert', 'Dune')",
+                NO_PARAMS,
+            )
+            .is_err());
+    }
+}
+
+/// Floats are not `Eq`, `Ord`, or `Hash`, so this only compiles if those derives are left off.
+mod float {
+    use rewryte::sqlite::{prelude::*, Connection, NO_PARAMS};
+
+    rewryte::models!("./schemas/float.dal");
+
+    const SCHEMA: &str = rewryte::schema!("sqlite", "./schemas/float.dal");
+
+    #[test]
+    fn float_columns() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA)
+            .expect("Unable to create the tables");
+        conn.execute_batch(
+            "INSERT INTO Reading (Id, Celsius, Humidity) VALUES ('kitchen', 21.5, 0.25);
+            INSERT INTO Reading (Id, Celsius, Humidity) VALUES ('garden', 12.5, NULL);",
+        )
+        .expect("Unable to insert the readings");
+
+        let mut stmt = conn
+            .prepare("SELECT Id, Celsius, Humidity FROM Reading ORDER BY Id")
+            .expect("Unable to prepare the query");
+        let readings = stmt
+            .type_query::<Reading, _>(NO_PARAMS)
+            .expect("Unable to read the readings")
+            .collect_vec()
+            .expect("Unable to read the readings");
+
+        assert_eq!(
+            vec![
+                Reading {
+                    id: "garden".into(),
+                    celsius: 12.5,
+                    humidity: None,
+                },
+                Reading {
+                    id: "kitchen".into(),
+                    celsius: 21.5,
+                    humidity: Some(0.25),
+                },
+            ],
+            readings
+        );
+        assert!(readings[0] < readings[1].clone());
+    }
+}
+
+mod try_from_row {
+    use {
+        rewryte::sqlite::{Connection, NO_PARAMS},
+        std::convert::{TryFrom, TryInto},
+    };
+
+    rewryte::models!("./schemas/story.dal", ["try_from_row"]);
+
+    const SCHEMA: &str = rewryte::schema!("sqlite", "./schemas/story.dal");
+
+    #[test]
+    fn try_into() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA)
+            .expect("Unable to create the tables");
+        conn.execute(
+            "INSERT INTO Story (Id, Name) VALUES ('first', 'First')",
+            NO_PARAMS,
+        )
+        .expect("Unable to insert story");
+
+        let story: Story = conn
+            .query_row("SELECT Id, Name FROM Story", NO_PARAMS, |row| {
+                Ok(row.try_into())
+            })
+            .expect("Unable to run the query")
+            .expect("Unable to read story");
+
+        assert_eq!("first", story.id);
+        assert_eq!("First", story.name);
+
+        let err = conn
+            .query_row("SELECT Id FROM Story", NO_PARAMS, |row| {
+                Ok(Story::try_from(row))
+            })
+            .expect("Unable to run the query")
+            .expect_err("A story was read without its name");
+
+        assert!(err.to_string().contains("`name`"), "{}", err);
+    }
+}
+
+mod find {
+    use rewryte::sqlite::{prelude::*, Connection};
+
+    rewryte::models!("./schemas/find.dal");
+
+    const SCHEMA: &str = rewryte::schema!("sqlite", "./schemas/find.dal");
+
+    #[test]
+    fn find() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA)
+            .expect("Unable to create the tables");
+        conn.execute_batch(
+            "INSERT INTO Story (Id, Name) VALUES ('first', 'First'), ('second', 'Second');
+            INSERT INTO Chapter (StoryId, Place, Title) VALUES ('first', 1, 'Opening'), ('first', 2, 'Ending');",
+        )
+        .expect("Unable to insert the stories");
+
+        let story = Story::find(&conn, "second".into())
+            .expect("Unable to find the story")
+            .expect("The story was not found");
+
+        assert_eq!("Second", story.name);
+        assert!(Story::find(&conn, "third".into())
+            .expect("Unable to find the story")
+            .is_none());
+
+        let chapter = Chapter::find(&conn, ("first".into(), 2))
+            .expect("Unable to find the chapter")
+            .expect("The chapter was not found");
+
+        assert_eq!("Ending", chapter.title);
+        assert!(Chapter::find(&conn, ("second".into(), 2))
+            .expect("Unable to find the chapter")
+            .is_none());
+    }
+}
+
+mod enums {
+    use {
+        rewryte::sqlite::{prelude::*, Connection, NO_PARAMS},
+        std::str::FromStr,
+    };
+
+    rewryte::models!("./schemas/enums.dal");
+
+    const SCHEMA: &str = rewryte::schema!("sqlite", "./schemas/enums.dal");
+
+    #[test]
+    fn string_forms() {
+        for mood in [Mood::Happy, Mood::NotSure].iter() {
+            assert_eq!(
+                *mood,
+                Mood::from_str(&mood.to_string()).expect("Unable to parse the mood")
+            );
+        }
+
+        assert_eq!("NotSure", Mood::NotSure.to_string());
+        assert!(Mood::from_str("not-sure").is_err());
+
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        conn.execute_batch(SCHEMA)
+            .expect("Unable to create the tables");
+        conn.execute(
+            "INSERT INTO Person (Name, Mood) VALUES (?, ?)",
+            sqlite_params!["anne", Mood::NotSure],
+        )
+        .expect("Unable to insert the person");
+
+        let stored: String = conn
+            .query_scalar("SELECT Mood FROM Person", NO_PARAMS)
+            .expect("Unable to read the mood");
+
+        assert_eq!("not-sure", stored);
+
+        let person: Person = conn
+            .type_query_one("SELECT Name, Mood FROM Person", NO_PARAMS)
+            .expect("Unable to read the person");
+
+        assert_eq!(Mood::NotSure, person.mood);
+    }
+}
+
+mod custom_error {
+    use rewryte::sqlite::{prelude::*, Connection, Row, NO_PARAMS};
+
+    #[derive(Debug)]
+    enum SettingError {
+        Sqlite(rewryte::sqlite::Error),
+        Empty,
+    }
+
+    impl std::fmt::Display for SettingError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                SettingError::Sqlite(err) => write!(f, "{}", err),
+                SettingError::Empty => write!(f, "the setting is empty"),
+            }
+        }
+    }
+
+    impl std::error::Error for SettingError {}
+
+    struct Setting {
+        value: String,
+    }
+
+    impl TryFromRow for Setting {
+        type Error = SettingError;
+
+        fn try_from_row(row: &Row<'_>) -> Result<Self, SettingError> {
+            let value: String = row.get(0).map_err(SettingError::Sqlite)?;
+
+            if value.is_empty() {
+                return Err(SettingError::Empty);
+            }
+
+            Ok(Setting { value })
+        }
+    }
+
+    /// Written the way `FromRow` was implemented before `TryFromRow` existed.
+    struct Key(String);
+
+    impl FromRow for Key {
+        fn from_row(row: &Row<'_>) -> anyhow::Result<Self> {
+            Ok(Key(row.get(0)?))
+        }
+    }
+
+    #[test]
+    fn try_from_row() {
+        let conn = Connection::open_in_memory().expect("Unable to open database");
+
+        let setting: Setting = conn
+            .type_query_one("SELECT 'dark'", NO_PARAMS)
+            .expect("Unable to read setting");
+
+        assert_eq!("dark", setting.value);
+
+        let err = conn
+            .type_query_one::<Setting, _>("SELECT ''", NO_PARAMS)
+            .err()
+            .expect("An empty setting was read");
+
+        assert!(matches!(
+            err.downcast_ref::<SettingError>(),
+            Some(SettingError::Empty)
+        ));
+
+        let key: Key = conn
+            .type_query_one("SELECT 'theme'", NO_PARAMS)
+            .expect("Unable to read key");
+
+        assert_eq!("theme", key.0);
+    }
+}